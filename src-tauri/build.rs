@@ -1,3 +1,7 @@
+#[path = "gazetteer/embed.rs"]
+mod gazetteer;
+
 fn main() {
+    gazetteer::build();
     tauri_build::build()
 }
@@ -0,0 +1,170 @@
+// 将 GeoNames 数据编译为紧凑的地名索引，由 gazetteer/fetch.sh 编译运行，
+// 生成的 gazetteer/gazetteer.bin 随代码一起提交，构建时由 gazetteer/embed.rs 内置到程序中
+//
+// 输入（位于 gazetteer/data，由 gazetteer/fetch.sh 下载）：
+// - cities*.txt：城市列表（GeoNames 标准制表符格式）
+// - admin1CodesASCII.txt：一级行政区名称
+// - countryInfo.txt：国家名称
+//
+// 输出格式（小端序）：
+// "LGZ1" | u32 字符串数量 | 每个字符串 u16 长度 + UTF-8 字节
+//        | u32 地点数量   | 每个地点 f32 纬度, f32 经度, u32 城市名, u32 行政区名, u32 国家名
+// 名称为字符串表中的下标，u32::MAX 表示缺失。
+
+use std::{
+    collections::HashMap,
+    env, fs, io,
+    path::{Path, PathBuf},
+    process,
+};
+
+/// 索引文件头
+pub const MAGIC: &[u8; 4] = b"LGZ1";
+
+/// 缺失名称的占位下标
+const NONE: u32 = u32::MAX;
+
+/// 入口：`compile <数据目录> <输出文件>`
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let [source_dir, output] = args.as_slice() else {
+        eprintln!("用法: compile <数据目录> <输出文件>");
+        process::exit(2);
+    };
+
+    match compile(Path::new(source_dir), Path::new(output)) {
+        Ok(0) => {
+            eprintln!("未找到城市数据: {}", source_dir);
+            process::exit(1);
+        }
+        Ok(count) => println!("已收录 {} 个地点: {}", count, output),
+        Err(e) => {
+            eprintln!("编译地名索引失败: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+/// 编译地名索引，返回收录的地点数量
+pub fn compile(source_dir: &Path, output: &Path) -> io::Result<usize> {
+    let mut strings = StringTable::default();
+    let mut places: Vec<[u32; 5]> = Vec::new();
+
+    let cities = find_cities_file(source_dir);
+    if let Some(cities) = cities {
+        let countries = read_countries(&source_dir.join("countryInfo.txt"))?;
+        let regions = read_regions(&source_dir.join("admin1CodesASCII.txt"))?;
+
+        for line in fs::read_to_string(&cities)?.lines() {
+            let fields: Vec<&str> = line.split('\t').collect();
+            if fields.len() < 11 {
+                continue;
+            }
+            let (Ok(latitude), Ok(longitude)) =
+                (fields[4].parse::<f32>(), fields[5].parse::<f32>())
+            else {
+                continue;
+            };
+
+            let country_code = fields[8];
+            let region = regions
+                .get(&format!("{}.{}", country_code, fields[10]))
+                .map(|name| strings.intern(name))
+                .unwrap_or(NONE);
+            let country = countries
+                .get(country_code)
+                .map(|name| strings.intern(name))
+                .unwrap_or(NONE);
+
+            places.push([
+                latitude.to_bits(),
+                longitude.to_bits(),
+                strings.intern(fields[1]),
+                region,
+                country,
+            ]);
+        }
+    }
+
+    let mut data = Vec::with_capacity(places.len() * 20 + strings.bytes);
+    data.extend_from_slice(MAGIC);
+    data.extend_from_slice(&(strings.values.len() as u32).to_le_bytes());
+    for value in &strings.values {
+        let bytes = &value.as_bytes()[..value.len().min(u16::MAX as usize)];
+        data.extend_from_slice(&(bytes.len() as u16).to_le_bytes());
+        data.extend_from_slice(bytes);
+    }
+    data.extend_from_slice(&(places.len() as u32).to_le_bytes());
+    for place in &places {
+        for value in place {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+
+    fs::write(output, data)?;
+    Ok(places.len())
+}
+
+/// 查找城市列表文件，存在多个时选择收录最多的（cities500 优先于 cities15000）
+fn find_cities_file(source_dir: &Path) -> Option<PathBuf> {
+    let mut candidates: Vec<(u32, PathBuf)> = fs::read_dir(source_dir)
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter_map(|path| {
+            let name = path.file_name()?.to_str()?;
+            let size = name.strip_prefix("cities")?.strip_suffix(".txt")?;
+            Some((size.parse().ok()?, path))
+        })
+        .collect();
+    candidates.sort();
+    candidates.into_iter().next().map(|(_, path)| path)
+}
+
+/// 读取国家代码到名称的映射，忽略 # 开头的注释行
+fn read_countries(path: &Path) -> io::Result<HashMap<String, String>> {
+    read_table(path, 0, 4)
+}
+
+/// 读取 `国家代码.行政区代码` 到名称的映射
+fn read_regions(path: &Path) -> io::Result<HashMap<String, String>> {
+    read_table(path, 0, 1)
+}
+
+fn read_table(path: &Path, key: usize, value: usize) -> io::Result<HashMap<String, String>> {
+    if !path.is_file() {
+        return Ok(HashMap::new());
+    }
+
+    Ok(fs::read_to_string(path)?
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split('\t').collect();
+            Some((
+                fields.get(key)?.to_string(),
+                fields.get(value)?.to_string(),
+            ))
+        })
+        .collect())
+}
+
+/// 去重的字符串表
+#[derive(Default)]
+struct StringTable {
+    values: Vec<String>,
+    index: HashMap<String, u32>,
+    bytes: usize,
+}
+
+impl StringTable {
+    fn intern(&mut self, value: &str) -> u32 {
+        if let Some(&id) = self.index.get(value) {
+            return id;
+        }
+        let id = self.values.len() as u32;
+        self.values.push(value.to_string());
+        self.index.insert(value.to_string(), id);
+        self.bytes += value.len() + 2;
+        id
+    }
+}
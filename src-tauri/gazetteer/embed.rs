@@ -0,0 +1,57 @@
+// 构建时校验随代码提交的地名索引 gazetteer/gazetteer.bin 并复制到 OUT_DIR，由 build.rs 引入
+//
+// 索引由 gazetteer/fetch.sh 生成，gazetteer/gazetteer.sha256 记录其 SHA-256。
+// 构建不访问网络；索引缺失或校验失败时内置空索引，运行时离线地理编码不可用。
+
+use std::{env, fs, path::Path, path::PathBuf};
+
+use sha2::{Digest, Sha256};
+
+/// 内置到程序中的索引文件名（位于 OUT_DIR）
+pub const OUTPUT_FILE: &str = "gazetteer.bin";
+
+/// 随代码提交的索引及其校验和，相对于 CARGO_MANIFEST_DIR
+const INDEX_FILE: &str = "gazetteer/gazetteer.bin";
+const CHECKSUM_FILE: &str = "gazetteer/gazetteer.sha256";
+
+/// 构建脚本入口：校验并内置地名索引，不可用时给出警告
+pub fn build() {
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR"));
+    let out_dir = PathBuf::from(env::var("OUT_DIR").expect("OUT_DIR"));
+
+    println!("cargo:rerun-if-changed={}", INDEX_FILE);
+    println!("cargo:rerun-if-changed={}", CHECKSUM_FILE);
+    println!("cargo:rerun-if-changed=gazetteer/embed.rs");
+
+    let data = load_index(&manifest_dir).unwrap_or_else(|reason| {
+        println!(
+            "cargo:warning={}，离线地理编码不可用（运行 gazetteer/fetch.sh 生成）",
+            reason
+        );
+        Vec::new()
+    });
+    fs::write(out_dir.join(OUTPUT_FILE), data).expect("写入地名索引失败");
+}
+
+/// 读取索引并核对校验和
+fn load_index(manifest_dir: &Path) -> Result<Vec<u8>, String> {
+    let checksum = fs::read_to_string(manifest_dir.join(CHECKSUM_FILE))
+        .map_err(|_| format!("未找到 {}", CHECKSUM_FILE))?;
+    let expected = checksum
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+
+    let data =
+        fs::read(manifest_dir.join(INDEX_FILE)).map_err(|_| format!("未找到 {}", INDEX_FILE))?;
+    let actual = format!("{:x}", Sha256::digest(&data));
+    if actual != expected {
+        return Err(format!(
+            "{} 与 {} 中的校验和不一致",
+            INDEX_FILE, CHECKSUM_FILE
+        ));
+    }
+
+    Ok(data)
+}
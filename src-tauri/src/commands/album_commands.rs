@@ -0,0 +1,182 @@
+use log::error;
+use tauri::{AppHandle, Emitter};
+
+use crate::database::album_repository::AlbumRepository;
+use crate::models::album::AlbumRecord;
+use crate::models::image::MediaRecord;
+
+/// 相册变更事件
+const ALBUM_UPDATED_EVENT: &str = "album-updated";
+
+/// 获取所有相册和文件夹
+#[tauri::command]
+pub async fn get_albums(app: AppHandle) -> Result<Vec<AlbumRecord>, String> {
+    let repository = AlbumRepository::new(app);
+
+    repository
+        .find_all()
+        .await
+        .map_err(|e| format!("获取相册列表失败: {}", e))
+}
+
+/// 创建相册，`is_folder` 为 true 时创建用于嵌套相册的文件夹
+#[tauri::command]
+pub async fn create_album(
+    app: AppHandle,
+    name: String,
+    description: Option<String>,
+    parent_id: Option<String>,
+    is_folder: Option<bool>,
+) -> Result<AlbumRecord, String> {
+    let repository = AlbumRepository::new(app.clone());
+
+    let album = repository
+        .create(&name, description, parent_id, is_folder.unwrap_or(false))
+        .await
+        .map_err(|e| format!("创建相册失败: {}", e))?;
+
+    let _ = app.emit(ALBUM_UPDATED_EVENT, album.clone());
+    Ok(album)
+}
+
+/// 重命名相册
+#[tauri::command]
+pub async fn rename_album(
+    app: AppHandle,
+    album_id: String,
+    name: String,
+) -> Result<AlbumRecord, String> {
+    let repository = AlbumRepository::new(app.clone());
+
+    let album = repository
+        .rename(&album_id, &name)
+        .await
+        .map_err(|e| format!("重命名相册失败: {}", e))?;
+
+    let _ = app.emit(ALBUM_UPDATED_EVENT, album.clone());
+    Ok(album)
+}
+
+/// 更新相册描述和封面
+#[tauri::command]
+pub async fn update_album_details(
+    app: AppHandle,
+    album_id: String,
+    description: Option<String>,
+    cover_media_id: Option<String>,
+) -> Result<AlbumRecord, String> {
+    let repository = AlbumRepository::new(app.clone());
+
+    let album = repository
+        .update_details(&album_id, description, cover_media_id)
+        .await
+        .map_err(|e| format!("更新相册失败: {}", e))?;
+
+    let _ = app.emit(ALBUM_UPDATED_EVENT, album.clone());
+    Ok(album)
+}
+
+/// 移动相册到指定文件夹的指定位置，`parent_id` 为空时移动到顶层
+#[tauri::command]
+pub async fn move_album(
+    app: AppHandle,
+    album_id: String,
+    parent_id: Option<String>,
+    position: usize,
+) -> Result<AlbumRecord, String> {
+    let repository = AlbumRepository::new(app.clone());
+
+    let album = repository
+        .move_to(&album_id, parent_id, position)
+        .await
+        .map_err(|e| format!("移动相册失败: {}", e))?;
+
+    let _ = app.emit(ALBUM_UPDATED_EVENT, album.clone());
+    Ok(album)
+}
+
+/// 删除相册，相册中的照片不受影响
+#[tauri::command]
+pub async fn delete_album(app: AppHandle, album_id: String) -> Result<(), String> {
+    let repository = AlbumRepository::new(app);
+
+    repository.delete(&album_id).await.map_err(|e| {
+        let error_msg = format!("删除相册失败: {}", e);
+        error!("{}", error_msg);
+        error_msg
+    })
+}
+
+/// 按手动排序获取相册中的媒体
+#[tauri::command]
+pub async fn get_album_media(app: AppHandle, album_id: String) -> Result<Vec<MediaRecord>, String> {
+    let repository = AlbumRepository::new(app);
+
+    repository
+        .find_media(&album_id)
+        .await
+        .map_err(|e| format!("获取相册内容失败: {}", e))
+}
+
+/// 将媒体添加到相册，`position` 为空时追加到末尾
+#[tauri::command]
+pub async fn add_media_to_album(
+    app: AppHandle,
+    album_id: String,
+    media_ids: Vec<String>,
+    position: Option<usize>,
+) -> Result<usize, String> {
+    let repository = AlbumRepository::new(app.clone());
+
+    let added = repository
+        .add_media(&album_id, &media_ids, position)
+        .await
+        .map_err(|e| format!("添加到相册失败: {}", e))?;
+
+    emit_album_updated(&app, &repository, &album_id).await;
+    Ok(added)
+}
+
+/// 从相册中移除媒体
+#[tauri::command]
+pub async fn remove_media_from_album(
+    app: AppHandle,
+    album_id: String,
+    media_ids: Vec<String>,
+) -> Result<usize, String> {
+    let repository = AlbumRepository::new(app.clone());
+
+    let removed = repository
+        .remove_media(&album_id, &media_ids)
+        .await
+        .map_err(|e| format!("从相册移除失败: {}", e))?;
+
+    emit_album_updated(&app, &repository, &album_id).await;
+    Ok(removed)
+}
+
+/// 拖拽排序后保存相册中媒体的新位置
+#[tauri::command]
+pub async fn reorder_album_media(
+    app: AppHandle,
+    album_id: String,
+    media_ids: Vec<String>,
+    target_index: usize,
+) -> Result<(), String> {
+    let repository = AlbumRepository::new(app.clone());
+
+    repository
+        .reorder_media(&album_id, &media_ids, target_index)
+        .await
+        .map_err(|e| format!("保存相册排序失败: {}", e))?;
+
+    emit_album_updated(&app, &repository, &album_id).await;
+    Ok(())
+}
+
+/// 重新读取相册并发送变更事件（数量和封面可能已变化）
+async fn emit_album_updated(app: &AppHandle, repository: &AlbumRepository, album_id: &str) {
+    if let Ok(Some(album)) = repository.find_by_id(album_id).await {
+        let _ = app.emit(ALBUM_UPDATED_EVENT, album);
+    }
+}
@@ -0,0 +1,98 @@
+use std::{thread, time::Duration};
+
+use log::{error, info};
+use tauri::{AppHandle, Emitter};
+
+use crate::database::backup_repository::BackupRepository;
+use crate::models::backup::{BackupInfo, BackupSettings, IntegrityReport};
+
+/// 数据库从备份恢复后发送的事件，前端需重新加载所有数据
+const CATALOG_RESTORED_EVENT: &str = "catalog-restored";
+
+/// 检查是否需要自动备份的间隔，实际备份间隔由设置决定
+const BACKUP_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// 立即备份数据库
+#[tauri::command]
+pub async fn create_backup(app: AppHandle) -> Result<BackupInfo, String> {
+    let repository = BackupRepository::new(app);
+
+    repository
+        .create_backup()
+        .await
+        .map_err(|e| format!("备份数据库失败: {}", e))
+}
+
+/// 获取所有备份，最新的在前
+#[tauri::command]
+pub async fn get_backups(app: AppHandle) -> Result<Vec<BackupInfo>, String> {
+    let repository = BackupRepository::new(app);
+
+    repository
+        .find_backups()
+        .await
+        .map_err(|e| format!("获取备份失败: {}", e))
+}
+
+/// 从备份恢复数据库，恢复前会自动备份当前数据库
+#[tauri::command]
+pub async fn restore_backup(app: AppHandle, file_name: String) -> Result<(), String> {
+    let repository = BackupRepository::new(app.clone());
+
+    repository
+        .restore(&file_name)
+        .await
+        .map_err(|e| format!("恢复备份失败: {}", e))?;
+
+    let _ = app.emit(CATALOG_RESTORED_EVENT, file_name);
+    Ok(())
+}
+
+/// 获取自动备份设置
+#[tauri::command]
+pub async fn get_backup_settings(app: AppHandle) -> Result<BackupSettings, String> {
+    let repository = BackupRepository::new(app);
+
+    repository
+        .settings()
+        .await
+        .map_err(|e| format!("获取备份设置失败: {}", e))
+}
+
+/// 保存自动备份设置，间隔为 0 时不自动备份
+#[tauri::command]
+pub async fn set_backup_settings(app: AppHandle, settings: BackupSettings) -> Result<(), String> {
+    let repository = BackupRepository::new(app);
+
+    repository
+        .set_settings(&settings)
+        .await
+        .map_err(|e| format!("保存备份设置失败: {}", e))
+}
+
+/// 检查媒体库完整性，`repair` 为 true 时同时修复发现的问题
+#[tauri::command]
+pub async fn check_library_integrity(
+    app: AppHandle,
+    repair: Option<bool>,
+) -> Result<IntegrityReport, String> {
+    let repository = BackupRepository::new(app);
+
+    repository
+        .check_integrity(repair.unwrap_or(false))
+        .await
+        .map_err(|e| format!("完整性检查失败: {}", e))
+}
+
+/// 启动后台任务，每隔 `BACKUP_CHECK_INTERVAL` 检查一次，距离上次备份超过设置的间隔时自动备份
+pub fn spawn_backup_schedule(app: AppHandle) {
+    thread::spawn(move || loop {
+        let repository = BackupRepository::new(app.clone());
+        match tauri::async_runtime::block_on(repository.backup_if_due()) {
+            Ok(Some(backup)) => info!("已自动备份数据库: {}", backup.file_name),
+            Ok(None) => {}
+            Err(e) => error!("自动备份数据库失败: {}", e),
+        }
+        thread::sleep(BACKUP_CHECK_INTERVAL);
+    });
+}
@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::database::contact_sheet_repository::ContactSheetRepository;
+use crate::models::contact_sheet::{ContactSheetOptions, ContactSheetSummary};
+
+/// 小样导出进度事件
+const CONTACT_SHEET_PROGRESS_EVENT: &str = "contact-sheet-progress";
+
+/// 小样导出进度
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContactSheetProgressEvent {
+    pub current: usize,
+    pub total: usize,
+}
+
+/// 将选中的照片生成小样（多页 PDF 或 JPEG），用于给客户挑选照片
+#[tauri::command]
+pub async fn export_contact_sheet(
+    app: AppHandle,
+    options: ContactSheetOptions,
+) -> Result<ContactSheetSummary, String> {
+    let repository = ContactSheetRepository::new(app.clone());
+
+    repository
+        .export(&options, |current, total| {
+            let _ = app.emit(
+                CONTACT_SHEET_PROGRESS_EVENT,
+                ContactSheetProgressEvent { current, total },
+            );
+        })
+        .await
+        .map_err(|e| format!("生成小样失败: {}", e))
+}
@@ -1,5 +1,8 @@
 use crate::database::media_repository::MediaRepository;
+use crate::database::trash_repository::TrashRepository;
 use crate::models::image::MediaRecord;
+use crate::models::media_filter::{MediaFilter, MediaSort};
+use crate::models::trash::TrashFileAction;
 use log::{error, info};
 use tauri::{AppHandle, Emitter};
 
@@ -25,13 +28,17 @@ pub struct ImagesDeleteProgressEvent {
     pub step: String,
 }
 
-/// 从数据库获取所有媒体记录
+/// 从数据库获取媒体记录，可按评分、收藏、颜色标签和挑选标记筛选并排序
 #[tauri::command]
-pub async fn get_media_list(app: AppHandle) -> Result<Vec<MediaRecord>, String> {
+pub async fn get_media_list(
+    app: AppHandle,
+    filter: Option<MediaFilter>,
+    sort: Option<MediaSort>,
+) -> Result<Vec<MediaRecord>, String> {
     let repository = MediaRepository::new(app);
 
     let res = repository
-        .find_all()
+        .find_filtered(&filter.unwrap_or_default(), &sort.unwrap_or_default())
         .await
         .map_err(|e| format!("获取媒体列表失败: {}", e));
     res
@@ -66,17 +73,23 @@ pub async fn import_media(_app: AppHandle, _paths: Vec<String>) -> Result<Vec<St
     Ok(vec![])
 }
 
-/// 删除选中的媒体记录
+/// 每移入多少个媒体发送一次删除进度
+const DELETE_PROGRESS_INTERVAL: usize = 50;
+
+/// 将选中的媒体移入回收站，返回移入的数量
+///
+/// `file_action` 指定原文件的处理方式，默认只从媒体库移除；回收站中的媒体可以恢复，保留期过后彻底删除。
 #[tauri::command]
 pub async fn delete_selected_media(
     app: AppHandle,
     media_ids: Vec<String>,
+    file_action: Option<TrashFileAction>,
 ) -> Result<usize, String> {
-    let repository = MediaRepository::new(app.clone());
-    let mut deleted_count = 0;
+    let repository = TrashRepository::new(app.clone());
+    let file_action = file_action.unwrap_or_default();
     let total_count = media_ids.len();
 
-    info!("开始删除 {} 个选中的媒体记录", total_count);
+    info!("开始将 {} 个选中的媒体移入回收站", total_count);
 
     // 发送删除开始事件
     let _ = app.emit(
@@ -89,47 +102,28 @@ pub async fn delete_selected_media(
         },
     );
 
-    // 首先获取所有记录以找到对应的路径
-    let all_records = repository
-        .find_all()
-        .await
-        .map_err(|e| format!("获取媒体列表失败: {}", e))?;
-
-    // 创建ID到路径的映射
-    let mut id_to_path = std::collections::HashMap::new();
-    for record in all_records {
-        id_to_path.insert(record.id.to_string(), record.path);
-    }
-
-    // 删除选中的媒体记录
-    for (index, media_id) in media_ids.iter().enumerate() {
-        if let Some(path) = id_to_path.get(media_id) {
-            match repository.delete_by_path(path).await {
-                Ok(_) => {
-                    deleted_count += 1;
-                    info!("已删除媒体记录: {}", path);
-                }
-                Err(e) => {
-                    error!("删除媒体记录失败 {}: {}", path, e);
-                }
+    // 整个删除作为一次操作记录，撤销时一起恢复
+    let result = repository
+        .trash(&media_ids, file_action, |current| {
+            if current % DELETE_PROGRESS_INTERVAL == 0 && current < total_count {
+                // 发送删除进度
+                let _ = app.emit(
+                    "images-delete-progress",
+                    ImagesDeleteProgressEvent {
+                        current,
+                        total: total_count,
+                        current_file: None,
+                        step: "deleting_selected".to_string(),
+                    },
+                );
             }
-        } else {
-            error!("未找到ID为 {} 的媒体记录", media_id);
-        }
-
-        // 发送删除进度
-        if (index + 1) % 5 == 0 || index + 1 == total_count {
-            let _ = app.emit(
-                "images-delete-progress",
-                ImagesDeleteProgressEvent {
-                    current: index + 1,
-                    total: total_count,
-                    current_file: None,
-                    step: "deleting_selected".to_string(),
-                },
-            );
-        }
-    }
+        })
+        .await;
+    let deleted_count = result.map_err(|e| {
+        let error_msg = format!("移入回收站失败: {}", e);
+        error!("{}", error_msg);
+        error_msg
+    })?;
 
     // 发送删除完成事件
     let _ = app.emit(
@@ -142,16 +136,19 @@ pub async fn delete_selected_media(
         },
     );
 
-    info!("删除完成，共删除了 {} 个媒体记录", deleted_count);
+    info!("删除完成，共将 {} 个媒体移入回收站", deleted_count);
     Ok(deleted_count)
 }
 
-/// 删除所有媒体记录
+/// 将所有媒体移入回收站，与删除选中媒体相同，可在保留期内恢复
 #[tauri::command]
-pub async fn delete_all_media(app: AppHandle) -> Result<String, String> {
-    let repository = MediaRepository::new(app.clone());
+pub async fn delete_all_media(
+    app: AppHandle,
+    file_action: Option<TrashFileAction>,
+) -> Result<String, String> {
+    let repository = TrashRepository::new(app.clone());
 
-    info!("开始删除所有媒体记录");
+    info!("开始将所有媒体移入回收站");
 
     // 发送删除开始事件
     let _ = app.emit(
@@ -164,8 +161,8 @@ pub async fn delete_all_media(app: AppHandle) -> Result<String, String> {
         },
     );
 
-    match repository.clear_all().await {
-        Ok(_) => {
+    match repository.trash_all(file_action.unwrap_or_default()).await {
+        Ok(count) => {
             // 发送删除完成事件
             let _ = app.emit(
                 "images-delete-progress",
@@ -177,8 +174,8 @@ pub async fn delete_all_media(app: AppHandle) -> Result<String, String> {
                 },
             );
 
-            info!("所有媒体记录已删除");
-            Ok("所有媒体记录已成功删除".to_string())
+            info!("所有媒体已移入回收站，共 {} 个", count);
+            Ok(format!("已将 {} 个媒体移入回收站", count))
         }
         Err(e) => {
             let error_msg = format!("删除所有媒体记录失败: {}", e);
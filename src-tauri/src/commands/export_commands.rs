@@ -0,0 +1,112 @@
+use log::error;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::database::export_preset_repository::ExportPresetRepository;
+use crate::database::export_repository::ExportRepository;
+use crate::models::export::{ExportPresetRecord, ExportSettings, ExportSummary};
+
+/// 导出进度事件
+const EXPORT_PROGRESS_EVENT: &str = "export-progress";
+
+/// 导出预设变更事件
+const EXPORT_PRESET_UPDATED_EVENT: &str = "export-preset-updated";
+
+/// 导出进度
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportProgressEvent {
+    pub current: usize,
+    pub total: usize,
+    pub current_file: String,
+}
+
+/// 将媒体导出到目标文件夹，可转换格式、缩放、转换为 sRGB、移除元数据并添加水印和边框
+#[tauri::command]
+pub async fn export_media(
+    app: AppHandle,
+    media_ids: Vec<String>,
+    target_dir: String,
+    settings: ExportSettings,
+) -> Result<ExportSummary, String> {
+    let repository = ExportRepository::new(app.clone());
+
+    repository
+        .export(
+            &media_ids,
+            &target_dir,
+            &settings,
+            |current, total, file| {
+                let _ = app.emit(
+                    EXPORT_PROGRESS_EVENT,
+                    ExportProgressEvent {
+                        current,
+                        total,
+                        current_file: file.to_string(),
+                    },
+                );
+            },
+        )
+        .await
+        .map_err(|e| format!("导出失败: {}", e))
+}
+
+/// 获取所有导出预设
+#[tauri::command]
+pub async fn get_export_presets(app: AppHandle) -> Result<Vec<ExportPresetRecord>, String> {
+    let repository = ExportPresetRepository::new(app);
+
+    repository
+        .find_all()
+        .await
+        .map_err(|e| format!("获取导出预设失败: {}", e))
+}
+
+/// 将导出设置保存为命名预设
+#[tauri::command]
+pub async fn create_export_preset(
+    app: AppHandle,
+    name: String,
+    settings: ExportSettings,
+) -> Result<ExportPresetRecord, String> {
+    let repository = ExportPresetRepository::new(app.clone());
+
+    let preset = repository
+        .create(&name, &settings)
+        .await
+        .map_err(|e| format!("保存导出预设失败: {}", e))?;
+
+    let _ = app.emit(EXPORT_PRESET_UPDATED_EVENT, preset.clone());
+    Ok(preset)
+}
+
+/// 修改导出预设的名称或设置，未提供的字段保持不变
+#[tauri::command]
+pub async fn update_export_preset(
+    app: AppHandle,
+    preset_id: String,
+    name: Option<String>,
+    settings: Option<ExportSettings>,
+) -> Result<ExportPresetRecord, String> {
+    let repository = ExportPresetRepository::new(app.clone());
+
+    let preset = repository
+        .update(&preset_id, name.as_deref(), settings.as_ref())
+        .await
+        .map_err(|e| format!("更新导出预设失败: {}", e))?;
+
+    let _ = app.emit(EXPORT_PRESET_UPDATED_EVENT, preset.clone());
+    Ok(preset)
+}
+
+/// 删除导出预设
+#[tauri::command]
+pub async fn delete_export_preset(app: AppHandle, preset_id: String) -> Result<(), String> {
+    let repository = ExportPresetRepository::new(app);
+
+    repository.delete(&preset_id).await.map_err(|e| {
+        let error_msg = format!("删除导出预设失败: {}", e);
+        error!("{}", error_msg);
+        error_msg
+    })
+}
@@ -0,0 +1,238 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use tauri::{path::BaseDirectory, AppHandle, Emitter, Manager};
+
+use crate::commands::person_commands::PEOPLE_UPDATED_EVENT;
+use crate::database::face_repository::{FaceRepository, PendingFaceMedia};
+use crate::database::person_repository::PersonRepository;
+use crate::models::face::FaceRecord;
+use crate::utils::face_detection::FaceAnalyzer;
+use crate::utils::image_export::load_oriented_image;
+
+/// 单张图片检测完成事件
+const FACES_DETECTED_EVENT: &str = "faces-detected";
+
+/// 人脸检测进度事件名
+const FACE_DETECTION_PROGRESS_EVENT: &str = "face-detection-progress";
+
+/// 默认的人脸检测模型路径（相对应用数据目录）
+const DEFAULT_DETECTOR_MODEL: &str = "models/face_detector.onnx";
+
+/// 默认的人脸特征模型路径（相对应用数据目录）
+const DEFAULT_EMBEDDER_MODEL: &str = "models/face_embedder.onnx";
+
+/// 检测任务是否正在运行，同一时间只允许一个任务
+static FACE_DETECTION_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// 是否已请求取消检测任务
+static FACE_DETECTION_CANCELLED: AtomicBool = AtomicBool::new(false);
+
+/// 离开作用域时（包括任务出错或 panic）清除运行标记
+struct RunningGuard;
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        FACE_DETECTION_RUNNING.store(false, Ordering::SeqCst);
+    }
+}
+
+/// 人脸检测进度事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FaceDetectionProgressEvent {
+    pub current: usize,
+    pub total: usize,
+    pub current_file: Option<String>,
+    pub step: String,
+}
+
+/// 单张图片检测完成事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FacesDetectedEvent {
+    pub media_id: String,
+    pub faces: Vec<FaceRecord>,
+}
+
+/// 在后台对尚未检测的图片执行人脸检测，返回待处理的图片数量
+///
+/// 模型文件未指定时从应用数据目录下的 `models/` 加载；`rescan` 为 true 时清除已有结果并重新检测全部图片。
+#[tauri::command]
+pub async fn start_face_detection(
+    app: AppHandle,
+    detector_path: Option<String>,
+    embedder_path: Option<String>,
+    rescan: Option<bool>,
+) -> Result<usize, String> {
+    let detector_path = resolve_model_path(&app, detector_path, DEFAULT_DETECTOR_MODEL)?;
+    let embedder_path = resolve_model_path(&app, embedder_path, DEFAULT_EMBEDDER_MODEL)?;
+
+    if FACE_DETECTION_RUNNING
+        .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+        .is_err()
+    {
+        return Err("人脸检测任务正在运行".to_string());
+    }
+    let running = RunningGuard;
+    FACE_DETECTION_CANCELLED.store(false, Ordering::SeqCst);
+
+    let prepared = async {
+        let analyzer = FaceAnalyzer::load(&detector_path, &embedder_path)
+            .map_err(|e| format!("加载人脸模型失败: {}", e))?;
+
+        let repository = FaceRepository::new(app.clone());
+        if rescan.unwrap_or(false) {
+            repository
+                .reset_detection()
+                .await
+                .map_err(|e| format!("清除人脸检测结果失败: {}", e))?;
+        }
+        let pending = repository
+            .find_pending_media()
+            .await
+            .map_err(|e| format!("获取待检测图片失败: {}", e))?;
+
+        Ok::<_, String>((analyzer, pending))
+    }
+    .await;
+
+    let (analyzer, pending) = prepared?;
+
+    let total = pending.len();
+    info!("开始人脸检测，共 {} 张图片", total);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let _running = running;
+        run_face_detection(&app, &analyzer, pending);
+    });
+
+    Ok(total)
+}
+
+/// 请求取消正在运行的人脸检测任务，已处理的图片保留检测结果
+#[tauri::command]
+pub fn cancel_face_detection() -> bool {
+    let running = FACE_DETECTION_RUNNING.load(Ordering::SeqCst);
+    if running {
+        FACE_DETECTION_CANCELLED.store(true, Ordering::SeqCst);
+    }
+    running
+}
+
+/// 获取图片中检测到的人脸
+#[tauri::command]
+pub async fn get_media_faces(app: AppHandle, media_id: String) -> Result<Vec<FaceRecord>, String> {
+    let repository = FaceRepository::new(app);
+
+    repository
+        .find_by_media(&media_id)
+        .await
+        .map_err(|e| format!("获取人脸失败: {}", e))
+}
+
+/// 依次检测每张图片并保存结果，无法解码或检测失败的图片保持未检测状态；结束后对新的人脸聚类
+fn run_face_detection(app: &AppHandle, analyzer: &FaceAnalyzer, pending: Vec<PendingFaceMedia>) {
+    let repository = FaceRepository::new(app.clone());
+    let total = pending.len();
+    let mut processed = 0;
+
+    for media in pending {
+        if FACE_DETECTION_CANCELLED.load(Ordering::SeqCst) {
+            info!("人脸检测已取消，已处理 {}/{} 张图片", processed, total);
+            break;
+        }
+
+        let path = Path::new(&media.path);
+        // 检测在按 EXIF 方向旋转后的图片上进行，人脸框与显示的图片一致
+        let faces = load_oriented_image(path).and_then(|image| {
+            analyzer
+                .analyze(&image)
+                .map_err(|e| format!("人脸检测失败: {}", e))
+        });
+
+        match faces {
+            Ok(faces) => {
+                match tauri::async_runtime::block_on(repository.save_faces(&media.id, &faces)) {
+                    Ok(records) => {
+                        if !records.is_empty() {
+                            let _ = app.emit(
+                                FACES_DETECTED_EVENT,
+                                FacesDetectedEvent {
+                                    media_id: media.id.clone(),
+                                    faces: records,
+                                },
+                            );
+                        }
+                    }
+                    Err(e) => error!("保存人脸检测结果失败 {}: {}", media.path, e),
+                }
+            }
+            // 不保存结果，图片保持未检测状态，下次检测时重试
+            Err(e) => error!("{}: {}", media.path, e),
+        }
+
+        processed += 1;
+        let _ = app.emit(
+            FACE_DETECTION_PROGRESS_EVENT,
+            FaceDetectionProgressEvent {
+                current: processed,
+                total,
+                current_file: path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .map(|s| s.to_string()),
+                step: "detecting".to_string(),
+            },
+        );
+    }
+
+    // 新检测到的人脸直接归入已有人物或聚成新的人物
+    if processed > 0 {
+        match tauri::async_runtime::block_on(PersonRepository::new(app.clone()).cluster(false)) {
+            Ok(_) => {
+                let _ = app.emit(PEOPLE_UPDATED_EVENT, ());
+            }
+            Err(e) => error!("人脸聚类失败: {}", e),
+        }
+    }
+
+    let _ = app.emit(
+        FACE_DETECTION_PROGRESS_EVENT,
+        FaceDetectionProgressEvent {
+            current: processed,
+            total,
+            current_file: None,
+            step: if processed < total {
+                "cancelled".to_string()
+            } else {
+                "completed".to_string()
+            },
+        },
+    );
+    info!("人脸检测结束，已处理 {}/{} 张图片", processed, total);
+}
+
+/// 解析模型文件路径，未指定时使用应用数据目录下的默认位置
+fn resolve_model_path(
+    app: &AppHandle,
+    path: Option<String>,
+    default: &str,
+) -> Result<PathBuf, String> {
+    let path = match path {
+        Some(path) => PathBuf::from(path),
+        None => app
+            .path()
+            .resolve(default, BaseDirectory::AppLocalData)
+            .map_err(|e| format!("无法获取模型目录: {}", e))?,
+    };
+
+    if !path.is_file() {
+        return Err(format!("未找到人脸模型文件: {}", path.display()));
+    }
+    Ok(path)
+}
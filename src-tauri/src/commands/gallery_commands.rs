@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::database::gallery_repository::GalleryRepository;
+use crate::models::gallery::{GalleryOptions, GallerySummary};
+
+/// 静态网站导出进度事件
+const GALLERY_EXPORT_PROGRESS_EVENT: &str = "gallery-export-progress";
+
+/// 静态网站导出进度
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GalleryExportProgressEvent {
+    pub current: usize,
+    pub total: usize,
+}
+
+/// 将相册或选中的媒体导出为可直接浏览的静态网站，写入文件夹或 zip 文件
+#[tauri::command]
+pub async fn export_gallery(
+    app: AppHandle,
+    options: GalleryOptions,
+) -> Result<GallerySummary, String> {
+    let repository = GalleryRepository::new(app.clone());
+
+    repository
+        .export(&options, |current, total| {
+            let _ = app.emit(
+                GALLERY_EXPORT_PROGRESS_EVENT,
+                GalleryExportProgressEvent { current, total },
+            );
+        })
+        .await
+        .map_err(|e| format!("导出网站失败: {}", e))
+}
@@ -7,7 +7,6 @@ use std::{
 };
 
 use chrono::Utc;
-use exif::{Reader as ExifReader, Tag};
 use image::image_dimensions;
 use log::info;
 use serde::{Deserialize, Serialize};
@@ -15,8 +14,12 @@ use tauri::{AppHandle, Emitter};
 use uuid::Uuid;
 
 use crate::database::media_repository::MediaRepository;
-use crate::models::image::{MediaKind, MediaRecord};
+use crate::models::image::{MediaKind, MediaRecord, PickFlag};
+use crate::utils::exif_metadata::extract_exif_metadata;
 use crate::utils::image_processor::{generate_thumbnail, generate_thumbnails_batch};
+use crate::utils::motion_photo::{detect_motion_component, LivePhotoIndex};
+use crate::utils::reverse_geocoder::reverse_geocode;
+use crate::utils::xmp_sidecar::read_xmp_marks;
 
 /// 图片处理进度事件
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,10 +32,12 @@ pub struct ImagesDealProgressEvent {
 }
 
 /// 支持的图片扩展名集合
-static SUPPORTED_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "bmp", "tiff", "webp"];
+static SUPPORTED_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "bmp", "tiff", "webp", "heic", "heif",
+];
 
 /// 检查文件是否为支持的图片格式
-fn _is_supported_image(path: &Path) -> bool {
+pub(crate) fn is_supported_image(path: &Path) -> bool {
     if let Some(ext) = path.extension().and_then(|s| s.to_str()) {
         SUPPORTED_EXTENSIONS.contains(&ext.to_lowercase().as_str())
     } else {
@@ -255,10 +260,12 @@ fn process_images_parallel_with_progress(
     let chunk_size = (paths.len() / 4).max(1);
     let results = Arc::new(Mutex::new(Vec::with_capacity(paths.len())));
     let processed_count = Arc::new(Mutex::new(0usize));
+    let live_photos = Arc::new(LivePhotoIndex::build(paths));
     let mut handles = Vec::new();
 
     for (i, chunk) in paths.chunks(chunk_size).enumerate() {
         let results = results.clone();
+        let live_photos = live_photos.clone();
         let processed_count = processed_count.clone();
         let app_handle = app.clone();
         let chunk = chunk.to_vec();
@@ -270,7 +277,7 @@ fn process_images_parallel_with_progress(
             let mut local_results = Vec::new();
             for (j, path) in chunk.iter().enumerate() {
                 let thumbnail_path = thumbnails_chunk.get(j).and_then(|t| t.as_ref().cloned());
-                let result = create_media_record_fast(path, thumbnail_path);
+                let result = create_media_record_fast(path, thumbnail_path, &live_photos);
                 local_results.push((i * chunk_size + j, result));
 
                 // 更新进度
@@ -322,6 +329,7 @@ fn process_images_parallel_with_progress(
 fn create_media_record_fast(
     path: &std::path::PathBuf,
     thumbnail_path: Option<String>,
+    live_photos: &LivePhotoIndex,
 ) -> Option<MediaRecord> {
     if !path.exists() || !path.is_file() {
         return None;
@@ -337,18 +345,28 @@ fn create_media_record_fast(
         .map(|(w, h)| (Some(w as u32), Some(h as u32)))
         .unwrap_or((None, None));
 
-    // 异步读取 EXIF 数据，失败时使用文件修改时间
-    let taken_date = extract_exif_date_fast(path).or_else(|| {
-        metadata
-            .modified()
-            .ok()
-            .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
-            .map(|dur| Utc::now() - chrono::Duration::seconds(dur.as_secs() as i64))
-    });
+    // 读取 EXIF 拍摄信息，拍摄时间缺失时使用文件修改时间
+    let exif = extract_exif_metadata(path).unwrap_or_default();
+    let taken_date = exif
+        .taken_date
+        .or_else(|| metadata.modified().ok().map(chrono::DateTime::<Utc>::from));
 
     let mime_type = get_mime_type_from_extension(path);
     let now = Utc::now();
 
+    // 沿用 XMP 边车文件中已有的评分和颜色标签
+    let marks = read_xmp_marks(path).unwrap_or_default();
+
+    // 实况照片配对视频或动态照片内嵌视频
+    let motion = detect_motion_component(path, live_photos);
+
+    // 离线反向地理编码
+    let place = exif
+        .latitude
+        .zip(exif.longitude)
+        .and_then(|(latitude, longitude)| reverse_geocode(latitude, longitude))
+        .unwrap_or_default();
+
     Some(MediaRecord {
         id: Uuid::new_v4(),
         path: path.to_string_lossy().to_string(),
@@ -366,32 +384,34 @@ fn create_media_record_fast(
         tags: None,
         album_ids: None,
         face_count: None,
+        motion_path: motion
+            .as_ref()
+            .map(|m| m.path.to_string_lossy().to_string()),
+        motion_offset: motion.as_ref().and_then(|m| m.offset),
+        motion_length: motion.as_ref().and_then(|m| m.length),
+        camera_make: exif.camera_make,
+        camera_model: exif.camera_model,
+        lens_model: exif.lens_model,
+        iso: exif.iso,
+        aperture: exif.aperture,
+        focal_length: exif.focal_length,
+        latitude: exif.latitude,
+        longitude: exif.longitude,
+        country: place.country,
+        region: place.region,
+        city: place.city,
+        caption: exif.caption,
+        rating: marks.rating,
+        favorite: false,
+        color_label: marks.color_label,
+        flag: if marks.rejected {
+            PickFlag::Reject
+        } else {
+            PickFlag::None
+        },
     })
 }
 
-/// 快速提取 EXIF 日期信息
-fn extract_exif_date_fast(path: &Path) -> Option<chrono::DateTime<Utc>> {
-    let file = fs::File::open(path).ok()?;
-    let mut bufreader = std::io::BufReader::new(file);
-
-    let exif_reader = ExifReader::new();
-    let exif = exif_reader.read_from_container(&mut bufreader).ok()?;
-
-    info!("尝试从 EXIF 中提取日期信息...");
-    if let Some(field) = exif.get_field(Tag::DateTimeOriginal, exif::In::PRIMARY) {
-        info!("EXIF 日期信息: {}", field.display_value());
-        // 尝试解析日期，失败时返回 None 而不是 panic
-        field
-            .display_value()
-            .to_string()
-            .parse::<chrono::DateTime<Utc>>()
-            .ok()
-    } else {
-        info!("无法从 EXIF 中提取日期信息");
-        None
-    }
-}
-
 /// 根据文件扩展名获取 MIME 类型
 fn get_mime_type_from_extension(path: &Path) -> Option<String> {
     match path.extension()?.to_str()?.to_lowercase().as_str() {
@@ -401,6 +421,8 @@ fn get_mime_type_from_extension(path: &Path) -> Option<String> {
         "bmp" => Some("image/bmp".to_string()),
         "tiff" | "tif" => Some("image/tiff".to_string()),
         "webp" => Some("image/webp".to_string()),
+        "heic" => Some("image/heic".to_string()),
+        "heif" => Some("image/heif".to_string()),
         _ => None,
     }
 }
@@ -409,8 +431,9 @@ fn get_mime_type_from_extension(path: &Path) -> Option<String> {
 pub fn get_media_record(app: AppHandle, path: String) -> Result<MediaRecord, String> {
     let path_buf = std::path::PathBuf::from(&path);
     let thumbnail_path = generate_thumbnail(&app, &path_buf);
+    let live_photos = LivePhotoIndex::build(std::slice::from_ref(&path_buf));
 
-    create_media_record_fast(&path_buf, thumbnail_path)
+    create_media_record_fast(&path_buf, thumbnail_path, &live_photos)
         .ok_or_else(|| format!("无法处理文件: {}", path))
 }
 
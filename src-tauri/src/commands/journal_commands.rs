@@ -0,0 +1,50 @@
+use tauri::{AppHandle, Emitter};
+
+use crate::database::journal_repository::JournalRepository;
+use crate::models::journal::JournalEntry;
+
+/// 撤销或重做后发送的事件，负载为对应的操作记录，前端需重新加载受影响的数据
+const JOURNAL_APPLIED_EVENT: &str = "journal-applied";
+
+/// 获取操作记录，最近的在前，`undone` 为 true 的记录可以重做
+#[tauri::command]
+pub async fn get_journal(app: AppHandle) -> Result<Vec<JournalEntry>, String> {
+    let repository = JournalRepository::new(app);
+
+    repository
+        .find_all()
+        .await
+        .map_err(|e| format!("获取操作记录失败: {}", e))
+}
+
+/// 撤销最近一次操作，没有可撤销的操作时返回空
+#[tauri::command]
+pub async fn undo(app: AppHandle) -> Result<Option<JournalEntry>, String> {
+    let repository = JournalRepository::new(app.clone());
+
+    let entry = repository
+        .undo()
+        .await
+        .map_err(|e| format!("撤销失败: {}", e))?;
+
+    if let Some(entry) = &entry {
+        let _ = app.emit(JOURNAL_APPLIED_EVENT, entry);
+    }
+    Ok(entry)
+}
+
+/// 重做最近一次撤销的操作，没有可重做的操作时返回空
+#[tauri::command]
+pub async fn redo(app: AppHandle) -> Result<Option<JournalEntry>, String> {
+    let repository = JournalRepository::new(app.clone());
+
+    let entry = repository
+        .redo()
+        .await
+        .map_err(|e| format!("重做失败: {}", e))?;
+
+    if let Some(entry) = &entry {
+        let _ = app.emit(JOURNAL_APPLIED_EVENT, entry);
+    }
+    Ok(entry)
+}
@@ -0,0 +1,412 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+use log::{info, warn};
+use tauri::{AppHandle, Emitter};
+
+use crate::commands::image_import::{
+    get_media_records_with_db, is_supported_image, ImagesDealProgressEvent,
+};
+use crate::database::library_import_repository::LibraryImportRepository;
+use crate::models::library_import::{
+    ImportFailure, ImportTransferMode, ImportedFile, LibraryImportSettings, LibraryImportSummary,
+};
+use crate::utils::exif_metadata::extract_exif_metadata;
+use crate::utils::file_ops::{copy_file_verified, file_sha256, move_file, walk_files};
+use crate::utils::motion_photo::LivePhotoIndex;
+use crate::utils::path_template::{assign_sequences, render_path_template, TemplateValues};
+
+/// 待导入的文件及生成路径所需的信息
+struct PendingImport {
+    source: PathBuf,
+    /// 配对的实况照片视频，与照片一起导入并使用相同的文件名
+    companion: Option<PathBuf>,
+    values: TemplateValues,
+}
+
+/// 已复制或移动到媒体库文件夹的文件，未能登记到媒体库时据此撤销
+struct TransferredFile {
+    source: PathBuf,
+    target: PathBuf,
+    content_hash: String,
+    /// 一起导入的实况照片视频的原路径和目标路径
+    companion: Option<(PathBuf, PathBuf)>,
+}
+
+/// 目标位置的检查结果
+enum ImportTarget {
+    /// 可以写入的位置，重名时已加上编号
+    Free(PathBuf),
+    /// 目标位置已有内容相同的文件
+    Duplicate,
+}
+
+/// 获取媒体库文件夹及导入路径模板
+#[tauri::command]
+pub async fn get_library_import_settings(app: AppHandle) -> Result<LibraryImportSettings, String> {
+    let repository = LibraryImportRepository::new(app);
+
+    repository
+        .settings()
+        .await
+        .map_err(|e| format!("获取导入设置失败: {}", e))
+}
+
+/// 保存媒体库文件夹及导入路径模板
+#[tauri::command]
+pub async fn set_library_import_settings(
+    app: AppHandle,
+    settings: LibraryImportSettings,
+) -> Result<(), String> {
+    let repository = LibraryImportRepository::new(app);
+
+    repository
+        .set_settings(&settings)
+        .await
+        .map_err(|e| format!("保存导入设置失败: {}", e))
+}
+
+/// 将文件或文件夹中的图片复制或移动到媒体库文件夹后导入
+///
+/// 按拍摄时间排序后编号，按路径模板放入媒体库文件夹；重名时在文件名后加编号，
+/// 内容已在媒体库中的文件跳过。复制后校验内容哈希，移动导入只在校验通过后删除原文件。
+/// 无法登记到数据库的文件被移回原位置（复制导入时删除副本），记入失败列表。
+#[tauri::command]
+pub async fn import_to_library(
+    app: AppHandle,
+    sources: Vec<String>,
+    mode: ImportTransferMode,
+) -> Result<LibraryImportSummary, String> {
+    let repository = LibraryImportRepository::new(app.clone());
+    let settings = repository
+        .settings()
+        .await
+        .map_err(|e| format!("获取导入设置失败: {}", e))?;
+    let library_root = settings
+        .library_root
+        .map(PathBuf::from)
+        .ok_or_else(|| "尚未设置媒体库文件夹".to_string())?;
+
+    let pending = collect_pending_imports(&sources);
+    let total = pending.len();
+    let mut summary = LibraryImportSummary::default();
+    let mut transferred: Vec<TransferredFile> = Vec::new();
+    let mut seen_hashes: HashSet<String> = HashSet::new();
+
+    for (index, file) in pending.into_iter().enumerate() {
+        let source_path = file.source.to_string_lossy().into_owned();
+        let _ = app.emit(
+            "images-deal-progress",
+            ImagesDealProgressEvent {
+                current: index,
+                total,
+                current_file: file
+                    .source
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned()),
+                step: "copying_files".to_string(),
+            },
+        );
+
+        let content_hash = match file_sha256(&file.source) {
+            Ok(hash) => hash,
+            Err(e) => {
+                summary.failed.push(ImportFailure {
+                    source_path,
+                    error: format!("读取文件失败: {}", e),
+                });
+                continue;
+            }
+        };
+        let catalogued = match repository.contains_hash(&content_hash).await {
+            Ok(catalogued) => catalogued,
+            Err(e) => {
+                summary.failed.push(ImportFailure {
+                    source_path,
+                    error: format!("检查重复文件失败: {}", e),
+                });
+                continue;
+            }
+        };
+        if catalogued || !seen_hashes.insert(content_hash.clone()) {
+            summary.duplicates.push(source_path);
+            continue;
+        }
+
+        let relative = match render_path_template(&settings.path_template, &file.values) {
+            Ok(relative) => relative,
+            Err(error) => {
+                summary.failed.push(ImportFailure { source_path, error });
+                continue;
+            }
+        };
+        let companion_ext = file
+            .companion
+            .as_ref()
+            .and_then(|companion| companion.extension())
+            .map(|ext| ext.to_os_string());
+        let target = match resolve_target(
+            library_root.join(relative),
+            &content_hash,
+            companion_ext.as_deref(),
+        ) {
+            ImportTarget::Free(target) => target,
+            ImportTarget::Duplicate => {
+                summary.duplicates.push(source_path);
+                continue;
+            }
+        };
+
+        if let Err(e) = transfer_file(&file.source, &target, &content_hash, mode) {
+            summary.failed.push(ImportFailure {
+                source_path,
+                error: e.to_string(),
+            });
+            continue;
+        }
+        let mut companion = None;
+        if let (Some(source), Some(ext)) = (file.companion, &companion_ext) {
+            let companion_target = target.with_extension(ext);
+            let result = file_sha256(&source)
+                .and_then(|hash| transfer_file(&source, &companion_target, &hash, mode));
+            match result {
+                Ok(()) => companion = Some((source, companion_target)),
+                Err(e) => warn!("导入实况照片视频失败 {}: {}", source.display(), e),
+            }
+        }
+
+        transferred.push(TransferredFile {
+            source: file.source,
+            target,
+            content_hash,
+            companion,
+        });
+    }
+
+    let _ = app.emit(
+        "images-deal-progress",
+        ImagesDealProgressEvent {
+            current: total,
+            total,
+            current_file: None,
+            step: "copying_files".to_string(),
+        },
+    );
+
+    if !transferred.is_empty() {
+        let paths = transferred
+            .iter()
+            .map(|file| file.target.to_string_lossy().into_owned())
+            .collect();
+        // 创建媒体记录失败时，已放入媒体库文件夹的文件全部撤销，避免移动导入的文件无人登记
+        let (records, records_error) = match get_media_records_with_db(app.clone(), paths).await {
+            Ok(records) => (records, None),
+            Err(e) => (Vec::new(), Some(e)),
+        };
+        let records: HashMap<String, (String, Option<String>)> = records
+            .into_iter()
+            .map(|record| (record.path, (record.id.to_string(), record.thumbnail_path)))
+            .collect();
+
+        for file in transferred {
+            let path = file.target.to_string_lossy().into_owned();
+            let source_path = file.source.to_string_lossy().into_owned();
+            let Some((media_id, thumbnail_path)) = records.get(&path) else {
+                let error = match &records_error {
+                    Some(e) => format!("创建媒体记录失败: {}", e),
+                    None => format!("文件已导入媒体库文件夹但无法读取: {}", path),
+                };
+                summary.failed.push(ImportFailure {
+                    source_path,
+                    error: revert_transfer(&file, mode, error),
+                });
+                continue;
+            };
+
+            let imported = ImportedFile {
+                media_id: media_id.clone(),
+                source_path,
+                path,
+            };
+            if let Err(e) = repository
+                .record_source(&imported, &file.content_hash)
+                .await
+            {
+                let mut error = format!("记录原文件路径失败: {}", e);
+                match repository.remove_record(media_id).await {
+                    Ok(()) => {
+                        if let Some(thumbnail_path) = thumbnail_path {
+                            let _ = fs::remove_file(thumbnail_path);
+                        }
+                        error = revert_transfer(&file, mode, error);
+                    }
+                    Err(e) => warn!("删除未完成导入的媒体记录失败 {}: {}", media_id, e),
+                }
+                summary.failed.push(ImportFailure {
+                    source_path: imported.source_path,
+                    error,
+                });
+                continue;
+            }
+            summary.imported.push(imported);
+        }
+    }
+
+    info!(
+        "已导入 {} 个文件到媒体库文件夹，跳过 {} 个重复文件，{} 个失败",
+        summary.imported.len(),
+        summary.duplicates.len(),
+        summary.failed.len()
+    );
+    Ok(summary)
+}
+
+/// 收集待导入的图片，文件夹会递归查找，按拍摄时间排序后编号
+///
+/// 拍摄时间缺失时使用文件修改时间，与创建媒体记录时的规则一致。
+fn collect_pending_imports(sources: &[String]) -> Vec<PendingImport> {
+    let mut files: Vec<PathBuf> = Vec::new();
+    for source in sources {
+        let path = Path::new(source);
+        if path.is_dir() {
+            files.extend(walk_files(path));
+        } else if path.is_file() {
+            files.push(path.to_path_buf());
+        }
+    }
+    let mut seen = HashSet::new();
+    files.retain(|path| is_supported_image(path) && seen.insert(path.clone()));
+
+    let live_photos = LivePhotoIndex::build(&files);
+    let mut pending: Vec<PendingImport> = files
+        .into_iter()
+        .map(|source| {
+            let exif = extract_exif_metadata(&source).unwrap_or_default();
+            let taken_date = exif.taken_date.or_else(|| {
+                fs::metadata(&source)
+                    .and_then(|metadata| metadata.modified())
+                    .ok()
+                    .map(DateTime::<Utc>::from)
+            });
+            PendingImport {
+                companion: live_photos.find_companion(&source),
+                values: TemplateValues {
+                    taken_date,
+                    camera_make: exif.camera_make,
+                    camera_model: exif.camera_model,
+                    ..TemplateValues::for_path(&source)
+                },
+                source,
+            }
+        })
+        .collect();
+    pending.sort_by(|a, b| {
+        a.values
+            .taken_date
+            .cmp(&b.values.taken_date)
+            .then_with(|| a.source.cmp(&b.source))
+    });
+    assign_sequences(pending.iter_mut().map(|file| &mut file.values));
+
+    pending
+}
+
+/// 检查目标位置，重名时依次尝试 `name-1.ext`、`name-2.ext`……
+///
+/// 已存在的文件内容与原文件相同时视为重复；有实况照片视频时两者的文件名都不能被占用。
+fn resolve_target(
+    target: PathBuf,
+    content_hash: &str,
+    companion_ext: Option<&std::ffi::OsStr>,
+) -> ImportTarget {
+    let stem = target
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let ext = target
+        .extension()
+        .map(|ext| format!(".{}", ext.to_string_lossy()))
+        .unwrap_or_default();
+
+    let mut number = 0;
+    loop {
+        let candidate = if number == 0 {
+            target.clone()
+        } else {
+            target.with_file_name(format!("{}-{}{}", stem, number, ext))
+        };
+
+        number += 1;
+
+        if candidate.exists() {
+            if file_sha256(&candidate).ok().as_deref() == Some(content_hash) {
+                return ImportTarget::Duplicate;
+            }
+            continue;
+        }
+        if companion_ext.is_some_and(|ext| candidate.with_extension(ext).exists()) {
+            continue;
+        }
+        return ImportTarget::Free(candidate);
+    }
+}
+
+/// 撤销放入媒体库文件夹的文件：移动导入的移回原位置，复制导入的删除副本
+///
+/// 返回附加了撤销结果的错误信息，撤销失败时说明文件的位置。
+fn revert_transfer(file: &TransferredFile, mode: ImportTransferMode, error: String) -> String {
+    let companion = file
+        .companion
+        .as_ref()
+        .map(|(source, target)| (source, target));
+
+    let mut kept = Vec::new();
+    for (source, target) in std::iter::once((&file.source, &file.target)).chain(companion) {
+        let result = match mode {
+            ImportTransferMode::Move => move_file(target, source),
+            ImportTransferMode::Copy => fs::remove_file(target),
+        };
+        if let Err(e) = result {
+            warn!("撤销导入失败 {}: {}", target.display(), e);
+            kept.push(target.to_string_lossy().into_owned());
+        }
+    }
+
+    if kept.is_empty() {
+        format!("{}，已撤销导入", error)
+    } else {
+        format!("{}，文件保留在 {}", error, kept.join("、"))
+    }
+}
+
+/// 复制或移动文件到媒体库文件夹
+///
+/// 移动时优先重命名；无法重命名（如从存储卡导入）时复制并校验后再删除原文件，
+/// 原文件删除失败时保留两份文件，只记录日志。
+fn transfer_file(
+    source: &Path,
+    target: &Path,
+    content_hash: &str,
+    mode: ImportTransferMode,
+) -> io::Result<()> {
+    if mode == ImportTransferMode::Move && !target.exists() {
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        if fs::rename(source, target).is_ok() {
+            return Ok(());
+        }
+    }
+
+    copy_file_verified(source, target, content_hash)?;
+    if mode == ImportTransferMode::Move {
+        if let Err(e) = fs::remove_file(source) {
+            warn!("删除原文件失败 {}: {}", source.display(), e);
+        }
+    }
+    Ok(())
+}
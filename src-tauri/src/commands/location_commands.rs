@@ -0,0 +1,47 @@
+use tauri::{AppHandle, Emitter};
+
+use crate::database::location_repository::LocationRepository;
+use crate::models::location::{MapBounds, MapCluster, PlaceCount};
+
+/// 地名变更事件，负载为地名有变化的媒体数量
+const MEDIA_PLACES_UPDATED_EVENT: &str = "media-places-updated";
+
+/// 获取所有地点及其媒体数量
+#[tauri::command]
+pub async fn get_places(app: AppHandle) -> Result<Vec<PlaceCount>, String> {
+    let repository = LocationRepository::new(app);
+
+    repository
+        .find_places()
+        .await
+        .map_err(|e| format!("获取地点失败: {}", e))
+}
+
+/// 获取地图可视范围内的照片聚合点，`zoom` 为地图缩放级别（0-22）
+#[tauri::command]
+pub async fn get_map_clusters(
+    app: AppHandle,
+    bounds: MapBounds,
+    zoom: u8,
+) -> Result<Vec<MapCluster>, String> {
+    let repository = LocationRepository::new(app);
+
+    repository
+        .find_map_clusters(&bounds, zoom)
+        .await
+        .map_err(|e| format!("获取地图聚合点失败: {}", e))
+}
+
+/// 使用内置地名索引重新为所有带 GPS 坐标的媒体计算地名
+#[tauri::command]
+pub async fn refresh_media_places(app: AppHandle) -> Result<usize, String> {
+    let repository = LocationRepository::new(app.clone());
+
+    let updated = repository
+        .refresh_places()
+        .await
+        .map_err(|e| format!("更新地名失败: {}", e))?;
+
+    let _ = app.emit(MEDIA_PLACES_UPDATED_EVENT, updated);
+    Ok(updated)
+}
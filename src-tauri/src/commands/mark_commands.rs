@@ -0,0 +1,148 @@
+use std::path::Path;
+
+use log::{error, info};
+use tauri::{AppHandle, Emitter};
+
+use crate::database::media_repository::MediaRepository;
+use crate::models::image::{ColorLabel, PickFlag};
+use crate::utils::xmp_sidecar::{read_xmp_marks, write_xmp_marks};
+
+/// 评分、收藏、颜色标签或挑选标记变更事件，负载为变更的媒体 ID
+const MEDIA_MARKS_UPDATED_EVENT: &str = "media-marks-updated";
+
+/// 批量设置星级评分（0-5），`write_xmp` 为 true 时同步写入 XMP 边车文件
+#[tauri::command]
+pub async fn set_media_rating(
+    app: AppHandle,
+    media_ids: Vec<String>,
+    rating: u8,
+    write_xmp: Option<bool>,
+) -> Result<usize, String> {
+    let repository = MediaRepository::new(app.clone());
+
+    let updated = repository
+        .set_rating(&media_ids, rating)
+        .await
+        .map_err(|e| format!("设置评分失败: {}", e))?;
+
+    finish_update(&app, &repository, &media_ids, write_xmp).await;
+    Ok(updated)
+}
+
+/// 批量设置或取消收藏
+#[tauri::command]
+pub async fn set_media_favorite(
+    app: AppHandle,
+    media_ids: Vec<String>,
+    favorite: bool,
+) -> Result<usize, String> {
+    let repository = MediaRepository::new(app.clone());
+
+    let updated = repository
+        .set_favorite(&media_ids, favorite)
+        .await
+        .map_err(|e| format!("设置收藏失败: {}", e))?;
+
+    // XMP 没有标准的收藏字段，不写入边车文件
+    finish_update(&app, &repository, &media_ids, Some(false)).await;
+    Ok(updated)
+}
+
+/// 批量设置颜色标签，`color_label` 为空时清除
+#[tauri::command]
+pub async fn set_media_color_label(
+    app: AppHandle,
+    media_ids: Vec<String>,
+    color_label: Option<ColorLabel>,
+    write_xmp: Option<bool>,
+) -> Result<usize, String> {
+    let repository = MediaRepository::new(app.clone());
+
+    let updated = repository
+        .set_color_label(&media_ids, color_label)
+        .await
+        .map_err(|e| format!("设置颜色标签失败: {}", e))?;
+
+    finish_update(&app, &repository, &media_ids, write_xmp).await;
+    Ok(updated)
+}
+
+/// 批量设置挑选/排除标记
+#[tauri::command]
+pub async fn set_media_flag(
+    app: AppHandle,
+    media_ids: Vec<String>,
+    flag: PickFlag,
+    write_xmp: Option<bool>,
+) -> Result<usize, String> {
+    let repository = MediaRepository::new(app.clone());
+
+    let updated = repository
+        .set_flag(&media_ids, flag)
+        .await
+        .map_err(|e| format!("设置标记失败: {}", e))?;
+
+    finish_update(&app, &repository, &media_ids, write_xmp).await;
+    Ok(updated)
+}
+
+/// 从 XMP 边车文件重新读取评分和颜色标签，返回读取到边车文件的媒体数量
+#[tauri::command]
+pub async fn sync_marks_from_xmp(app: AppHandle, media_ids: Vec<String>) -> Result<usize, String> {
+    let repository = MediaRepository::new(app.clone());
+    let mut marks = Vec::new();
+
+    for media_id in &media_ids {
+        let record = match repository.find_by_id(media_id).await {
+            Ok(Some(record)) => record,
+            Ok(None) => continue,
+            Err(e) => return Err(format!("读取媒体记录失败: {}", e)),
+        };
+        let Some(xmp) = read_xmp_marks(Path::new(&record.path)) else {
+            continue;
+        };
+
+        let flag = match (xmp.rejected, record.flag) {
+            (true, _) => PickFlag::Reject,
+            // 边车文件中不再是排除状态时才清除排除标记，保留挑选标记
+            (false, PickFlag::Reject) => PickFlag::None,
+            (false, flag) => flag,
+        };
+        marks.push((media_id.clone(), xmp.rating, xmp.color_label, flag));
+    }
+
+    // 所有媒体的标记作为一次操作记录，可以一起撤销
+    let synced = repository
+        .set_marks("同步 XMP 标记", &marks)
+        .await
+        .map_err(|e| format!("同步 XMP 标记失败: {}", e))?;
+
+    info!("已从 XMP 边车文件同步 {} 个媒体的标记", synced);
+    let _ = app.emit(MEDIA_MARKS_UPDATED_EVENT, media_ids);
+    Ok(synced)
+}
+
+/// 按需写入 XMP 边车文件并发送变更事件，写入失败只记录日志
+async fn finish_update(
+    app: &AppHandle,
+    repository: &MediaRepository,
+    media_ids: &[String],
+    write_xmp: Option<bool>,
+) {
+    if write_xmp.unwrap_or(false) {
+        for media_id in media_ids {
+            if let Ok(Some(record)) = repository.find_by_id(media_id).await {
+                if let Err(e) = write_xmp_marks(
+                    Path::new(&record.path),
+                    record.rating,
+                    record.color_label,
+                    record.flag,
+                ) {
+                    error!("写入 XMP 边车文件失败 {}: {}", record.path, e);
+                }
+            }
+        }
+    }
+
+    let _ = app.emit(MEDIA_MARKS_UPDATED_EVENT, media_ids.to_vec());
+}
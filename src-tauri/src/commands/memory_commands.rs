@@ -0,0 +1,82 @@
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+    time::Duration,
+};
+
+use log::{error, info};
+use tauri::{AppHandle, Emitter};
+
+use crate::database::memory_repository::MemoryRepository;
+use crate::models::image::MediaRecord;
+use crate::models::memory::MemoryRecord;
+
+/// 回忆刷新完成事件，负载为回忆数量
+const MEMORIES_UPDATED_EVENT: &str = "memories-updated";
+
+/// 后台刷新回忆的间隔，保证“那年今日”跨天后及时更新
+const MEMORY_REFRESH_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// 是否正在刷新回忆，同一时间只允许一次刷新
+static MEMORY_REFRESH_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// 获取所有回忆
+#[tauri::command]
+pub async fn get_memories(app: AppHandle) -> Result<Vec<MemoryRecord>, String> {
+    let repository = MemoryRepository::new(app);
+
+    repository
+        .find_all()
+        .await
+        .map_err(|e| format!("获取回忆失败: {}", e))
+}
+
+/// 获取回忆中的媒体
+#[tauri::command]
+pub async fn get_memory_media(
+    app: AppHandle,
+    memory_id: String,
+) -> Result<Vec<MediaRecord>, String> {
+    let repository = MemoryRepository::new(app);
+
+    repository
+        .find_media(&memory_id)
+        .await
+        .map_err(|e| format!("获取回忆中的媒体失败: {}", e))
+}
+
+/// 立即重新生成回忆，返回回忆数量
+#[tauri::command]
+pub async fn refresh_memories(app: AppHandle) -> Result<usize, String> {
+    refresh(&app).await
+}
+
+/// 启动后台任务，启动时及之后每隔 `MEMORY_REFRESH_INTERVAL` 重新生成回忆
+pub fn spawn_memory_refresh(app: AppHandle) {
+    thread::spawn(move || loop {
+        if let Err(e) = tauri::async_runtime::block_on(refresh(&app)) {
+            error!("{}", e);
+        }
+        thread::sleep(MEMORY_REFRESH_INTERVAL);
+    });
+}
+
+async fn refresh(app: &AppHandle) -> Result<usize, String> {
+    if MEMORY_REFRESH_RUNNING
+        .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+        .is_err()
+    {
+        return Err("回忆正在刷新".to_string());
+    }
+
+    let result = MemoryRepository::new(app.clone())
+        .refresh()
+        .await
+        .map_err(|e| format!("刷新回忆失败: {}", e));
+    MEMORY_REFRESH_RUNNING.store(false, Ordering::SeqCst);
+
+    let count = result?;
+    info!("已生成 {} 个回忆", count);
+    let _ = app.emit(MEMORIES_UPDATED_EVENT, count);
+    Ok(count)
+}
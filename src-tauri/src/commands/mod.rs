@@ -1,2 +1,24 @@
+pub mod album_commands;
+pub mod backup_commands;
+pub mod contact_sheet_commands;
 pub mod database_commands;
+pub mod export_commands;
+pub mod face_commands;
+pub mod gallery_commands;
 pub mod image_import;
+pub mod journal_commands;
+pub mod library_import_commands;
+pub mod location_commands;
+pub mod mark_commands;
+pub mod memory_commands;
+pub mod motion_commands;
+pub mod organize_commands;
+pub mod person_commands;
+pub mod relink_commands;
+pub mod rename_commands;
+pub mod search_commands;
+pub mod smart_album_commands;
+pub mod stats_commands;
+pub mod tag_commands;
+pub mod timeline_commands;
+pub mod trash_commands;
@@ -0,0 +1,63 @@
+use std::path::{Path, PathBuf};
+
+use log::info;
+use tauri::{path::BaseDirectory, AppHandle, Manager};
+
+use crate::database::media_repository::MediaRepository;
+use crate::utils::motion_photo::extract_embedded_motion;
+
+/// 导出实况照片 / 动态照片的视频片段，返回视频文件路径
+///
+/// 未指定 `output_path` 时，配对视频直接返回原文件路径，
+/// 内嵌视频则提取到应用数据目录下的 `motion_clips` 缓存中。
+#[tauri::command]
+pub async fn extract_motion_clip(
+    app: AppHandle,
+    media_id: String,
+    output_path: Option<String>,
+) -> Result<String, String> {
+    let repository = MediaRepository::new(app.clone());
+
+    let record = repository
+        .find_by_id(&media_id)
+        .await
+        .map_err(|e| format!("获取媒体详情失败: {}", e))?
+        .ok_or_else(|| format!("未找到ID为 {} 的媒体记录", media_id))?;
+
+    let motion_path = record
+        .motion_path
+        .ok_or_else(|| format!("{} 不包含动态视频", record.name))?;
+
+    match (record.motion_offset, record.motion_length) {
+        (Some(offset), Some(length)) => {
+            let destination = match output_path {
+                Some(path) => PathBuf::from(path),
+                None => {
+                    let cached = app
+                        .path()
+                        .resolve("motion_clips", BaseDirectory::AppLocalData)
+                        .map_err(|e| format!("解析视频缓存目录失败: {}", e))?
+                        .join(format!("{}.mp4", record.id));
+                    if cached.exists() {
+                        return Ok(cached.to_string_lossy().to_string());
+                    }
+                    cached
+                }
+            };
+
+            extract_embedded_motion(Path::new(&motion_path), offset, length, &destination)
+                .map_err(|e| format!("提取动态照片视频失败: {}", e))?;
+
+            info!("已提取动态照片视频: {:?}", destination);
+            Ok(destination.to_string_lossy().to_string())
+        }
+        _ => match output_path {
+            Some(path) => {
+                std::fs::copy(&motion_path, &path)
+                    .map_err(|e| format!("复制实况照片视频失败: {}", e))?;
+                Ok(path)
+            }
+            None => Ok(motion_path),
+        },
+    }
+}
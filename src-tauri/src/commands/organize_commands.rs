@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::database::organize_repository::OrganizeRepository;
+use crate::models::organize::{OrganizeOptions, OrganizeResult};
+
+/// 文件夹整理进度事件
+const ORGANIZE_PROGRESS_EVENT: &str = "organize-progress";
+
+/// 文件整理完成后发送的事件，负载为移动的数量
+const MEDIA_ORGANIZED_EVENT: &str = "media-organized";
+
+/// 文件夹整理进度
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrganizeProgressEvent {
+    pub current: usize,
+    pub total: usize,
+}
+
+/// 按拍摄日期、相机、相册、人物或地点将媒体文件整理到目标文件夹
+///
+/// `dryRun` 为 true 时只返回计划，不移动文件。
+#[tauri::command]
+pub async fn organize_media(
+    app: AppHandle,
+    options: OrganizeOptions,
+) -> Result<OrganizeResult, String> {
+    let repository = OrganizeRepository::new(app.clone());
+
+    let result = repository
+        .organize(&options, |current, total| {
+            let _ = app.emit(
+                ORGANIZE_PROGRESS_EVENT,
+                OrganizeProgressEvent { current, total },
+            );
+        })
+        .await
+        .map_err(|e| format!("整理文件失败: {}", e))?;
+
+    if !result.dry_run {
+        let _ = app.emit(MEDIA_ORGANIZED_EVENT, result.moved);
+    }
+    Ok(result)
+}
@@ -0,0 +1,151 @@
+use log::error;
+use tauri::{AppHandle, Emitter};
+
+use crate::database::person_repository::PersonRepository;
+use crate::models::face::{ClusterSummary, FaceRecord, PersonRecord};
+use crate::models::image::MediaRecord;
+
+/// 人物或人脸归属变更事件
+pub(crate) const PEOPLE_UPDATED_EVENT: &str = "people-updated";
+
+/// 获取所有人物及其照片数量
+#[tauri::command]
+pub async fn get_people(app: AppHandle) -> Result<Vec<PersonRecord>, String> {
+    let repository = PersonRepository::new(app);
+
+    repository
+        .find_all()
+        .await
+        .map_err(|e| format!("获取人物失败: {}", e))
+}
+
+/// 获取人物的所有人脸
+#[tauri::command]
+pub async fn get_person_faces(
+    app: AppHandle,
+    person_id: String,
+) -> Result<Vec<FaceRecord>, String> {
+    let repository = PersonRepository::new(app);
+
+    repository
+        .find_faces(&person_id)
+        .await
+        .map_err(|e| format!("获取人物的人脸失败: {}", e))
+}
+
+/// 获取包含该人物的媒体
+#[tauri::command]
+pub async fn get_person_media(
+    app: AppHandle,
+    person_id: String,
+) -> Result<Vec<MediaRecord>, String> {
+    let repository = PersonRepository::new(app);
+
+    repository
+        .find_media(&person_id)
+        .await
+        .map_err(|e| format!("获取人物的媒体失败: {}", e))
+}
+
+/// 设置人物名称，`name` 为空时清除名称
+#[tauri::command]
+pub async fn rename_person(
+    app: AppHandle,
+    person_id: String,
+    name: Option<String>,
+) -> Result<PersonRecord, String> {
+    let repository = PersonRepository::new(app.clone());
+
+    let person = repository
+        .rename(&person_id, name.as_deref())
+        .await
+        .map_err(|e| format!("重命名人物失败: {}", e))?;
+
+    let _ = app.emit(PEOPLE_UPDATED_EVENT, ());
+    Ok(person)
+}
+
+/// 将一个人物合并到另一个人物
+#[tauri::command]
+pub async fn merge_people(
+    app: AppHandle,
+    source_id: String,
+    target_id: String,
+) -> Result<PersonRecord, String> {
+    let repository = PersonRepository::new(app.clone());
+
+    let person = repository
+        .merge(&source_id, &target_id)
+        .await
+        .map_err(|e| format!("合并人物失败: {}", e))?;
+
+    let _ = app.emit(PEOPLE_UPDATED_EVENT, ());
+    Ok(person)
+}
+
+/// 将人脸移动到指定人物，`person_id` 为空时拆分为新的人物
+#[tauri::command]
+pub async fn move_faces_to_person(
+    app: AppHandle,
+    face_ids: Vec<String>,
+    person_id: Option<String>,
+) -> Result<PersonRecord, String> {
+    let repository = PersonRepository::new(app.clone());
+
+    let person = repository
+        .move_faces(&face_ids, person_id.as_deref())
+        .await
+        .map_err(|e| format!("移动人脸失败: {}", e))?;
+
+    let _ = app.emit(PEOPLE_UPDATED_EVENT, ());
+    Ok(person)
+}
+
+/// 将人脸标记为误检
+#[tauri::command]
+pub async fn reject_faces(app: AppHandle, face_ids: Vec<String>) -> Result<usize, String> {
+    let repository = PersonRepository::new(app.clone());
+
+    let rejected = repository.reject_faces(&face_ids).await.map_err(|e| {
+        error!("标记误检人脸失败: {}", e);
+        format!("标记误检人脸失败: {}", e)
+    })?;
+
+    let _ = app.emit(PEOPLE_UPDATED_EVENT, ());
+    Ok(rejected)
+}
+
+/// 设置人物封面，`face_id` 为空时恢复自动选择
+#[tauri::command]
+pub async fn set_person_cover(
+    app: AppHandle,
+    person_id: String,
+    face_id: Option<String>,
+) -> Result<PersonRecord, String> {
+    let repository = PersonRepository::new(app.clone());
+
+    let person = repository
+        .set_cover(&person_id, face_id.as_deref())
+        .await
+        .map_err(|e| format!("设置人物封面失败: {}", e))?;
+
+    let _ = app.emit(PEOPLE_UPDATED_EVENT, ());
+    Ok(person)
+}
+
+/// 对未归属的人脸进行聚类，`recluster` 为 true 时重新聚类所有未确认的人脸
+#[tauri::command]
+pub async fn cluster_faces(
+    app: AppHandle,
+    recluster: Option<bool>,
+) -> Result<ClusterSummary, String> {
+    let repository = PersonRepository::new(app.clone());
+
+    let summary = repository
+        .cluster(recluster.unwrap_or(false))
+        .await
+        .map_err(|e| format!("人脸聚类失败: {}", e))?;
+
+    let _ = app.emit(PEOPLE_UPDATED_EVENT, ());
+    Ok(summary)
+}
@@ -0,0 +1,60 @@
+use std::{thread, time::Duration};
+
+use log::error;
+use tauri::{AppHandle, Emitter};
+
+use crate::database::relink_repository::RelinkRepository;
+use crate::models::relink::{MissingMedia, RelinkMatch, RelinkProposal};
+
+/// 媒体重新关联后发送的事件，负载为更新的数量
+const MEDIA_RELINKED_EVENT: &str = "media-relinked";
+
+/// 后台记录文件内容哈希的间隔，只处理新增或修改过的文件
+const FILE_INDEX_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// 获取原文件已不存在的媒体，`offline` 为 true 的媒体所在文件夹也不可访问
+#[tauri::command]
+pub async fn get_missing_media(app: AppHandle) -> Result<Vec<MissingMedia>, String> {
+    let repository = RelinkRepository::new(app);
+
+    repository
+        .find_missing()
+        .await
+        .map_err(|e| format!("检查丢失的文件失败: {}", e))
+}
+
+/// 在新的文件夹中为丢失的媒体查找匹配的文件，返回建议供用户确认
+#[tauri::command]
+pub async fn propose_relink(app: AppHandle, root: String) -> Result<Vec<RelinkProposal>, String> {
+    let repository = RelinkRepository::new(app);
+
+    repository
+        .propose(&root)
+        .await
+        .map_err(|e| format!("查找匹配文件失败: {}", e))
+}
+
+/// 按用户确认的匹配更新媒体路径，返回更新的数量
+#[tauri::command]
+pub async fn relink_media(app: AppHandle, matches: Vec<RelinkMatch>) -> Result<usize, String> {
+    let repository = RelinkRepository::new(app.clone());
+
+    let relinked = repository
+        .relink(&matches)
+        .await
+        .map_err(|e| format!("重新关联失败: {}", e))?;
+
+    let _ = app.emit(MEDIA_RELINKED_EVENT, relinked);
+    Ok(relinked)
+}
+
+/// 启动后台任务，启动时及之后每隔 `FILE_INDEX_INTERVAL` 记录文件的内容哈希和修改时间
+pub fn spawn_file_indexing(app: AppHandle) {
+    thread::spawn(move || loop {
+        let repository = RelinkRepository::new(app.clone());
+        if let Err(e) = tauri::async_runtime::block_on(repository.index_files()) {
+            error!("记录文件哈希失败: {}", e);
+        }
+        thread::sleep(FILE_INDEX_INTERVAL);
+    });
+}
@@ -0,0 +1,40 @@
+use tauri::{AppHandle, Emitter};
+
+use crate::database::rename_repository::RenameRepository;
+use crate::models::rename::RenamePreview;
+
+/// 媒体文件重命名后发送的事件，负载为重命名的数量
+const MEDIA_RENAMED_EVENT: &str = "media-renamed";
+
+/// 预览按模板重命名的结果，`template` 生成不含扩展名的文件名
+#[tauri::command]
+pub async fn preview_rename(
+    app: AppHandle,
+    media_ids: Vec<String>,
+    template: String,
+) -> Result<Vec<RenamePreview>, String> {
+    let repository = RenameRepository::new(app);
+
+    repository
+        .preview(&media_ids, &template)
+        .await
+        .map_err(|e| format!("预览重命名失败: {}", e))
+}
+
+/// 按模板重命名媒体文件，存在冲突时不做修改，返回重命名的数量
+#[tauri::command]
+pub async fn rename_media_files(
+    app: AppHandle,
+    media_ids: Vec<String>,
+    template: String,
+) -> Result<usize, String> {
+    let repository = RenameRepository::new(app.clone());
+
+    let renamed = repository
+        .rename(&media_ids, &template)
+        .await
+        .map_err(|e| format!("重命名失败: {}", e))?;
+
+    let _ = app.emit(MEDIA_RENAMED_EVENT, renamed);
+    Ok(renamed)
+}
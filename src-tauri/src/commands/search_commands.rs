@@ -0,0 +1,97 @@
+use log::error;
+use tauri::{AppHandle, Emitter};
+
+use crate::database::media_repository::MediaRepository;
+use crate::database::saved_search_repository::SavedSearchRepository;
+use crate::models::media_filter::{MediaFilter, MediaSort};
+use crate::models::search::{SavedSearchRecord, SearchResult};
+use crate::models::smart_album::SmartRule;
+use crate::utils::search_query::{self, QueryParseError};
+
+/// 保存的搜索变更事件
+const SAVED_SEARCH_UPDATED_EVENT: &str = "saved-search-updated";
+
+/// 按查询语法搜索媒体，可与列表的筛选条件组合；未指定排序时按相关度排序，未指定数量时返回全部结果
+#[tauri::command]
+pub async fn search_media(
+    app: AppHandle,
+    query: String,
+    filter: Option<MediaFilter>,
+    sort: Option<MediaSort>,
+    limit: Option<usize>,
+) -> Result<Vec<SearchResult>, String> {
+    let repository = MediaRepository::new(app);
+
+    repository
+        .search(&query, &filter.unwrap_or_default(), sort.as_ref(), limit)
+        .await
+        .map_err(|e| format!("搜索失败: {}", e))
+}
+
+/// 解析查询语法，供搜索框实时校验；错误中包含出错位置，可用于标注输入文本
+///
+/// 返回的规则可直接用于创建智能相册。
+#[tauri::command]
+pub fn parse_search_query(query: String) -> Result<SmartRule, QueryParseError> {
+    search_query::parse_search_query(&query)
+}
+
+/// 获取所有保存的搜索
+#[tauri::command]
+pub async fn get_saved_searches(app: AppHandle) -> Result<Vec<SavedSearchRecord>, String> {
+    let repository = SavedSearchRepository::new(app);
+
+    repository
+        .find_all()
+        .await
+        .map_err(|e| format!("获取保存的搜索失败: {}", e))
+}
+
+/// 将查询保存为命名搜索
+#[tauri::command]
+pub async fn create_saved_search(
+    app: AppHandle,
+    name: String,
+    query: String,
+) -> Result<SavedSearchRecord, String> {
+    let repository = SavedSearchRepository::new(app.clone());
+
+    let search = repository
+        .create(&name, &query)
+        .await
+        .map_err(|e| format!("保存搜索失败: {}", e))?;
+
+    let _ = app.emit(SAVED_SEARCH_UPDATED_EVENT, search.clone());
+    Ok(search)
+}
+
+/// 修改保存搜索的名称或查询，未提供的字段保持不变
+#[tauri::command]
+pub async fn update_saved_search(
+    app: AppHandle,
+    search_id: String,
+    name: Option<String>,
+    query: Option<String>,
+) -> Result<SavedSearchRecord, String> {
+    let repository = SavedSearchRepository::new(app.clone());
+
+    let search = repository
+        .update(&search_id, name.as_deref(), query.as_deref())
+        .await
+        .map_err(|e| format!("更新保存的搜索失败: {}", e))?;
+
+    let _ = app.emit(SAVED_SEARCH_UPDATED_EVENT, search.clone());
+    Ok(search)
+}
+
+/// 删除保存的搜索
+#[tauri::command]
+pub async fn delete_saved_search(app: AppHandle, search_id: String) -> Result<(), String> {
+    let repository = SavedSearchRepository::new(app);
+
+    repository.delete(&search_id).await.map_err(|e| {
+        let error_msg = format!("删除保存的搜索失败: {}", e);
+        error!("{}", error_msg);
+        error_msg
+    })
+}
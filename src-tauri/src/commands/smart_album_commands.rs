@@ -0,0 +1,96 @@
+use log::error;
+use tauri::{AppHandle, Emitter};
+
+use crate::database::smart_album_repository::SmartAlbumRepository;
+use crate::models::image::MediaRecord;
+use crate::models::smart_album::{SmartAlbumRecord, SmartRule};
+
+/// 智能相册变更事件
+const SMART_ALBUM_UPDATED_EVENT: &str = "smart-album-updated";
+
+/// 获取所有智能相册及当前匹配数量
+///
+/// 规则在查询时实时计算，前端在导入、删除或标签/相册变更后重新获取即可得到最新结果。
+#[tauri::command]
+pub async fn get_smart_albums(app: AppHandle) -> Result<Vec<SmartAlbumRecord>, String> {
+    let repository = SmartAlbumRepository::new(app);
+
+    repository
+        .find_all()
+        .await
+        .map_err(|e| format!("获取智能相册失败: {}", e))
+}
+
+/// 创建智能相册
+#[tauri::command]
+pub async fn create_smart_album(
+    app: AppHandle,
+    name: String,
+    rule: SmartRule,
+) -> Result<SmartAlbumRecord, String> {
+    let repository = SmartAlbumRepository::new(app.clone());
+
+    let album = repository
+        .create(&name, &rule)
+        .await
+        .map_err(|e| format!("创建智能相册失败: {}", e))?;
+
+    let _ = app.emit(SMART_ALBUM_UPDATED_EVENT, album.clone());
+    Ok(album)
+}
+
+/// 修改智能相册的名称或规则，未提供的字段保持不变
+#[tauri::command]
+pub async fn update_smart_album(
+    app: AppHandle,
+    album_id: String,
+    name: Option<String>,
+    rule: Option<SmartRule>,
+) -> Result<SmartAlbumRecord, String> {
+    let repository = SmartAlbumRepository::new(app.clone());
+
+    let album = repository
+        .update(&album_id, name.as_deref(), rule.as_ref())
+        .await
+        .map_err(|e| format!("更新智能相册失败: {}", e))?;
+
+    let _ = app.emit(SMART_ALBUM_UPDATED_EVENT, album.clone());
+    Ok(album)
+}
+
+/// 删除智能相册
+#[tauri::command]
+pub async fn delete_smart_album(app: AppHandle, album_id: String) -> Result<(), String> {
+    let repository = SmartAlbumRepository::new(app);
+
+    repository.delete(&album_id).await.map_err(|e| {
+        let error_msg = format!("删除智能相册失败: {}", e);
+        error!("{}", error_msg);
+        error_msg
+    })
+}
+
+/// 获取智能相册当前匹配的媒体
+#[tauri::command]
+pub async fn get_smart_album_media(
+    app: AppHandle,
+    album_id: String,
+) -> Result<Vec<MediaRecord>, String> {
+    let repository = SmartAlbumRepository::new(app);
+
+    repository
+        .find_media(&album_id)
+        .await
+        .map_err(|e| format!("获取智能相册内容失败: {}", e))
+}
+
+/// 统计规则匹配的媒体数量，供编辑规则时预览
+#[tauri::command]
+pub async fn count_smart_rule(app: AppHandle, rule: SmartRule) -> Result<u64, String> {
+    let repository = SmartAlbumRepository::new(app);
+
+    repository
+        .count_matching(&rule)
+        .await
+        .map_err(|e| format!("规则无效: {}", e))
+}
@@ -0,0 +1,18 @@
+use tauri::AppHandle;
+
+use crate::database::stats_repository::StatsRepository;
+use crate::models::stats::LibraryStats;
+
+/// 获取媒体库统计与存储报告，`refresh` 为 true 时忽略缓存重新统计
+#[tauri::command]
+pub async fn get_library_stats(
+    app: AppHandle,
+    refresh: Option<bool>,
+) -> Result<LibraryStats, String> {
+    let repository = StatsRepository::new(app);
+
+    repository
+        .find_stats(refresh.unwrap_or(false))
+        .await
+        .map_err(|e| format!("获取媒体库统计失败: {}", e))
+}
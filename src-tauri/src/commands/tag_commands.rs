@@ -0,0 +1,107 @@
+use log::error;
+use tauri::{AppHandle, Emitter};
+
+use crate::database::tag_repository::TagRepository;
+use crate::models::tag::{TagRecord, TagWithCount};
+
+/// 标签变更事件
+const TAG_UPDATED_EVENT: &str = "tag-updated";
+
+/// 获取所有标签及侧边栏所需的媒体数量
+#[tauri::command]
+pub async fn get_tags(app: AppHandle) -> Result<Vec<TagWithCount>, String> {
+    let repository = TagRepository::new(app);
+
+    repository
+        .find_all_with_counts()
+        .await
+        .map_err(|e| format!("获取标签列表失败: {}", e))
+}
+
+/// 创建标签，支持 `Travel/Japan/Kyoto` 形式的层级路径
+#[tauri::command]
+pub async fn create_tag(app: AppHandle, path: String) -> Result<TagRecord, String> {
+    let repository = TagRepository::new(app.clone());
+
+    let tag = repository
+        .create(&path)
+        .await
+        .map_err(|e| format!("创建标签失败: {}", e))?;
+
+    let _ = app.emit(TAG_UPDATED_EVENT, tag.clone());
+    Ok(tag)
+}
+
+/// 重命名标签
+#[tauri::command]
+pub async fn rename_tag(app: AppHandle, tag_id: String, name: String) -> Result<TagRecord, String> {
+    let repository = TagRepository::new(app.clone());
+
+    let tag = repository
+        .rename(&tag_id, &name)
+        .await
+        .map_err(|e| format!("重命名标签失败: {}", e))?;
+
+    let _ = app.emit(TAG_UPDATED_EVENT, tag.clone());
+    Ok(tag)
+}
+
+/// 将源标签合并到目标标签
+#[tauri::command]
+pub async fn merge_tags(
+    app: AppHandle,
+    source_id: String,
+    target_id: String,
+) -> Result<TagRecord, String> {
+    let repository = TagRepository::new(app.clone());
+
+    let tag = repository
+        .merge(&source_id, &target_id)
+        .await
+        .map_err(|e| format!("合并标签失败: {}", e))?;
+
+    let _ = app.emit(TAG_UPDATED_EVENT, tag.clone());
+    Ok(tag)
+}
+
+/// 删除标签及其子标签，返回删除的标签数量
+#[tauri::command]
+pub async fn delete_tag(app: AppHandle, tag_id: String) -> Result<usize, String> {
+    let repository = TagRepository::new(app);
+
+    repository.delete(&tag_id).await.map_err(|e| {
+        let error_msg = format!("删除标签失败: {}", e);
+        error!("{}", error_msg);
+        error_msg
+    })
+}
+
+/// 为选中的媒体批量添加标签
+#[tauri::command]
+pub async fn add_tags_to_media(
+    app: AppHandle,
+    media_ids: Vec<String>,
+    tag_ids: Vec<String>,
+) -> Result<usize, String> {
+    let repository = TagRepository::new(app);
+
+    repository
+        .add_to_media(&media_ids, &tag_ids)
+        .await
+        .map_err(|e| format!("添加标签失败: {}", e))
+}
+
+/// 从选中的媒体批量移除标签
+#[tauri::command]
+pub async fn remove_tags_from_media(
+    app: AppHandle,
+    media_ids: Vec<String>,
+    tag_ids: Vec<String>,
+) -> Result<usize, String> {
+    let repository = TagRepository::new(app);
+
+    repository
+        .remove_from_media(&media_ids, &tag_ids)
+        .await
+        .map_err(|e| format!("移除标签失败: {}", e))
+}
@@ -0,0 +1,46 @@
+use tauri::AppHandle;
+
+use crate::database::media_repository::MediaRepository;
+use crate::models::media_filter::{MediaFilter, MediaSort, MediaSortField};
+use crate::models::timeline::{TimelineBucket, TimelineGranularity};
+
+/// 获取时间线分组，用于时间轴滚动条和日期标题
+///
+/// 筛选和排序需与 `get_media_list` 相同，返回的 `offset` 才能对应列表位置；未指定排序时按拍摄时间倒序。
+#[tauri::command]
+pub async fn get_timeline(
+    app: AppHandle,
+    granularity: Option<TimelineGranularity>,
+    filter: Option<MediaFilter>,
+    sort: Option<MediaSort>,
+) -> Result<Vec<TimelineBucket>, String> {
+    let repository = MediaRepository::new(app);
+    let sort = sort.unwrap_or(MediaSort {
+        field: MediaSortField::TakenDate,
+        ascending: false,
+    });
+
+    repository
+        .find_timeline(
+            granularity.unwrap_or_default(),
+            &filter.unwrap_or_default(),
+            &sort,
+        )
+        .await
+        .map_err(|e| format!("获取时间线失败: {}", e))
+}
+
+/// 批量调整拍摄时间（如修正相机时区），`offset_seconds` 为负数时提前，返回调整的数量
+#[tauri::command]
+pub async fn shift_media_taken_dates(
+    app: AppHandle,
+    media_ids: Vec<String>,
+    offset_seconds: i64,
+) -> Result<usize, String> {
+    let repository = MediaRepository::new(app);
+
+    repository
+        .shift_taken_dates(&media_ids, offset_seconds)
+        .await
+        .map_err(|e| format!("调整拍摄时间失败: {}", e))
+}
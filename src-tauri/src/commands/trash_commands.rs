@@ -0,0 +1,89 @@
+use std::{thread, time::Duration};
+
+use log::error;
+use tauri::{AppHandle, Emitter};
+
+use crate::database::trash_repository::TrashRepository;
+use crate::models::trash::TrashedMedia;
+
+/// 回收站内容变更事件（恢复或彻底删除），负载为受影响的媒体数量
+const MEDIA_TRASH_UPDATED_EVENT: &str = "media-trash-updated";
+
+/// 检查回收站过期媒体的间隔
+const TRASH_PURGE_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// 获取回收站中的媒体
+#[tauri::command]
+pub async fn get_trash(app: AppHandle) -> Result<Vec<TrashedMedia>, String> {
+    let repository = TrashRepository::new(app);
+
+    repository
+        .find_trashed()
+        .await
+        .map_err(|e| format!("获取回收站失败: {}", e))
+}
+
+/// 从回收站恢复媒体，返回恢复的数量
+#[tauri::command]
+pub async fn restore_media(app: AppHandle, media_ids: Vec<String>) -> Result<usize, String> {
+    let repository = TrashRepository::new(app.clone());
+
+    let restored = repository
+        .restore(&media_ids)
+        .await
+        .map_err(|e| format!("恢复媒体失败: {}", e))?;
+
+    let _ = app.emit(MEDIA_TRASH_UPDATED_EVENT, restored);
+    Ok(restored)
+}
+
+/// 彻底删除回收站中的媒体，未指定媒体时清空回收站，返回删除的数量
+#[tauri::command]
+pub async fn empty_trash(app: AppHandle, media_ids: Option<Vec<String>>) -> Result<usize, String> {
+    let repository = TrashRepository::new(app.clone());
+
+    let purged = repository
+        .purge(media_ids.as_deref())
+        .await
+        .map_err(|e| format!("清空回收站失败: {}", e))?;
+
+    let _ = app.emit(MEDIA_TRASH_UPDATED_EVENT, purged);
+    Ok(purged)
+}
+
+/// 获取回收站保留天数，0 表示不自动删除
+#[tauri::command]
+pub async fn get_trash_retention_days(app: AppHandle) -> Result<u32, String> {
+    let repository = TrashRepository::new(app);
+
+    repository
+        .retention_days()
+        .await
+        .map_err(|e| format!("获取回收站保留天数失败: {}", e))
+}
+
+/// 设置回收站保留天数，0 表示不自动删除
+#[tauri::command]
+pub async fn set_trash_retention_days(app: AppHandle, days: u32) -> Result<(), String> {
+    let repository = TrashRepository::new(app);
+
+    repository
+        .set_retention_days(days)
+        .await
+        .map_err(|e| format!("设置回收站保留天数失败: {}", e))
+}
+
+/// 启动后台任务，启动时及之后每隔 `TRASH_PURGE_INTERVAL` 彻底删除超过保留期的媒体
+pub fn spawn_trash_purge(app: AppHandle) {
+    thread::spawn(move || loop {
+        let repository = TrashRepository::new(app.clone());
+        match tauri::async_runtime::block_on(repository.purge_expired()) {
+            Ok(0) => {}
+            Ok(purged) => {
+                let _ = app.emit(MEDIA_TRASH_UPDATED_EVENT, purged);
+            }
+            Err(e) => error!("清理回收站失败: {}", e),
+        }
+        thread::sleep(TRASH_PURGE_INTERVAL);
+    });
+}
@@ -0,0 +1,618 @@
+use chrono::Utc;
+use log::info;
+use rusqlite::{params, Connection, OptionalExtension};
+use tauri::AppHandle;
+use uuid::Uuid;
+
+use crate::database::journal_repository::{record_journal, AlbumMediaState, JournalChange};
+use crate::database::media_repository::{map_media_row, MEDIA_COLUMNS};
+use crate::database::{open_connection, parse_time_column, parse_uuid_column};
+use crate::models::album::AlbumRecord;
+use crate::models::image::MediaRecord;
+
+/// 查询相册时使用的列，顺序与 `map_album_row` 保持一致
+const ALBUM_COLUMNS: &str = "a.id, a.name, a.description, a.parent_id, a.is_folder,
+     a.cover_media_id,
+     COALESCE(
+         (SELECT thumbnail_path FROM media_records WHERE id = a.cover_media_id),
+         (SELECT m.thumbnail_path FROM album_media am
+          JOIN media_records m ON m.id = am.media_id
+          WHERE am.album_id = a.id AND m.trashed_at IS NULL ORDER BY am.position LIMIT 1)
+     ),
+     a.position,
+     (SELECT COUNT(*) FROM album_media am
+      JOIN media_records m ON m.id = am.media_id
+      WHERE am.album_id = a.id AND m.trashed_at IS NULL),
+     a.created_at, a.updated_at";
+
+/// SQLite 相册仓库实现
+pub struct AlbumRepository {
+    app: AppHandle,
+}
+
+impl AlbumRepository {
+    pub fn new(app: AppHandle) -> Self {
+        Self { app }
+    }
+
+    /// 获取数据库连接
+    fn get_connection(&self) -> Result<Connection, Box<dyn std::error::Error>> {
+        open_connection(&self.app)
+    }
+
+    /// 获取所有相册和文件夹，按层级内的位置排序
+    pub async fn find_all(&self) -> Result<Vec<AlbumRecord>, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM albums a ORDER BY a.parent_id, a.position, a.name",
+            ALBUM_COLUMNS
+        ))?;
+        let albums = stmt
+            .query_map([], map_album_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(albums)
+    }
+
+    /// 通过 ID 查找相册
+    pub async fn find_by_id(
+        &self,
+        album_id: &str,
+    ) -> Result<Option<AlbumRecord>, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+        Ok(find_album(&conn, album_id)?)
+    }
+
+    /// 创建相册或文件夹，追加到同级末尾
+    pub async fn create(
+        &self,
+        name: &str,
+        description: Option<String>,
+        parent_id: Option<String>,
+        is_folder: bool,
+    ) -> Result<AlbumRecord, Box<dyn std::error::Error>> {
+        let name = validate_name(name)?;
+
+        let conn = self.get_connection()?;
+        let tx = conn.unchecked_transaction()?;
+
+        if let Some(parent_id) = parent_id.as_deref() {
+            ensure_folder(&tx, parent_id)?;
+        }
+
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now().to_rfc3339();
+        tx.execute(
+            "INSERT INTO albums (id, name, description, parent_id, is_folder, position,
+                                 created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5,
+                     (SELECT COALESCE(MAX(position) + 1, 0) FROM albums WHERE parent_id IS ?4),
+                     ?6, ?6)",
+            params![id, name, description, parent_id, is_folder, now],
+        )?;
+
+        let album = find_album(&tx, &id)?.ok_or("创建相册后未能读取记录")?;
+        tx.commit()?;
+
+        info!("已创建相册: {}", album.name);
+        Ok(album)
+    }
+
+    /// 重命名相册
+    pub async fn rename(
+        &self,
+        album_id: &str,
+        name: &str,
+    ) -> Result<AlbumRecord, Box<dyn std::error::Error>> {
+        let name = validate_name(name)?;
+        let conn = self.get_connection()?;
+
+        let updated = conn.execute(
+            "UPDATE albums SET name = ?1, updated_at = ?2 WHERE id = ?3",
+            params![name, Utc::now().to_rfc3339(), album_id],
+        )?;
+        if updated == 0 {
+            return Err(format!("未找到相册: {}", album_id).into());
+        }
+
+        Ok(find_album(&conn, album_id)?.ok_or_else(|| format!("未找到相册: {}", album_id))?)
+    }
+
+    /// 更新相册描述和封面，封面必须是相册内的媒体
+    pub async fn update_details(
+        &self,
+        album_id: &str,
+        description: Option<String>,
+        cover_media_id: Option<String>,
+    ) -> Result<AlbumRecord, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+
+        if let Some(cover_media_id) = cover_media_id.as_deref() {
+            let in_album: bool = conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM album_media WHERE album_id = ?1 AND media_id = ?2)",
+                params![album_id, cover_media_id],
+                |row| row.get(0),
+            )?;
+            if !in_album {
+                return Err("封面必须是相册中的照片".into());
+            }
+        }
+
+        let description = description
+            .map(|d| d.trim().to_string())
+            .filter(|d| !d.is_empty());
+        let updated = conn.execute(
+            "UPDATE albums SET description = ?1, cover_media_id = ?2, updated_at = ?3
+             WHERE id = ?4",
+            params![
+                description,
+                cover_media_id,
+                Utc::now().to_rfc3339(),
+                album_id
+            ],
+        )?;
+        if updated == 0 {
+            return Err(format!("未找到相册: {}", album_id).into());
+        }
+
+        Ok(find_album(&conn, album_id)?.ok_or_else(|| format!("未找到相册: {}", album_id))?)
+    }
+
+    /// 将相册移动到指定文件夹（None 为顶层）的指定位置
+    pub async fn move_to(
+        &self,
+        album_id: &str,
+        parent_id: Option<String>,
+        position: usize,
+    ) -> Result<AlbumRecord, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+        let tx = conn.unchecked_transaction()?;
+
+        let album =
+            find_album(&tx, album_id)?.ok_or_else(|| format!("未找到相册: {}", album_id))?;
+
+        if let Some(parent_id) = parent_id.as_deref() {
+            ensure_folder(&tx, parent_id)?;
+            // 不能移动到自身或自己的子文件夹中
+            let mut ancestor = Some(parent_id.to_string());
+            while let Some(current) = ancestor {
+                if current == album_id {
+                    return Err("不能将文件夹移动到其自身或子文件夹中".into());
+                }
+                ancestor = tx
+                    .query_row(
+                        "SELECT parent_id FROM albums WHERE id = ?1",
+                        params![current],
+                        |row| row.get::<_, Option<String>>(0),
+                    )
+                    .optional()?
+                    .flatten();
+            }
+        }
+
+        let old_parent = album.parent_id.map(|id| id.to_string());
+        tx.execute(
+            "UPDATE albums SET parent_id = ?1, updated_at = ?2 WHERE id = ?3",
+            params![parent_id, Utc::now().to_rfc3339(), album_id],
+        )?;
+
+        let mut siblings = sibling_ids(&tx, parent_id.as_deref(), album_id)?;
+        siblings.insert(position.min(siblings.len()), album_id.to_string());
+        renumber_albums(&tx, &siblings)?;
+        if old_parent != parent_id {
+            renumber_albums(&tx, &sibling_ids(&tx, old_parent.as_deref(), album_id)?)?;
+        }
+
+        let moved =
+            find_album(&tx, album_id)?.ok_or_else(|| format!("未找到相册: {}", album_id))?;
+        tx.commit()?;
+
+        Ok(moved)
+    }
+
+    /// 删除相册；删除文件夹时其中的相册移动到上一级
+    pub async fn delete(&self, album_id: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+        let tx = conn.unchecked_transaction()?;
+
+        let album =
+            find_album(&tx, album_id)?.ok_or_else(|| format!("未找到相册: {}", album_id))?;
+        let parent_id = album.parent_id.map(|id| id.to_string());
+
+        tx.execute(
+            "UPDATE albums SET parent_id = ?1 WHERE parent_id = ?2",
+            params![parent_id, album_id],
+        )?;
+        // 相册内的媒体关联通过外键级联删除，媒体本身不受影响
+        tx.execute("DELETE FROM albums WHERE id = ?1", params![album_id])?;
+        renumber_albums(&tx, &sibling_ids(&tx, parent_id.as_deref(), album_id)?)?;
+
+        tx.commit()?;
+
+        info!("已删除相册: {}", album.name);
+        Ok(())
+    }
+
+    /// 按相册内的手动顺序获取媒体
+    pub async fn find_media(
+        &self,
+        album_id: &str,
+    ) -> Result<Vec<MediaRecord>, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM album_media
+             JOIN media_records ON media_records.id = album_media.media_id
+             WHERE album_media.album_id = ?1 AND media_records.trashed_at IS NULL
+             ORDER BY album_media.position",
+            MEDIA_COLUMNS
+        ))?;
+        let records = stmt
+            .query_map(params![album_id], map_media_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(records)
+    }
+
+    /// 将媒体加入相册，插入到指定位置（默认末尾），已在相册中的媒体保持原位
+    pub async fn add_media(
+        &self,
+        album_id: &str,
+        media_ids: &[String],
+        position: Option<usize>,
+    ) -> Result<usize, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+        let tx = conn.unchecked_transaction()?;
+
+        let album =
+            find_album(&tx, album_id)?.ok_or_else(|| format!("未找到相册: {}", album_id))?;
+        if album.is_folder {
+            return Err("文件夹中不能直接添加照片".into());
+        }
+
+        let before = album_media_state(&tx, album_id)?;
+        let mut order = album_media_ids(&tx, album_id)?;
+        let existing: std::collections::HashSet<String> = order.iter().cloned().collect();
+        let mut new_ids: Vec<String> = Vec::new();
+        for media_id in media_ids {
+            let exists: bool = tx.query_row(
+                "SELECT EXISTS(SELECT 1 FROM media_records WHERE id = ?1)",
+                params![media_id],
+                |row| row.get(0),
+            )?;
+            if exists && !existing.contains(media_id) && !new_ids.contains(media_id) {
+                new_ids.push(media_id.clone());
+            }
+        }
+
+        let now = Utc::now().to_rfc3339();
+        for media_id in &new_ids {
+            tx.execute(
+                "INSERT INTO album_media (album_id, media_id, position, added_at)
+                 VALUES (?1, ?2, 0, ?3)",
+                params![album_id, media_id, now],
+            )?;
+        }
+
+        let insert_at = match position {
+            Some(position) => visible_insert_index(&tx, album_id, &order, position)?,
+            None => order.len(),
+        };
+        order.splice(insert_at..insert_at, new_ids.iter().cloned());
+        renumber_album_media(&tx, album_id, &order)?;
+        touch_album(&tx, album_id)?;
+        if let Some(before) = before {
+            record_album_media_change(&tx, "添加到相册", album_id, before)?;
+        }
+
+        tx.commit()?;
+
+        info!("已向相册 {} 添加 {} 个媒体", album.name, new_ids.len());
+        Ok(new_ids.len())
+    }
+
+    /// 从相册移除媒体
+    pub async fn remove_media(
+        &self,
+        album_id: &str,
+        media_ids: &[String],
+    ) -> Result<usize, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+        let tx = conn.unchecked_transaction()?;
+        let before = album_media_state(&tx, album_id)?;
+
+        let mut removed = 0;
+        for media_id in media_ids {
+            removed += tx.execute(
+                "DELETE FROM album_media WHERE album_id = ?1 AND media_id = ?2",
+                params![album_id, media_id],
+            )?;
+        }
+        // 移除的媒体如果是封面则清空封面
+        tx.execute(
+            "UPDATE albums SET cover_media_id = NULL WHERE id = ?1 AND cover_media_id IS NOT NULL
+             AND cover_media_id NOT IN (SELECT media_id FROM album_media WHERE album_id = ?1)",
+            params![album_id],
+        )?;
+
+        renumber_album_media(&tx, album_id, &album_media_ids(&tx, album_id)?)?;
+        touch_album(&tx, album_id)?;
+        if let Some(before) = before {
+            record_album_media_change(&tx, "从相册移除", album_id, before)?;
+        }
+        tx.commit()?;
+
+        Ok(removed)
+    }
+
+    /// 拖拽排序：将选中的媒体按给定顺序移动到目标位置
+    ///
+    /// `target_index` 为移除选中媒体后剩余可见（未在回收站中）序列中的插入位置。
+    pub async fn reorder_media(
+        &self,
+        album_id: &str,
+        media_ids: &[String],
+        target_index: usize,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+        let tx = conn.unchecked_transaction()?;
+        let before = album_media_state(&tx, album_id)?;
+
+        let order = album_media_ids(&tx, album_id)?;
+        let moving: Vec<String> = media_ids
+            .iter()
+            .filter(|id| order.contains(id))
+            .cloned()
+            .collect();
+        let mut remaining: Vec<String> = order
+            .into_iter()
+            .filter(|id| !moving.contains(id))
+            .collect();
+
+        let insert_at = visible_insert_index(&tx, album_id, &remaining, target_index)?;
+        remaining.splice(insert_at..insert_at, moving);
+        renumber_album_media(&tx, album_id, &remaining)?;
+        touch_album(&tx, album_id)?;
+        if let Some(before) = before {
+            record_album_media_change(&tx, "调整相册排序", album_id, before)?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+fn validate_name(name: &str) -> Result<&str, Box<dyn std::error::Error>> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("相册名称不能为空".into());
+    }
+    Ok(name)
+}
+
+/// 确认目标存在且为文件夹
+fn ensure_folder(conn: &Connection, album_id: &str) -> Result<(), Box<dyn std::error::Error>> {
+    match find_album(conn, album_id)? {
+        Some(album) if album.is_folder => Ok(()),
+        Some(album) => Err(format!("{} 不是文件夹", album.name).into()),
+        None => Err(format!("未找到文件夹: {}", album_id).into()),
+    }
+}
+
+fn touch_album(conn: &Connection, album_id: &str) -> rusqlite::Result<usize> {
+    conn.execute(
+        "UPDATE albums SET updated_at = ?1 WHERE id = ?2",
+        params![Utc::now().to_rfc3339(), album_id],
+    )
+}
+
+/// 同一文件夹下除 exclude_id 以外的相册，按当前顺序排列
+fn sibling_ids(
+    conn: &Connection,
+    parent_id: Option<&str>,
+    exclude_id: &str,
+) -> rusqlite::Result<Vec<String>> {
+    let mut stmt = conn.prepare(
+        "SELECT id FROM albums WHERE parent_id IS ?1 AND id != ?2 ORDER BY position, name",
+    )?;
+    let ids = stmt
+        .query_map(params![parent_id, exclude_id], |row| row.get(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(ids)
+}
+
+fn renumber_albums(conn: &Connection, ids: &[String]) -> rusqlite::Result<()> {
+    let mut stmt = conn.prepare("UPDATE albums SET position = ?1 WHERE id = ?2")?;
+    for (position, id) in ids.iter().enumerate() {
+        stmt.execute(params![position as i64, id])?;
+    }
+    Ok(())
+}
+
+/// 相册内媒体的当前顺序
+fn album_media_ids(conn: &Connection, album_id: &str) -> rusqlite::Result<Vec<String>> {
+    let mut stmt = conn.prepare(
+        "SELECT media_id FROM album_media WHERE album_id = ?1 ORDER BY position, added_at",
+    )?;
+    let ids = stmt
+        .query_map(params![album_id], |row| row.get(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(ids)
+}
+
+/// 将界面上可见媒体（不含回收站中的媒体）的插入位置换算为 order 中的位置
+///
+/// 插入到第 index 个可见媒体之前；超出可见数量时追加到末尾。
+fn visible_insert_index(
+    conn: &Connection,
+    album_id: &str,
+    order: &[String],
+    index: usize,
+) -> rusqlite::Result<usize> {
+    let mut stmt = conn.prepare(
+        "SELECT am.media_id FROM album_media am JOIN media_records m ON m.id = am.media_id
+         WHERE am.album_id = ?1 AND m.trashed_at IS NOT NULL",
+    )?;
+    let trashed = stmt
+        .query_map(params![album_id], |row| row.get::<_, String>(0))?
+        .collect::<Result<std::collections::HashSet<_>, _>>()?;
+
+    Ok(order
+        .iter()
+        .enumerate()
+        .filter(|(_, id)| !trashed.contains(*id))
+        .nth(index)
+        .map_or(order.len(), |(position, _)| position))
+}
+
+/// 相册中媒体的当前顺序和封面，相册不存在时返回 None
+fn album_media_state(
+    conn: &Connection,
+    album_id: &str,
+) -> rusqlite::Result<Option<AlbumMediaState>> {
+    let cover_media_id: Option<Option<String>> = conn
+        .query_row(
+            "SELECT cover_media_id FROM albums WHERE id = ?1",
+            params![album_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    cover_media_id
+        .map(|cover_media_id| {
+            Ok(AlbumMediaState {
+                media_ids: album_media_ids(conn, album_id)?,
+                cover_media_id,
+            })
+        })
+        .transpose()
+}
+
+/// 相册中的媒体与 `before` 不同时记录到操作日志
+fn record_album_media_change(
+    conn: &Connection,
+    label: &str,
+    album_id: &str,
+    before: AlbumMediaState,
+) -> rusqlite::Result<()> {
+    let Some(after) = album_media_state(conn, album_id)? else {
+        return Ok(());
+    };
+    if after == before {
+        return Ok(());
+    }
+
+    record_journal(
+        conn,
+        label,
+        &[JournalChange::AlbumMedia {
+            album_id: album_id.to_string(),
+            before,
+            after,
+        }],
+    )
+}
+
+/// 将相册中的媒体恢复为指定的顺序和封面，相册或媒体已被删除时跳过
+pub(crate) fn apply_album_media_state(
+    conn: &Connection,
+    album_id: &str,
+    state: &AlbumMediaState,
+) -> rusqlite::Result<()> {
+    let album_exists: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM albums WHERE id = ?1)",
+        params![album_id],
+        |row| row.get(0),
+    )?;
+    if !album_exists {
+        return Ok(());
+    }
+
+    let mut media_ids = Vec::new();
+    for media_id in &state.media_ids {
+        let exists: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM media_records WHERE id = ?1)",
+            params![media_id],
+            |row| row.get(0),
+        )?;
+        if exists {
+            media_ids.push(media_id.clone());
+        }
+    }
+
+    for media_id in album_media_ids(conn, album_id)? {
+        if !media_ids.contains(&media_id) {
+            conn.execute(
+                "DELETE FROM album_media WHERE album_id = ?1 AND media_id = ?2",
+                params![album_id, media_id],
+            )?;
+        }
+    }
+    let now = Utc::now().to_rfc3339();
+    for media_id in &media_ids {
+        conn.execute(
+            "INSERT OR IGNORE INTO album_media (album_id, media_id, position, added_at)
+             VALUES (?1, ?2, 0, ?3)",
+            params![album_id, media_id, now],
+        )?;
+    }
+    renumber_album_media(conn, album_id, &media_ids)?;
+
+    let cover_media_id = state
+        .cover_media_id
+        .as_ref()
+        .filter(|media_id| media_ids.contains(media_id));
+    conn.execute(
+        "UPDATE albums SET cover_media_id = ?1 WHERE id = ?2",
+        params![cover_media_id, album_id],
+    )?;
+    touch_album(conn, album_id)?;
+
+    Ok(())
+}
+
+fn renumber_album_media(
+    conn: &Connection,
+    album_id: &str,
+    media_ids: &[String],
+) -> rusqlite::Result<()> {
+    let mut stmt =
+        conn.prepare("UPDATE album_media SET position = ?1 WHERE album_id = ?2 AND media_id = ?3")?;
+    for (position, media_id) in media_ids.iter().enumerate() {
+        stmt.execute(params![position as i64, album_id, media_id])?;
+    }
+    Ok(())
+}
+
+fn find_album(conn: &Connection, album_id: &str) -> rusqlite::Result<Option<AlbumRecord>> {
+    conn.query_row(
+        &format!("SELECT {} FROM albums a WHERE a.id = ?1", ALBUM_COLUMNS),
+        params![album_id],
+        map_album_row,
+    )
+    .optional()
+}
+
+/// 将一行查询结果解析为相册记录，列顺序与 `ALBUM_COLUMNS` 一致
+fn map_album_row(row: &rusqlite::Row) -> rusqlite::Result<AlbumRecord> {
+    Ok(AlbumRecord {
+        id: parse_uuid_column(0, row.get(0)?)?,
+        name: row.get(1)?,
+        description: row.get(2)?,
+        parent_id: row
+            .get::<_, Option<String>>(3)?
+            .map(|id| parse_uuid_column(3, id))
+            .transpose()?,
+        is_folder: row.get(4)?,
+        cover_media_id: row
+            .get::<_, Option<String>>(5)?
+            .map(|id| parse_uuid_column(5, id))
+            .transpose()?,
+        cover_thumbnail_path: row.get(6)?,
+        position: row.get::<_, i64>(7)? as u32,
+        media_count: row.get::<_, i64>(8)? as u64,
+        created_at: parse_time_column(9, row.get(9)?)?,
+        updated_at: parse_time_column(10, row.get(10)?)?,
+    })
+}
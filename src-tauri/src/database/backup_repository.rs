@@ -0,0 +1,393 @@
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Duration, Local, Utc};
+use log::{info, warn};
+use rusqlite::{backup::Progress, Connection, DatabaseName, OpenFlags};
+use tauri::{path::BaseDirectory, AppHandle, Manager};
+
+use crate::database::migrations::{latest_version, run_migrations};
+use crate::database::relink_repository::find_missing_media;
+use crate::database::trash_repository::TrashRepository;
+use crate::database::{open_connection, read_setting, write_setting};
+use crate::models::backup::{BackupInfo, BackupSettings, IntegrityReport};
+use crate::models::trash::TrashFileAction;
+use crate::utils::image_processor::get_thumbnail_dir;
+
+/// 备份目录（相对应用数据目录）
+const BACKUP_DIR: &str = "backups";
+
+/// 备份文件名前缀，备份目录中只有以此开头的 .db 文件被视为备份
+const BACKUP_PREFIX: &str = "lumen-";
+
+const BACKUP_EXTENSION: &str = "db";
+
+/// 恢复前自动备份当前数据库时使用的文件名后缀
+const BEFORE_RESTORE_SUFFIX: &str = "-before-restore";
+
+/// 自动备份间隔和保留数量的设置项
+const INTERVAL_SETTING: &str = "backup.interval_hours";
+const KEEP_COUNT_SETTING: &str = "backup.keep_count";
+
+const DEFAULT_INTERVAL_HOURS: u32 = 24;
+const DEFAULT_KEEP_COUNT: u32 = 7;
+
+/// SQLite 数据库备份与完整性检查
+pub struct BackupRepository {
+    app: AppHandle,
+}
+
+impl BackupRepository {
+    pub fn new(app: AppHandle) -> Self {
+        Self { app }
+    }
+
+    /// 获取数据库连接
+    fn get_connection(&self) -> Result<Connection, Box<dyn std::error::Error>> {
+        open_connection(&self.app)
+    }
+
+    fn backup_dir(&self) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        Ok(self
+            .app
+            .path()
+            .resolve(BACKUP_DIR, BaseDirectory::AppLocalData)?)
+    }
+
+    /// 立即备份数据库，完成后按保留数量删除最早的备份
+    pub async fn create_backup(&self) -> Result<BackupInfo, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+        let backup_dir = self.backup_dir()?;
+
+        let backup = backup_database(&conn, &backup_dir, "")?;
+        rotate_backups(&backup_dir, read_settings(&conn)?.keep_count, None)?;
+
+        Ok(backup)
+    }
+
+    /// 距离最近一次备份超过自动备份间隔时备份数据库，返回新建的备份
+    pub async fn backup_if_due(&self) -> Result<Option<BackupInfo>, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+        let settings = read_settings(&conn)?;
+        if settings.interval_hours == 0 {
+            return Ok(None);
+        }
+
+        let backup_dir = self.backup_dir()?;
+        if let Some(latest) = list_backups(&backup_dir)?.first() {
+            if Utc::now() - latest.created_at < Duration::hours(settings.interval_hours as i64) {
+                return Ok(None);
+            }
+        }
+
+        let backup = backup_database(&conn, &backup_dir, "")?;
+        rotate_backups(&backup_dir, settings.keep_count, None)?;
+
+        Ok(Some(backup))
+    }
+
+    /// 获取所有备份，最新的在前
+    pub async fn find_backups(&self) -> Result<Vec<BackupInfo>, Box<dyn std::error::Error>> {
+        list_backups(&self.backup_dir()?)
+    }
+
+    /// 从备份恢复数据库
+    ///
+    /// 备份需通过完整性检查且不能来自更新版本的 Lumen。恢复前先备份当前数据库，
+    /// 再通过在线备份 API 整体替换数据库内容，最后执行备份中尚未应用的迁移。
+    pub async fn restore(&self, file_name: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let backup_dir = self.backup_dir()?;
+        let source = backup_dir.join(file_name);
+        if Path::new(file_name).file_name() != Some(file_name.as_ref()) || !source.is_file() {
+            return Err(format!("未找到备份: {}", file_name).into());
+        }
+        validate_backup(&source)?;
+
+        let mut conn = self.get_connection()?;
+        backup_database(&conn, &backup_dir, BEFORE_RESTORE_SUFFIX)?;
+        conn.restore(DatabaseName::Main, &source, None::<fn(Progress)>)?;
+        run_migrations(&conn)?;
+        // 恢复所用的备份不参与轮换，避免保留数量较小时被删除
+        rotate_backups(
+            &backup_dir,
+            read_settings(&conn)?.keep_count,
+            Some(file_name),
+        )?;
+
+        info!("已从备份恢复数据库: {}", file_name);
+        Ok(())
+    }
+
+    /// 获取自动备份设置
+    pub async fn settings(&self) -> Result<BackupSettings, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+
+        Ok(read_settings(&conn)?)
+    }
+
+    /// 保存自动备份设置，保留数量至少为 1
+    pub async fn set_settings(
+        &self,
+        settings: &BackupSettings,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if settings.keep_count == 0 {
+            return Err("至少需要保留 1 个备份".into());
+        }
+
+        let conn = self.get_connection()?;
+        let tx = conn.unchecked_transaction()?;
+        write_setting(&tx, INTERVAL_SETTING, &settings.interval_hours.to_string())?;
+        write_setting(&tx, KEEP_COUNT_SETTING, &settings.keep_count.to_string())?;
+        tx.commit()?;
+
+        info!(
+            "自动备份设置已更新：每 {} 小时，保留 {} 个",
+            settings.interval_hours, settings.keep_count
+        );
+        Ok(())
+    }
+
+    /// 检查媒体库完整性，`repair` 为 true 时同时修复
+    ///
+    /// 修复会重建索引、删除孤立的缩略图、将原文件已不存在的媒体移入回收站（不处理文件，可以恢复），
+    /// 并清除无效的 JSON 数据；重建索引后仍存在的数据库错误需从备份恢复。
+    pub async fn check_integrity(
+        &self,
+        repair: bool,
+    ) -> Result<IntegrityReport, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+
+        if repair {
+            conn.execute_batch("REINDEX")?;
+        }
+        let report = IntegrityReport {
+            database_errors: integrity_errors(&conn)?,
+            orphaned_thumbnails: find_orphaned_thumbnails(&conn, &get_thumbnail_dir(&self.app))?,
+            missing_media: find_missing_media(&conn)?
+                .into_iter()
+                .filter(|media| !media.offline)
+                .collect(),
+            invalid_json_media_ids: find_invalid_json(&conn)?,
+            repaired: repair,
+        };
+
+        if repair {
+            for thumbnail in &report.orphaned_thumbnails {
+                if let Err(e) = fs::remove_file(thumbnail) {
+                    warn!("删除孤立的缩略图失败 {}: {}", thumbnail, e);
+                }
+            }
+
+            conn.execute_batch(
+                "UPDATE media_records SET tags = NULL WHERE tags IS NOT NULL AND json_valid(tags) = 0;
+                 UPDATE media_records SET album_ids = NULL
+                 WHERE album_ids IS NOT NULL AND json_valid(album_ids) = 0;",
+            )?;
+            drop(conn);
+
+            let missing_ids: Vec<String> = report
+                .missing_media
+                .iter()
+                .map(|media| media.media_id.clone())
+                .collect();
+            TrashRepository::new(self.app.clone())
+                .trash(&missing_ids, TrashFileAction::Keep, |_| {})
+                .await?;
+        }
+
+        info!(
+            "完整性检查完成：数据库错误 {} 个，孤立缩略图 {} 个，丢失文件 {} 个，无效数据 {} 个",
+            report.database_errors.len(),
+            report.orphaned_thumbnails.len(),
+            report.missing_media.len(),
+            report.invalid_json_media_ids.len()
+        );
+        Ok(report)
+    }
+}
+
+fn read_settings(conn: &Connection) -> rusqlite::Result<BackupSettings> {
+    let read = |key: &str, default: u32| -> rusqlite::Result<u32> {
+        Ok(read_setting(conn, key)?
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(default))
+    };
+
+    Ok(BackupSettings {
+        interval_hours: read(INTERVAL_SETTING, DEFAULT_INTERVAL_HOURS)?,
+        keep_count: read(KEEP_COUNT_SETTING, DEFAULT_KEEP_COUNT)?,
+    })
+}
+
+/// 使用在线备份 API 备份数据库，先写入临时文件，完成后再重命名，不会留下不完整的备份
+fn backup_database(
+    conn: &Connection,
+    backup_dir: &Path,
+    suffix: &str,
+) -> Result<BackupInfo, Box<dyn std::error::Error>> {
+    fs::create_dir_all(backup_dir)?;
+
+    let stem = format!(
+        "{}{}{}",
+        BACKUP_PREFIX,
+        Local::now().format("%Y%m%d-%H%M%S"),
+        suffix
+    );
+    let mut path = backup_dir.join(format!("{}.{}", stem, BACKUP_EXTENSION));
+    let mut index = 1;
+    while path.exists() {
+        path = backup_dir.join(format!("{}-{}.{}", stem, index, BACKUP_EXTENSION));
+        index += 1;
+    }
+
+    let temp_path = path.with_extension("tmp");
+    conn.backup(DatabaseName::Main, &temp_path, None)?;
+    fs::rename(&temp_path, &path)?;
+
+    let backup = backup_info(&path)?;
+    info!("数据库已备份到 {}（{} 字节）", backup.path, backup.size);
+    Ok(backup)
+}
+
+fn backup_info(path: &Path) -> Result<BackupInfo, Box<dyn std::error::Error>> {
+    let metadata = fs::metadata(path)?;
+
+    Ok(BackupInfo {
+        file_name: path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        path: path.to_string_lossy().into_owned(),
+        size: metadata.len(),
+        created_at: DateTime::<Utc>::from(metadata.modified()?),
+    })
+}
+
+/// 备份目录中的所有备份，最新的在前，目录不存在时为空
+fn list_backups(backup_dir: &Path) -> Result<Vec<BackupInfo>, Box<dyn std::error::Error>> {
+    let Ok(entries) = fs::read_dir(backup_dir) else {
+        return Ok(Vec::new());
+    };
+
+    let mut backups = Vec::new();
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let path = entry.path();
+        let is_backup = path.extension().is_some_and(|ext| ext == BACKUP_EXTENSION)
+            && entry
+                .file_name()
+                .to_string_lossy()
+                .starts_with(BACKUP_PREFIX);
+        if is_backup && path.is_file() {
+            backups.push(backup_info(&path)?);
+        }
+    }
+    backups.sort_by(|a, b| {
+        b.created_at
+            .cmp(&a.created_at)
+            .then_with(|| b.file_name.cmp(&a.file_name))
+    });
+
+    Ok(backups)
+}
+
+/// 只保留最新的 `keep_count` 个备份，`exclude` 指定的备份不计数也不删除
+fn rotate_backups(
+    backup_dir: &Path,
+    keep_count: u32,
+    exclude: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    for backup in list_backups(backup_dir)?
+        .iter()
+        .filter(|backup| Some(backup.file_name.as_str()) != exclude)
+        .skip(keep_count.max(1) as usize)
+    {
+        match fs::remove_file(&backup.path) {
+            Ok(()) => info!("已删除过期的备份: {}", backup.file_name),
+            Err(e) => warn!("删除过期的备份失败 {}: {}", backup.path, e),
+        }
+    }
+
+    Ok(())
+}
+
+/// 确认备份文件完整、是 Lumen 的数据库且不来自更新的版本
+fn validate_backup(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+
+    let errors = integrity_errors(&conn)?;
+    if !errors.is_empty() {
+        return Err(format!("备份文件已损坏: {}", errors.join("; ")).into());
+    }
+
+    let has_media: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'media_records')",
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_media {
+        return Err("不是有效的媒体库备份".into());
+    }
+
+    let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if version > latest_version() {
+        return Err("备份来自更新版本的 Lumen，无法恢复".into());
+    }
+
+    Ok(())
+}
+
+/// `PRAGMA integrity_check` 报告的问题，数据库正常时为空
+fn integrity_errors(conn: &Connection) -> rusqlite::Result<Vec<String>> {
+    let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+    let rows = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(rows.into_iter().filter(|row| row != "ok").collect())
+}
+
+/// 缩略图目录中没有被任何媒体（含回收站）引用的文件
+fn find_orphaned_thumbnails(
+    conn: &Connection,
+    thumbnail_dir: &Path,
+) -> rusqlite::Result<Vec<String>> {
+    let referenced: HashSet<PathBuf> = {
+        let mut stmt = conn.prepare(
+            "SELECT DISTINCT thumbnail_path FROM media_records WHERE thumbnail_path IS NOT NULL",
+        )?;
+        let paths = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        paths.into_iter().map(PathBuf::from).collect()
+    };
+
+    let Ok(entries) = fs::read_dir(thumbnail_dir) else {
+        return Ok(Vec::new());
+    };
+    let mut orphaned: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && !referenced.contains(path))
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect();
+    orphaned.sort();
+
+    Ok(orphaned)
+}
+
+/// tags 或 album_ids 中不是有效 JSON 的媒体
+fn find_invalid_json(conn: &Connection) -> rusqlite::Result<Vec<String>> {
+    let mut stmt = conn.prepare(
+        "SELECT id FROM media_records
+         WHERE (tags IS NOT NULL AND json_valid(tags) = 0)
+            OR (album_ids IS NOT NULL AND json_valid(album_ids) = 0)",
+    )?;
+    let ids = stmt
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(ids)
+}
@@ -0,0 +1,199 @@
+use std::{
+    fs,
+    io::{Cursor, Write},
+    path::{Path, PathBuf},
+};
+
+use image::{codecs::jpeg::JpegEncoder, RgbImage};
+use log::{info, warn};
+use rusqlite::Connection;
+use tauri::AppHandle;
+
+use crate::database::gallery_repository::{exif_summary, find_media};
+use crate::database::open_connection;
+use crate::models::contact_sheet::{ContactSheetFormat, ContactSheetOptions, ContactSheetSummary};
+use crate::models::export::ExportFailure;
+use crate::models::image::MediaKind;
+use crate::utils::contact_sheet::{render_sheet, SheetCell, SheetLayout};
+use crate::utils::file_ops::numbered_path;
+use crate::utils::image_export::load_oriented_image;
+use crate::utils::pdf_writer::{write_image_pdf, PdfPage};
+use crate::utils::text_render::load_font;
+
+/// 小样页面的 JPEG 质量
+const SHEET_QUALITY: u8 = 90;
+
+/// SQLite 小样导出仓库实现
+pub struct ContactSheetRepository {
+    app: AppHandle,
+}
+
+impl ContactSheetRepository {
+    pub fn new(app: AppHandle) -> Self {
+        Self { app }
+    }
+
+    /// 获取数据库连接
+    fn get_connection(&self) -> Result<Connection, Box<dyn std::error::Error>> {
+        open_connection(&self.app)
+    }
+
+    /// 将选中的照片排成带文件名、评分和可选拍摄参数的网格，生成多页 PDF 或每页一个 JPEG
+    ///
+    /// 视频等非图片媒体及读取失败的照片被跳过，不会覆盖已有文件。
+    /// 每处理一张照片调用一次 `on_progress`，参数为已处理的数量和总数。
+    pub async fn export(
+        &self,
+        options: &ContactSheetOptions,
+        on_progress: impl Fn(usize, usize),
+    ) -> Result<ContactSheetSummary, Box<dyn std::error::Error>> {
+        let target = Path::new(&options.target);
+        if !target.is_absolute() {
+            return Err(format!("目标路径必须是绝对路径: {}", options.target).into());
+        }
+        if target.exists() {
+            return Err(format!("文件已存在: {}", options.target).into());
+        }
+        let layout = SheetLayout::new(options)?;
+        let font = load_font(&options.font_path)?;
+
+        let conn = self.get_connection()?;
+        let records = find_media(&conn, &options.media_ids)?;
+
+        let mut summary = ContactSheetSummary::default();
+        let mut pages: Vec<(Vec<u8>, u32, u32)> = Vec::new();
+        let mut cells = Vec::new();
+        for (index, record) in records.iter().enumerate() {
+            let image = if matches!(record.kind, MediaKind::Image) {
+                load_oriented_image(Path::new(&record.path))
+            } else {
+                Err("不支持的媒体类型".to_string())
+            };
+
+            match image {
+                Ok(image) => {
+                    cells.push(SheetCell {
+                        image: image.thumbnail(layout.cell_width, layout.cell_width),
+                        name: record.name.clone(),
+                        rating: record.rating,
+                        exif: exif_summary(record),
+                    });
+                    summary.photos += 1;
+                }
+                Err(e) => {
+                    warn!("跳过 {}: {}", record.path, e);
+                    summary.skipped.push(ExportFailure {
+                        media_id: record.id.to_string(),
+                        path: record.path.clone(),
+                        error: e,
+                    });
+                }
+            }
+            if cells.len() == layout.per_page() || (index + 1 == records.len() && !cells.is_empty())
+            {
+                let sheet = render_sheet(
+                    &layout,
+                    &font,
+                    &cells,
+                    options.title.as_deref(),
+                    pages.len(),
+                );
+                pages.push(encode_sheet(&sheet)?);
+                cells.clear();
+            }
+            on_progress(index + 1, records.len());
+        }
+        if pages.is_empty() {
+            return Err("没有可以加入小样的照片".into());
+        }
+
+        summary.pages = pages.len();
+        summary.files = match options.format {
+            ContactSheetFormat::Pdf => {
+                let (width, height) = layout.page_size_points();
+                let pages: Vec<PdfPage> = pages
+                    .into_iter()
+                    .map(|(jpeg, width, height)| PdfPage {
+                        jpeg,
+                        width,
+                        height,
+                    })
+                    .collect();
+                write_new_file(target, &write_image_pdf(&pages, width, height))?;
+                vec![options.target.clone()]
+            }
+            ContactSheetFormat::Jpeg => write_jpeg_pages(target, &pages)?,
+        };
+
+        info!(
+            "已生成小样 {}：{} 张照片，{} 页，跳过 {} 个",
+            options.target,
+            summary.photos,
+            summary.pages,
+            summary.skipped.len()
+        );
+        Ok(summary)
+    }
+}
+
+/// 编码为 JPEG，返回数据和像素尺寸
+fn encode_sheet(sheet: &RgbImage) -> Result<(Vec<u8>, u32, u32), String> {
+    let mut buf = Cursor::new(Vec::new());
+    JpegEncoder::new_with_quality(&mut buf, SHEET_QUALITY)
+        .encode_image(sheet)
+        .map_err(|e| format!("编码小样失败: {}", e))?;
+
+    Ok((buf.into_inner(), sheet.width(), sheet.height()))
+}
+
+/// 写入每页的 JPEG，只有一页时使用目标路径，多页时在文件名后加页码
+///
+/// 任何一个文件已存在时不写入；写入中途失败时删除已写入的文件。
+fn write_jpeg_pages(
+    target: &Path,
+    pages: &[(Vec<u8>, u32, u32)],
+) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let paths: Vec<PathBuf> = if pages.len() == 1 {
+        vec![target.to_path_buf()]
+    } else {
+        (1..=pages.len())
+            .map(|page| numbered_path(target, page))
+            .collect()
+    };
+    if let Some(path) = paths.iter().find(|path| path.exists()) {
+        return Err(format!("文件已存在: {}", path.display()).into());
+    }
+
+    for (index, (path, (jpeg, _, _))) in paths.iter().zip(pages).enumerate() {
+        if let Err(e) = write_new_file(path, jpeg) {
+            for written in &paths[..index] {
+                let _ = fs::remove_file(written);
+            }
+            return Err(e);
+        }
+    }
+
+    Ok(paths
+        .iter()
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect())
+}
+
+/// 写入新文件，文件已存在时返回错误，写入失败时删除不完整的文件
+fn write_new_file(path: &Path, data: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .map_err(|e| format!("无法创建 {}: {}", path.display(), e))?;
+
+    if let Err(e) = file.write_all(data).and_then(|_| file.sync_all()) {
+        drop(file);
+        let _ = fs::remove_file(path);
+        return Err(format!("写入 {} 失败: {}", path.display(), e).into());
+    }
+    Ok(())
+}
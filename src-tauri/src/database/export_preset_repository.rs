@@ -0,0 +1,146 @@
+use chrono::Utc;
+use log::info;
+use rusqlite::{params, Connection, OptionalExtension};
+use tauri::AppHandle;
+use uuid::Uuid;
+
+use crate::database::export_repository::validate_export_settings;
+use crate::database::{open_connection, parse_time_column, parse_uuid_column};
+use crate::models::export::{ExportPresetRecord, ExportSettings};
+
+/// SQLite 导出预设仓库实现
+pub struct ExportPresetRepository {
+    app: AppHandle,
+}
+
+impl ExportPresetRepository {
+    pub fn new(app: AppHandle) -> Self {
+        Self { app }
+    }
+
+    /// 获取数据库连接
+    fn get_connection(&self) -> Result<Connection, Box<dyn std::error::Error>> {
+        open_connection(&self.app)
+    }
+
+    /// 获取所有导出预设，按名称排序
+    pub async fn find_all(&self) -> Result<Vec<ExportPresetRecord>, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT id, name, settings, created_at, updated_at
+             FROM export_presets ORDER BY name",
+        )?;
+        let presets = stmt
+            .query_map([], map_export_preset_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(presets)
+    }
+
+    /// 保存导出预设，设置必须能通过检查
+    pub async fn create(
+        &self,
+        name: &str,
+        settings: &ExportSettings,
+    ) -> Result<ExportPresetRecord, Box<dyn std::error::Error>> {
+        let name = validate_name(name)?;
+        validate_export_settings(settings)?;
+
+        let conn = self.get_connection()?;
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO export_presets (id, name, settings, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?4)",
+            params![id, name, serde_json::to_string(settings)?, now],
+        )?;
+
+        let preset = find_export_preset(&conn, &id)?.ok_or("保存导出预设后未能读取记录")?;
+        info!("已保存导出预设: {}", preset.name);
+        Ok(preset)
+    }
+
+    /// 修改导出预设的名称和/或设置
+    pub async fn update(
+        &self,
+        preset_id: &str,
+        name: Option<&str>,
+        settings: Option<&ExportSettings>,
+    ) -> Result<ExportPresetRecord, Box<dyn std::error::Error>> {
+        let name = name.map(validate_name).transpose()?;
+        let settings = match settings {
+            Some(settings) => {
+                validate_export_settings(settings)?;
+                Some(serde_json::to_string(settings)?)
+            }
+            None => None,
+        };
+
+        let conn = self.get_connection()?;
+        let updated = conn.execute(
+            "UPDATE export_presets SET name = COALESCE(?1, name), settings = COALESCE(?2, settings),
+                                       updated_at = ?3
+             WHERE id = ?4",
+            params![name, settings, Utc::now().to_rfc3339(), preset_id],
+        )?;
+        if updated == 0 {
+            return Err(format!("未找到导出预设: {}", preset_id).into());
+        }
+
+        let preset = find_export_preset(&conn, preset_id)?
+            .ok_or_else(|| format!("未找到导出预设: {}", preset_id))?;
+        Ok(preset)
+    }
+
+    /// 删除导出预设
+    pub async fn delete(&self, preset_id: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+
+        let deleted = conn.execute(
+            "DELETE FROM export_presets WHERE id = ?1",
+            params![preset_id],
+        )?;
+        if deleted == 0 {
+            return Err(format!("未找到导出预设: {}", preset_id).into());
+        }
+
+        Ok(())
+    }
+}
+
+fn validate_name(name: &str) -> Result<&str, Box<dyn std::error::Error>> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("预设名称不能为空".into());
+    }
+    Ok(name)
+}
+
+fn find_export_preset(
+    conn: &Connection,
+    preset_id: &str,
+) -> rusqlite::Result<Option<ExportPresetRecord>> {
+    conn.query_row(
+        "SELECT id, name, settings, created_at, updated_at
+         FROM export_presets WHERE id = ?1",
+        params![preset_id],
+        map_export_preset_row,
+    )
+    .optional()
+}
+
+fn map_export_preset_row(row: &rusqlite::Row) -> rusqlite::Result<ExportPresetRecord> {
+    let settings: String = row.get(2)?;
+    let settings = serde_json::from_str(&settings).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(2, rusqlite::types::Type::Text, Box::new(e))
+    })?;
+
+    Ok(ExportPresetRecord {
+        id: parse_uuid_column(0, row.get(0)?)?,
+        name: row.get(1)?,
+        settings,
+        created_at: parse_time_column(3, row.get(3)?)?,
+        updated_at: parse_time_column(4, row.get(4)?)?,
+    })
+}
@@ -0,0 +1,201 @@
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use log::{info, warn};
+use rusqlite::Connection;
+use tauri::AppHandle;
+
+use crate::database::open_connection;
+use crate::database::rename_repository::{load_media_files, MediaFiles};
+use crate::models::export::{
+    ExportFailure, ExportFormat, ExportSettings, ExportSummary, MetadataPolicy,
+};
+use crate::utils::file_ops::numbered_path;
+use crate::utils::image_export::render_export;
+use crate::utils::path_template::{assign_sequences, render_path_template, TemplateValues};
+use crate::utils::watermark::{validate_border, validate_watermark};
+
+/// SQLite 媒体导出仓库实现
+pub struct ExportRepository {
+    app: AppHandle,
+}
+
+impl ExportRepository {
+    pub fn new(app: AppHandle) -> Self {
+        Self { app }
+    }
+
+    /// 获取数据库连接
+    fn get_connection(&self) -> Result<Connection, Box<dyn std::error::Error>> {
+        open_connection(&self.app)
+    }
+
+    /// 按导出设置将媒体导出到目标文件夹，按拍摄时间排序以便模板中的序号连续
+    ///
+    /// 不会覆盖已有文件，文件名已被占用时加编号。单个文件失败时记录原因后继续。
+    /// 每处理一个文件调用一次 `on_progress`，参数为已处理的数量、总数和当前文件。
+    pub async fn export(
+        &self,
+        media_ids: &[String],
+        target_dir: &str,
+        settings: &ExportSettings,
+        on_progress: impl Fn(usize, usize, &str),
+    ) -> Result<ExportSummary, Box<dyn std::error::Error>> {
+        let target_dir = Path::new(target_dir);
+        if !target_dir.is_absolute() {
+            return Err(format!("目标文件夹必须是绝对路径: {}", target_dir.display()).into());
+        }
+        validate_export_settings(settings)?;
+        fs::create_dir_all(target_dir)?;
+
+        let conn = self.get_connection()?;
+        let mut files = load_media_files(&conn, media_ids)?;
+        files.sort_by(|a, b| {
+            a.values
+                .taken_date
+                .cmp(&b.values.taken_date)
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        assign_sequences(files.iter_mut().map(|file| &mut file.values));
+
+        let mut summary = ExportSummary::default();
+        let total = files.len();
+        for (index, file) in files.iter().enumerate() {
+            let path = file.path.to_string_lossy();
+            on_progress(index + 1, total, &path);
+
+            match export_file(file, target_dir, settings) {
+                Ok((target, size)) => {
+                    summary.exported.push(target.to_string_lossy().into_owned());
+                    summary.total_size += size;
+                }
+                Err(e) => {
+                    warn!("导出文件失败 {}: {}", path, e);
+                    summary.failed.push(ExportFailure {
+                        media_id: file.media_id.clone(),
+                        path: path.into_owned(),
+                        error: e,
+                    });
+                }
+            }
+        }
+
+        info!(
+            "已导出 {} 个文件到 {}，共 {} 字节，{} 个失败",
+            summary.exported.len(),
+            target_dir.display(),
+            summary.total_size,
+            summary.failed.len()
+        );
+        Ok(summary)
+    }
+}
+
+/// 检查导出设置中的文件名模板、水印和边框，字体和水印图片在导出时才读取
+pub(crate) fn validate_export_settings(settings: &ExportSettings) -> Result<(), String> {
+    if let Some(template) = &settings.file_name_template {
+        render_file_name(
+            template,
+            &TemplateValues::for_path(Path::new("IMG_0001.jpg")),
+        )?;
+    }
+    if settings.format == ExportFormat::Original
+        && (settings.watermark.is_some() || settings.border.is_some())
+    {
+        return Err("原图无法添加水印或边框，请选择转换格式".to_string());
+    }
+    if let Some(watermark) = &settings.watermark {
+        validate_watermark(watermark)?;
+    }
+    if let Some(border) = &settings.border {
+        validate_border(border)?;
+    }
+
+    Ok(())
+}
+
+/// 导出单个文件，返回导出的路径和大小
+fn export_file(
+    file: &MediaFiles,
+    target_dir: &Path,
+    settings: &ExportSettings,
+) -> Result<(PathBuf, u64), String> {
+    if !file.path.is_file() {
+        return Err("文件不存在".to_string());
+    }
+
+    let stem = match &settings.file_name_template {
+        Some(template) => render_file_name(template, &file.values)?,
+        None => file
+            .path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+    };
+    let extension = match settings.format.extension() {
+        Some(extension) => Some(extension.to_string()),
+        None => file
+            .path
+            .extension()
+            .map(|ext| ext.to_string_lossy().into_owned()),
+    };
+    let file_name = match extension {
+        Some(extension) => format!("{}.{}", stem, extension),
+        None => stem,
+    };
+
+    // 原图且保留全部元数据时直接复制，不读入内存
+    let data = if settings.format == ExportFormat::Original
+        && settings.metadata_policy == MetadataPolicy::KeepAll
+    {
+        None
+    } else {
+        Some(render_export(&file.path, settings)?)
+    };
+
+    let base = target_dir.join(file_name);
+    let mut number = 0;
+    loop {
+        let target = numbered_path(&base, number);
+        // create_new 保证不会覆盖其他程序同时写入的文件
+        let mut output = match fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&target)
+        {
+            Ok(output) => output,
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                number += 1;
+                continue;
+            }
+            Err(e) => return Err(format!("创建文件失败: {}", e)),
+        };
+
+        let written = match &data {
+            Some(data) => output.write_all(data).map(|_| data.len() as u64),
+            None => fs::File::open(&file.path)
+                .and_then(|mut input| std::io::copy(&mut input, &mut output)),
+        };
+        return match written.and_then(|size| output.sync_all().map(|_| size)) {
+            Ok(size) => Ok((target, size)),
+            Err(e) => {
+                drop(output);
+                let _ = fs::remove_file(&target);
+                Err(format!("写入文件失败: {}", e))
+            }
+        };
+    }
+}
+
+/// 按模板生成不含扩展名的文件名，模板中不能包含文件夹
+fn render_file_name(template: &str, values: &TemplateValues) -> Result<String, String> {
+    let name = render_path_template(template, values)?;
+    if name.components().count() != 1 {
+        return Err(format!("文件名模板不能包含文件夹: {}", template));
+    }
+
+    Ok(name.to_string_lossy().into_owned())
+}
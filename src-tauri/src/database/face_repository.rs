@@ -0,0 +1,175 @@
+use chrono::Utc;
+use log::info;
+use rusqlite::{params, Connection};
+use tauri::AppHandle;
+use uuid::Uuid;
+
+use crate::database::{open_connection, parse_time_column, parse_uuid_column};
+use crate::models::face::FaceRecord;
+use crate::utils::face_detection::DetectedFace;
+
+/// 查询人脸时使用的列，顺序与 `map_face_row` 保持一致
+pub(crate) const FACE_COLUMNS: &str =
+    "f.id, f.media_id, f.person_id, f.x, f.y, f.width, f.height, f.confidence, f.confirmed,
+     f.created_at, f.updated_at";
+
+/// 等待人脸检测的媒体
+#[derive(Debug, Clone)]
+pub struct PendingFaceMedia {
+    pub id: String,
+    pub path: String,
+}
+
+/// SQLite 人脸仓库实现
+pub struct FaceRepository {
+    app: AppHandle,
+}
+
+impl FaceRepository {
+    pub fn new(app: AppHandle) -> Self {
+        Self { app }
+    }
+
+    /// 获取数据库连接
+    fn get_connection(&self) -> Result<Connection, Box<dyn std::error::Error>> {
+        open_connection(&self.app)
+    }
+
+    /// 获取尚未检测人脸的图片，`face_count` 为空表示未检测
+    pub async fn find_pending_media(
+        &self,
+    ) -> Result<Vec<PendingFaceMedia>, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT id, path FROM media_records
+             WHERE kind = 'Image' AND face_count IS NULL AND trashed_at IS NULL
+             ORDER BY created_at",
+        )?;
+
+        let media = stmt
+            .query_map([], |row| {
+                Ok(PendingFaceMedia {
+                    id: row.get(0)?,
+                    path: row.get(1)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(media)
+    }
+
+    /// 保存一张图片的检测结果，替换已有的人脸并更新 `face_count`
+    pub async fn save_faces(
+        &self,
+        media_id: &str,
+        faces: &[DetectedFace],
+    ) -> Result<Vec<FaceRecord>, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+        let tx = conn.unchecked_transaction()?;
+        let now = Utc::now();
+
+        tx.execute("DELETE FROM faces WHERE media_id = ?1", params![media_id])?;
+
+        let mut records = Vec::with_capacity(faces.len());
+        for face in faces {
+            let id = Uuid::new_v4();
+            tx.execute(
+                "INSERT INTO faces
+                 (id, media_id, x, y, width, height, confidence, embedding, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?9)",
+                params![
+                    id.to_string(),
+                    media_id,
+                    face.bbox[0] as f64,
+                    face.bbox[1] as f64,
+                    face.bbox[2] as f64,
+                    face.bbox[3] as f64,
+                    face.confidence as f64,
+                    embedding_to_blob(&face.embedding),
+                    now.to_rfc3339(),
+                ],
+            )?;
+
+            records.push(FaceRecord {
+                id,
+                media_id: Uuid::parse_str(media_id)?,
+                person_id: None,
+                bbox: face.bbox.map(|v| v as f64),
+                confidence: face.confidence as f64,
+                confirmed: false,
+                created_at: now,
+                updated_at: now,
+            });
+        }
+
+        tx.execute(
+            "UPDATE media_records SET face_count = ?1 WHERE id = ?2",
+            params![faces.len() as i64, media_id],
+        )?;
+        tx.commit()?;
+
+        Ok(records)
+    }
+
+    /// 获取一张图片中的人脸，不包含已标记为误检的人脸
+    pub async fn find_by_media(
+        &self,
+        media_id: &str,
+    ) -> Result<Vec<FaceRecord>, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM faces f WHERE f.media_id = ?1 AND f.rejected = 0 ORDER BY f.x",
+            FACE_COLUMNS
+        ))?;
+
+        let faces = stmt
+            .query_map(params![media_id], map_face_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(faces)
+    }
+
+    /// 清除所有检测结果，之后的检测任务会重新处理全部图片
+    pub async fn reset_detection(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+        let tx = conn.unchecked_transaction()?;
+
+        tx.execute("DELETE FROM faces", [])?;
+        tx.execute("UPDATE media_records SET face_count = NULL", [])?;
+        tx.commit()?;
+
+        info!("已清除所有人脸检测结果");
+        Ok(())
+    }
+}
+
+/// 将特征向量编码为 f32 小端序字节
+pub(crate) fn embedding_to_blob(embedding: &[f32]) -> Vec<u8> {
+    embedding.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+/// 从 f32 小端序字节解码特征向量
+pub(crate) fn blob_to_embedding(blob: &[u8]) -> Vec<f32> {
+    blob.chunks_exact(4)
+        .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        .collect()
+}
+
+/// 将一行查询结果解析为人脸记录
+pub(crate) fn map_face_row(row: &rusqlite::Row) -> rusqlite::Result<FaceRecord> {
+    Ok(FaceRecord {
+        id: parse_uuid_column(0, row.get(0)?)?,
+        media_id: parse_uuid_column(1, row.get(1)?)?,
+        person_id: row
+            .get::<_, Option<String>>(2)?
+            .map(|value| parse_uuid_column(2, value))
+            .transpose()?,
+        bbox: [row.get(3)?, row.get(4)?, row.get(5)?, row.get(6)?],
+        confidence: row.get(7)?,
+        confirmed: row.get(8)?,
+        created_at: parse_time_column(9, row.get(9)?)?,
+        updated_at: parse_time_column(10, row.get(10)?)?,
+    })
+}
@@ -0,0 +1,392 @@
+use std::{
+    fs,
+    io::{Cursor, Write},
+    path::{Path, PathBuf},
+};
+
+use chrono::Local;
+use log::{info, warn};
+use rusqlite::{params, Connection, OptionalExtension};
+use tauri::AppHandle;
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+use crate::database::media_repository::{map_media_row, MEDIA_COLUMNS};
+use crate::database::open_connection;
+use crate::models::export::{ExportFailure, ExportFormat, ExportSettings, MetadataPolicy};
+use crate::models::gallery::{GalleryOptions, GalleryOutput, GallerySummary};
+use crate::models::image::{MediaKind, MediaRecord};
+use crate::utils::gallery_site::{
+    index_page_path, page_count, render_index_page, render_map_page, render_photo_page,
+    GalleryPhoto, MAP_SCRIPT, STYLESHEET, THUMBNAIL_SIZES,
+};
+use crate::utils::image_export::render_export;
+
+/// 网站中大图的 JPEG 质量
+const GALLERY_IMAGE_QUALITY: u8 = 85;
+
+/// SQLite 静态网站导出仓库实现
+pub struct GalleryRepository {
+    app: AppHandle,
+}
+
+impl GalleryRepository {
+    pub fn new(app: AppHandle) -> Self {
+        Self { app }
+    }
+
+    /// 获取数据库连接
+    fn get_connection(&self) -> Result<Connection, Box<dyn std::error::Error>> {
+        open_connection(&self.app)
+    }
+
+    /// 将相册或选中的媒体导出为静态网站，包括索引页、缩略图、照片页和可选的地图页
+    ///
+    /// 视频等非图片媒体及读取失败的照片被跳过。导出失败时删除已写入的内容。
+    /// 每处理一张照片调用一次 `on_progress`，参数为已处理的数量和总数。
+    pub async fn export(
+        &self,
+        options: &GalleryOptions,
+        on_progress: impl Fn(usize, usize),
+    ) -> Result<GallerySummary, Box<dyn std::error::Error>> {
+        let title = options.title.trim();
+        if title.is_empty() {
+            return Err("网站标题不能为空".into());
+        }
+        if options.page_size == 0 || options.image_long_edge == 0 {
+            return Err("每页照片数和大图尺寸必须大于 0".into());
+        }
+        let target = Path::new(&options.target);
+        if !target.is_absolute() {
+            return Err(format!("目标路径必须是绝对路径: {}", options.target).into());
+        }
+
+        let conn = self.get_connection()?;
+        let records = match (&options.album_id, &options.media_ids) {
+            (Some(album_id), None) => find_album_media(&conn, album_id)?,
+            (None, Some(media_ids)) => find_media(&conn, media_ids)?,
+            _ => return Err("需要指定相册或媒体中的一种".into()),
+        };
+
+        let mut writer = SiteWriter::create(target, options.output)?;
+        match build_site(&mut writer, title, &records, options, &on_progress) {
+            Ok(summary) => {
+                writer.finish()?;
+                info!(
+                    "已导出静态网站到 {}：{} 张照片，{} 页，跳过 {} 个",
+                    options.target,
+                    summary.photos,
+                    summary.pages,
+                    summary.skipped.len()
+                );
+                Ok(GallerySummary {
+                    output_path: options.target.clone(),
+                    ..summary
+                })
+            }
+            Err(e) => {
+                writer.discard();
+                Err(e)
+            }
+        }
+    }
+}
+
+/// 生成所有图片和页面
+fn build_site(
+    writer: &mut SiteWriter,
+    title: &str,
+    records: &[MediaRecord],
+    options: &GalleryOptions,
+    on_progress: &impl Fn(usize, usize),
+) -> Result<GallerySummary, Box<dyn std::error::Error>> {
+    let settings = ExportSettings {
+        format: ExportFormat::Jpeg,
+        long_edge: Some(options.image_long_edge),
+        quality: Some(GALLERY_IMAGE_QUALITY),
+        convert_to_srgb: true,
+        metadata_policy: options.metadata_policy,
+        ..Default::default()
+    };
+
+    let mut summary = GallerySummary::default();
+    let mut photos = Vec::new();
+    for (index, record) in records.iter().enumerate() {
+        let key = format!("{:04}", photos.len() + 1);
+        let result = if matches!(record.kind, MediaKind::Image) {
+            render_photo(Path::new(&record.path), &settings)
+        } else {
+            Err("不支持的媒体类型".to_string())
+        };
+
+        match result {
+            Ok((image, thumbnails)) => {
+                let photo = gallery_photo(key, record, options);
+                writer.write(&photo.image_path(), &image)?;
+                for (size, thumbnail) in THUMBNAIL_SIZES.iter().zip(thumbnails) {
+                    writer.write(&photo.thumbnail_path(*size), &thumbnail)?;
+                }
+                photos.push(photo);
+            }
+            Err(e) => {
+                warn!("跳过 {}: {}", record.path, e);
+                summary.skipped.push(ExportFailure {
+                    media_id: record.id.to_string(),
+                    path: record.path.clone(),
+                    error: e,
+                });
+            }
+        }
+        on_progress(index + 1, records.len());
+    }
+
+    let map = if options.include_map {
+        render_map_page(title, &photos, options.online_map_tiles)
+    } else {
+        None
+    };
+    let pages = page_count(photos.len(), options.page_size);
+    for page in 0..pages {
+        let html = render_index_page(title, &photos, page, options.page_size, map.is_some());
+        writer.write(&index_page_path(page), html.as_bytes())?;
+    }
+    for (index, photo) in photos.iter().enumerate() {
+        let html = render_photo_page(title, &photos, index, options.page_size);
+        writer.write(&photo.page_path(), html.as_bytes())?;
+    }
+    if let Some(map) = map {
+        writer.write("map.html", map.as_bytes())?;
+        writer.write("map.js", MAP_SCRIPT.as_bytes())?;
+    }
+    writer.write("style.css", STYLESHEET.as_bytes())?;
+
+    summary.photos = photos.len();
+    summary.pages = pages;
+    Ok(summary)
+}
+
+/// 生成大图及各尺寸的缩略图，缩略图由大图缩小，不含元数据
+fn render_photo(path: &Path, settings: &ExportSettings) -> Result<(Vec<u8>, Vec<Vec<u8>>), String> {
+    let image = render_export(path, settings)?;
+    let decoded = image::load_from_memory(&image).map_err(|e| format!("无法解码图片: {}", e))?;
+
+    let mut thumbnails = Vec::new();
+    for size in THUMBNAIL_SIZES {
+        let mut buf = Cursor::new(Vec::new());
+        decoded
+            .thumbnail(size, size)
+            .write_to(&mut buf, image::ImageFormat::Jpeg)
+            .map_err(|e| format!("生成缩略图失败: {}", e))?;
+        thumbnails.push(buf.into_inner());
+    }
+
+    Ok((image, thumbnails))
+}
+
+/// 照片页显示的标题、说明和拍摄参数
+///
+/// 元数据策略移除 GPS 时不保留拍摄地点，地图页也因此不会生成。
+fn gallery_photo(key: String, record: &MediaRecord, options: &GalleryOptions) -> GalleryPhoto {
+    let exif = if options.include_exif {
+        exif_summary(record)
+    } else {
+        None
+    };
+
+    GalleryPhoto {
+        key,
+        name: record.name.clone(),
+        caption: record
+            .caption
+            .clone()
+            .filter(|caption| !caption.trim().is_empty()),
+        exif,
+        location: match options.metadata_policy {
+            MetadataPolicy::KeepAll => record.latitude.zip(record.longitude),
+            MetadataPolicy::StripGps | MetadataPolicy::StripAll => None,
+        },
+    }
+}
+
+/// 拍摄参数摘要，如 `Canon EOS R5 · 50mm · f/2.8 · ISO 100 · 2023-07-02 12:00`
+pub(crate) fn exif_summary(record: &MediaRecord) -> Option<String> {
+    let mut parts = Vec::new();
+    parts.extend(
+        record
+            .camera_model
+            .clone()
+            .or_else(|| record.camera_make.clone()),
+    );
+    parts.extend(record.lens_model.clone());
+    parts.extend(record.focal_length.map(|value| format!("{}mm", value)));
+    parts.extend(record.aperture.map(|value| format!("f/{}", value)));
+    parts.extend(record.iso.map(|value| format!("ISO {}", value)));
+    parts.extend(record.taken_date.map(|date| {
+        date.with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M")
+            .to_string()
+    }));
+
+    Some(parts.join(" · ")).filter(|summary| !summary.is_empty())
+}
+
+/// 按相册内的顺序读取媒体，不含回收站中的媒体
+fn find_album_media(
+    conn: &Connection,
+    album_id: &str,
+) -> Result<Vec<MediaRecord>, Box<dyn std::error::Error>> {
+    let exists = conn
+        .query_row(
+            "SELECT 1 FROM albums WHERE id = ?1",
+            params![album_id],
+            |_| Ok(()),
+        )
+        .optional()?;
+    if exists.is_none() {
+        return Err(format!("未找到相册: {}", album_id).into());
+    }
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM album_media
+         JOIN media_records ON media_records.id = album_media.media_id
+         WHERE album_media.album_id = ?1 AND media_records.trashed_at IS NULL
+         ORDER BY album_media.position",
+        MEDIA_COLUMNS
+    ))?;
+    let records = stmt
+        .query_map(params![album_id], map_media_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(records)
+}
+
+/// 按给定顺序读取媒体，不存在或在回收站中的媒体被忽略
+pub(crate) fn find_media(
+    conn: &Connection,
+    media_ids: &[String],
+) -> Result<Vec<MediaRecord>, Box<dyn std::error::Error>> {
+    let mut stmt = conn.prepare_cached(&format!(
+        "SELECT {} FROM media_records WHERE id = ?1 AND trashed_at IS NULL",
+        MEDIA_COLUMNS
+    ))?;
+
+    let mut records = Vec::new();
+    for media_id in media_ids {
+        records.extend(
+            stmt.query_row(params![media_id], map_media_row)
+                .optional()?,
+        );
+    }
+    Ok(records)
+}
+
+/// 网站文件的写入位置：文件夹或 zip 文件
+enum SiteWriter {
+    Folder {
+        root: PathBuf,
+        /// 文件夹是否由本次导出创建，失败时只删除自己创建的文件夹
+        created: bool,
+    },
+    Zip {
+        path: PathBuf,
+        zip: Box<ZipWriter<fs::File>>,
+    },
+}
+
+impl SiteWriter {
+    /// 文件夹必须不存在或为空，zip 文件必须不存在
+    fn create(target: &Path, output: GalleryOutput) -> Result<Self, Box<dyn std::error::Error>> {
+        match output {
+            GalleryOutput::Folder => {
+                let created = !target.exists();
+                if !created && fs::read_dir(target)?.next().is_some() {
+                    return Err(format!("目标文件夹不是空的: {}", target.display()).into());
+                }
+                fs::create_dir_all(target)?;
+                Ok(SiteWriter::Folder {
+                    root: target.to_path_buf(),
+                    created,
+                })
+            }
+            GalleryOutput::Zip => {
+                if let Some(parent) = target.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                let file = fs::OpenOptions::new()
+                    .write(true)
+                    .create_new(true)
+                    .open(target)
+                    .map_err(|e| format!("无法创建 {}: {}", target.display(), e))?;
+                Ok(SiteWriter::Zip {
+                    path: target.to_path_buf(),
+                    zip: Box::new(ZipWriter::new(file)),
+                })
+            }
+        }
+    }
+
+    /// 写入网站中的文件，`path` 为以 `/` 分隔的相对路径
+    fn write(&mut self, path: &str, data: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
+        match self {
+            SiteWriter::Folder { root, .. } => {
+                let path = root.join(path);
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(path, data)?;
+            }
+            SiteWriter::Zip { zip, .. } => {
+                // JPEG 已经压缩过，直接存储
+                let method = if path.ends_with(".jpg") {
+                    CompressionMethod::Stored
+                } else {
+                    CompressionMethod::Deflated
+                };
+                zip.start_file(
+                    path,
+                    SimpleFileOptions::default().compression_method(method),
+                )?;
+                zip.write_all(data)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<(), Box<dyn std::error::Error>> {
+        if let SiteWriter::Zip { zip, .. } = self {
+            zip.finish()?.sync_all()?;
+        }
+        Ok(())
+    }
+
+    /// 删除已写入的内容
+    fn discard(self) {
+        let result = match self {
+            SiteWriter::Folder {
+                root,
+                created: true,
+            } => fs::remove_dir_all(root),
+            SiteWriter::Folder {
+                root,
+                created: false,
+            } => fs::read_dir(&root).and_then(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .try_for_each(|entry| {
+                        let path = entry.path();
+                        if path.is_dir() {
+                            fs::remove_dir_all(path)
+                        } else {
+                            fs::remove_file(path)
+                        }
+                    })
+            }),
+            SiteWriter::Zip { path, zip } => {
+                drop(zip);
+                fs::remove_file(path)
+            }
+        };
+        if let Err(e) = result {
+            warn!("删除未完成的网站文件失败: {}", e);
+        }
+    }
+}
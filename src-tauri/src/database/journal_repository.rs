@@ -0,0 +1,353 @@
+use std::path::Path;
+
+use chrono::Utc;
+use log::{error, info, warn};
+use rusqlite::types::Value;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::database::album_repository::apply_album_media_state;
+use crate::database::trash_repository::{
+    restore_trashed_media, trash_dir, trash_media, TrashOutcome,
+};
+use crate::database::{open_connection, parse_time_column};
+use crate::models::journal::JournalEntry;
+use crate::models::trash::TrashFileAction;
+use crate::utils::file_ops::move_file;
+
+/// 保留的操作记录数量上限，超出时删除最早的记录
+const MAX_JOURNAL_ENTRIES: i64 = 100;
+
+/// 可以通过操作日志恢复的媒体列
+const JOURNAL_COLUMNS: &[&str] = &["rating", "favorite", "color_label", "flag", "taken_date"];
+
+/// 查询操作记录时使用的列，顺序与 `map_entry_row` 保持一致
+const ENTRY_COLUMNS: &str = "id, label, change_count, undone, created_at";
+
+/// 相册中媒体的顺序和封面
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct AlbumMediaState {
+    pub media_ids: Vec<String>,
+    pub cover_media_id: Option<String>,
+}
+
+/// 操作中的单项变更，撤销时恢复到 before，重做时恢复到 after
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub(crate) enum JournalChange {
+    /// media_records 中的一列，列名必须在 `JOURNAL_COLUMNS` 中
+    MediaField {
+        media_id: String,
+        column: String,
+        before: serde_json::Value,
+        after: serde_json::Value,
+    },
+    /// 媒体是否带有标签
+    MediaTag {
+        media_id: String,
+        tag_id: String,
+        before: bool,
+        after: bool,
+    },
+    /// 相册中的媒体及封面
+    AlbumMedia {
+        album_id: String,
+        before: AlbumMediaState,
+        after: AlbumMediaState,
+    },
+    /// 媒体是否在回收站中，在回收站中时记录原文件的处理方式
+    MediaTrash {
+        media_id: String,
+        before: Option<TrashFileAction>,
+        after: Option<TrashFileAction>,
+    },
+}
+
+/// SQLite 操作日志仓库实现
+pub struct JournalRepository {
+    app: AppHandle,
+}
+
+impl JournalRepository {
+    pub fn new(app: AppHandle) -> Self {
+        Self { app }
+    }
+
+    /// 获取数据库连接
+    fn get_connection(&self) -> Result<Connection, Box<dyn std::error::Error>> {
+        open_connection(&self.app)
+    }
+
+    /// 获取操作记录，最近的在前
+    pub async fn find_all(&self) -> Result<Vec<JournalEntry>, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM journal_entries ORDER BY id DESC",
+            ENTRY_COLUMNS
+        ))?;
+        let entries = stmt
+            .query_map([], map_entry_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(entries)
+    }
+
+    /// 撤销最近一次未撤销的操作，没有可撤销的操作时返回 None
+    pub async fn undo(&self) -> Result<Option<JournalEntry>, Box<dyn std::error::Error>> {
+        self.apply_entry(true)
+    }
+
+    /// 重做最早一次已撤销的操作，没有可重做的操作时返回 None
+    pub async fn redo(&self) -> Result<Option<JournalEntry>, Box<dyn std::error::Error>> {
+        self.apply_entry(false)
+    }
+
+    /// 撤销或重做一条操作记录，数据库变更在同一个事务中完成
+    ///
+    /// 已被彻底删除的媒体、标签或相册直接跳过。任何变更失败时事务回滚，
+    /// 已在原位置和应用回收站之间移动的文件被移回，操作记录保持原状态。
+    fn apply_entry(&self, undo: bool) -> Result<Option<JournalEntry>, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+        let tx = conn.unchecked_transaction()?;
+
+        let order = if undo { "DESC" } else { "ASC" };
+        let entry = tx
+            .query_row(
+                &format!(
+                    "SELECT {} FROM journal_entries WHERE undone = ?1 ORDER BY id {} LIMIT 1",
+                    ENTRY_COLUMNS, order
+                ),
+                params![!undo],
+                map_entry_row,
+            )
+            .optional()?;
+        let Some(mut entry) = entry else {
+            return Ok(None);
+        };
+
+        let mut changes = {
+            let mut stmt = tx.prepare(
+                "SELECT change FROM journal_changes WHERE entry_id = ?1 ORDER BY position",
+            )?;
+            let changes = stmt
+                .query_map(params![entry.id], |row| row.get::<_, String>(0))?
+                .collect::<Result<Vec<_>, _>>()?;
+            changes
+                .iter()
+                .map(|json| serde_json::from_str::<JournalChange>(json))
+                .collect::<Result<Vec<_>, _>>()?
+        };
+        // 撤销时按相反的顺序恢复，同一媒体的多次变更才能回到最初的状态
+        if undo {
+            changes.reverse();
+        }
+
+        // 回收站变更会移动文件，放在其他变更之后；移到系统回收站的文件无法自动移回，最后执行
+        changes.sort_by_key(|change| match change {
+            JournalChange::MediaTrash { before, after, .. } => {
+                if *(if undo { before } else { after }) == Some(TrashFileAction::System) {
+                    2
+                } else {
+                    1
+                }
+            }
+            _ => 0,
+        });
+
+        let trash_dir = trash_dir(&self.app)?;
+        let mut trash_outcomes = Vec::new();
+        let result = (|| -> Result<(), Box<dyn std::error::Error>> {
+            for change in &changes {
+                apply_change(&tx, change, undo, &trash_dir, &mut trash_outcomes)?;
+            }
+            tx.execute(
+                "UPDATE journal_entries SET undone = ?1 WHERE id = ?2",
+                params![undo, entry.id],
+            )?;
+            tx.commit()?;
+            Ok(())
+        })();
+        if let Err(e) = result {
+            revert_trash_outcomes(&trash_outcomes);
+            return Err(e);
+        }
+
+        entry.undone = undo;
+        info!(
+            "已{}操作 \"{}\"，共 {} 项变更",
+            if undo { "撤销" } else { "重做" },
+            entry.label,
+            changes.len()
+        );
+        Ok(Some(entry))
+    }
+}
+
+/// 在当前事务中记录一次操作，没有变更时不记录
+///
+/// 新的操作会清除所有已撤销的记录，超出 `MAX_JOURNAL_ENTRIES` 的最早记录被删除。
+pub(crate) fn record_journal(
+    conn: &Connection,
+    label: &str,
+    changes: &[JournalChange],
+) -> rusqlite::Result<()> {
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    conn.execute("DELETE FROM journal_entries WHERE undone = 1", [])?;
+    conn.execute(
+        "INSERT INTO journal_entries (label, change_count, created_at) VALUES (?1, ?2, ?3)",
+        params![label, changes.len() as i64, Utc::now().to_rfc3339()],
+    )?;
+    let entry_id = conn.last_insert_rowid();
+
+    {
+        let mut stmt = conn.prepare_cached(
+            "INSERT INTO journal_changes (entry_id, position, change) VALUES (?1, ?2, ?3)",
+        )?;
+        for (position, change) in changes.iter().enumerate() {
+            let json = serde_json::to_string(change)
+                .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+            stmt.execute(params![entry_id, position as i64, json])?;
+        }
+    }
+
+    conn.execute(
+        "DELETE FROM journal_entries WHERE id NOT IN
+         (SELECT id FROM journal_entries ORDER BY id DESC LIMIT ?1)",
+        params![MAX_JOURNAL_ENTRIES],
+    )?;
+    Ok(())
+}
+
+/// 将列的值转换为 JSON 保存到操作日志，`JOURNAL_COLUMNS` 中没有 BLOB 列
+pub(crate) fn value_to_json(value: &Value) -> serde_json::Value {
+    match value {
+        Value::Integer(value) => (*value).into(),
+        Value::Real(value) => (*value).into(),
+        Value::Text(value) => value.clone().into(),
+        Value::Null | Value::Blob(_) => serde_json::Value::Null,
+    }
+}
+
+fn json_to_value(value: &serde_json::Value) -> Value {
+    match value {
+        serde_json::Value::Null => Value::Null,
+        serde_json::Value::Bool(value) => Value::Integer(*value as i64),
+        serde_json::Value::Number(number) => match number.as_i64() {
+            Some(value) => Value::Integer(value),
+            None => Value::Real(number.as_f64().unwrap_or_default()),
+        },
+        serde_json::Value::String(value) => Value::Text(value.clone()),
+        other => Value::Text(other.to_string()),
+    }
+}
+
+/// 将单项变更恢复到操作前（`undo` 为 true）或操作后的状态
+///
+/// 回收站变更的结果追加到 `trash_outcomes`，事务回滚时用来移回文件。
+fn apply_change(
+    conn: &Connection,
+    change: &JournalChange,
+    undo: bool,
+    trash_dir: &Path,
+    trash_outcomes: &mut Vec<(String, bool, TrashOutcome)>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let now = Utc::now().to_rfc3339();
+
+    match change {
+        JournalChange::MediaField {
+            media_id,
+            column,
+            before,
+            after,
+        } => {
+            if !JOURNAL_COLUMNS.contains(&column.as_str()) {
+                warn!("操作日志中的列不可恢复: {}", column);
+                return Ok(());
+            }
+            let value = if undo { before } else { after };
+            conn.execute(
+                &format!(
+                    "UPDATE media_records SET {} = ?1, updated_at = ?2 WHERE id = ?3",
+                    column
+                ),
+                params![json_to_value(value), now, media_id],
+            )?;
+        }
+        JournalChange::MediaTag {
+            media_id,
+            tag_id,
+            before,
+            after,
+        } => {
+            if *(if undo { before } else { after }) {
+                conn.execute(
+                    "INSERT OR IGNORE INTO media_tags (media_id, tag_id, created_at)
+                     SELECT m.id, t.id, ?3 FROM media_records m, tags t
+                     WHERE m.id = ?1 AND t.id = ?2",
+                    params![media_id, tag_id, now],
+                )?;
+            } else {
+                conn.execute(
+                    "DELETE FROM media_tags WHERE media_id = ?1 AND tag_id = ?2",
+                    params![media_id, tag_id],
+                )?;
+            }
+        }
+        JournalChange::AlbumMedia {
+            album_id,
+            before,
+            after,
+        } => {
+            apply_album_media_state(conn, album_id, if undo { before } else { after })?;
+        }
+        JournalChange::MediaTrash {
+            media_id,
+            before,
+            after,
+        } => {
+            let target = if undo { before } else { after };
+            let outcome = match target {
+                Some(action) => trash_media(conn, media_id, *action, trash_dir),
+                None => restore_trashed_media(conn, media_id),
+            }
+            .map_err(|e| format!("恢复媒体 {} 的回收站状态失败: {}", media_id, e))?;
+            if let Some(outcome) = outcome {
+                trash_outcomes.push((media_id.clone(), target.is_some(), outcome));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// 撤销或重做失败时把已移动的文件移回，已移到系统回收站的文件需用户手动还原
+fn revert_trash_outcomes(outcomes: &[(String, bool, TrashOutcome)]) {
+    for (media_id, trashed, outcome) in outcomes.iter().rev() {
+        match &outcome.moved {
+            Some((from, to)) => {
+                if let Err(e) = move_file(to, from) {
+                    error!("移回媒体 {} 的文件失败 {}: {}", media_id, to.display(), e);
+                }
+            }
+            None if *trashed && outcome.action == TrashFileAction::System => {
+                error!("媒体 {} 的文件已移到系统回收站，需手动还原", media_id);
+            }
+            None => {}
+        }
+    }
+}
+
+fn map_entry_row(row: &rusqlite::Row) -> rusqlite::Result<JournalEntry> {
+    Ok(JournalEntry {
+        id: row.get(0)?,
+        label: row.get(1)?,
+        change_count: row.get::<_, i64>(2)? as u64,
+        undone: row.get(3)?,
+        created_at: parse_time_column(4, row.get(4)?)?,
+    })
+}
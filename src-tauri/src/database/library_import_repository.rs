@@ -0,0 +1,123 @@
+use std::path::Path;
+
+use log::info;
+use rusqlite::{params, Connection};
+use tauri::AppHandle;
+
+use crate::database::relink_repository::modified_time;
+use crate::database::{open_connection, read_setting, write_setting};
+use crate::models::library_import::{ImportedFile, LibraryImportSettings};
+use crate::utils::path_template::{render_path_template, TemplateValues};
+
+const LIBRARY_ROOT_SETTING: &str = "import.library_root";
+const PATH_TEMPLATE_SETTING: &str = "import.path_template";
+
+/// 默认按拍摄日期分文件夹，文件名为相机型号加序号
+const DEFAULT_PATH_TEMPLATE: &str = "{year}/{year}-{month}-{day}/{camera}_{seq}.{ext}";
+
+/// SQLite 媒体库导入仓库实现
+pub struct LibraryImportRepository {
+    app: AppHandle,
+}
+
+impl LibraryImportRepository {
+    pub fn new(app: AppHandle) -> Self {
+        Self { app }
+    }
+
+    /// 获取数据库连接
+    fn get_connection(&self) -> Result<Connection, Box<dyn std::error::Error>> {
+        open_connection(&self.app)
+    }
+
+    /// 获取媒体库文件夹及导入路径模板
+    pub async fn settings(&self) -> Result<LibraryImportSettings, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+
+        Ok(LibraryImportSettings {
+            library_root: read_setting(&conn, LIBRARY_ROOT_SETTING)?,
+            path_template: read_setting(&conn, PATH_TEMPLATE_SETTING)?
+                .unwrap_or_else(|| DEFAULT_PATH_TEMPLATE.to_string()),
+        })
+    }
+
+    /// 保存媒体库文件夹及导入路径模板，文件夹必须是绝对路径，模板必须能生成有效的路径
+    pub async fn set_settings(
+        &self,
+        settings: &LibraryImportSettings,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(root) = &settings.library_root {
+            if !Path::new(root).is_absolute() {
+                return Err(format!("媒体库文件夹必须是绝对路径: {}", root).into());
+            }
+        }
+        render_path_template(
+            &settings.path_template,
+            &TemplateValues::for_path(Path::new("IMG_0001.jpg")),
+        )?;
+
+        let conn = self.get_connection()?;
+        let tx = conn.unchecked_transaction()?;
+        match &settings.library_root {
+            Some(root) => write_setting(&tx, LIBRARY_ROOT_SETTING, root)?,
+            None => {
+                tx.execute(
+                    "DELETE FROM settings WHERE key = ?1",
+                    params![LIBRARY_ROOT_SETTING],
+                )?;
+            }
+        }
+        write_setting(&tx, PATH_TEMPLATE_SETTING, &settings.path_template)?;
+        tx.commit()?;
+
+        info!(
+            "媒体库导入设置已更新：{:?}，模板 {}",
+            settings.library_root, settings.path_template
+        );
+        Ok(())
+    }
+
+    /// 媒体库中（不含回收站）是否已有内容哈希相同的媒体
+    pub async fn contains_hash(
+        &self,
+        content_hash: &str,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+
+        let exists = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM media_records
+             WHERE content_hash = ?1 AND trashed_at IS NULL)",
+            params![content_hash],
+            |row| row.get(0),
+        )?;
+        Ok(exists)
+    }
+
+    /// 记录导入文件的原路径和内容哈希，文件已校验过哈希，无需等待后台索引
+    pub async fn record_source(
+        &self,
+        file: &ImportedFile,
+        content_hash: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "UPDATE media_records
+             SET source_path = ?1, content_hash = ?2, file_modified_at = ?3
+             WHERE id = ?4",
+            params![
+                file.source_path,
+                content_hash,
+                modified_time(Path::new(&file.path)),
+                file.media_id
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// 删除导入未完成的媒体记录，文件被移回原位置或删除后调用
+    pub async fn remove_record(&self, media_id: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+        conn.execute("DELETE FROM media_records WHERE id = ?1", params![media_id])?;
+        Ok(())
+    }
+}
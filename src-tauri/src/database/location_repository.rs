@@ -0,0 +1,219 @@
+use log::{info, warn};
+use rusqlite::{params, params_from_iter, types::Value, Connection};
+use tauri::AppHandle;
+
+use crate::database::open_connection;
+use crate::models::location::{MapBounds, MapCluster, PlaceCount};
+use crate::utils::reverse_geocoder::{self, reverse_geocode};
+
+/// 每个 256 像素地图瓦片在经度方向划分的聚合单元数，约 64 像素一个单元
+const CLUSTER_CELLS_PER_TILE: f64 = 4.0;
+
+/// 支持的最大缩放级别
+const MAX_ZOOM: u8 = 22;
+
+/// SQLite 地点仓库实现
+pub struct LocationRepository {
+    app: AppHandle,
+}
+
+impl LocationRepository {
+    pub fn new(app: AppHandle) -> Self {
+        Self { app }
+    }
+
+    /// 获取数据库连接
+    fn get_connection(&self) -> Result<Connection, Box<dyn std::error::Error>> {
+        open_connection(&self.app)
+    }
+
+    /// 按国家、行政区、城市统计媒体数量，按国家和数量排序
+    pub async fn find_places(&self) -> Result<Vec<PlaceCount>, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT country, region, city, COUNT(*) FROM media_records
+             WHERE (country IS NOT NULL OR region IS NOT NULL OR city IS NOT NULL)
+               AND trashed_at IS NULL
+             GROUP BY country, region, city
+             ORDER BY country, COUNT(*) DESC, city",
+        )?;
+        let places = stmt
+            .query_map([], |row| {
+                Ok(PlaceCount {
+                    country: row.get(0)?,
+                    region: row.get(1)?,
+                    city: row.get(2)?,
+                    media_count: row.get::<_, i64>(3)? as u64,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(places)
+    }
+
+    /// 按网格聚合可视范围内的照片，每个网格单元返回一个聚合点
+    ///
+    /// 网格以经纬度原点对齐，平移地图时聚合结果保持稳定；纬度方向的单元按可视范围中心纬度缩放，
+    /// 使单元在墨卡托投影下接近正方形。坐标先通过 R*Tree 空间索引筛选。
+    pub async fn find_map_clusters(
+        &self,
+        bounds: &MapBounds,
+        zoom: u8,
+    ) -> Result<Vec<MapCluster>, Box<dyn std::error::Error>> {
+        let MapBounds {
+            north,
+            south,
+            east,
+            west,
+        } = *bounds;
+        if !(-90.0..=90.0).contains(&south)
+            || !(-90.0..=90.0).contains(&north)
+            || south > north
+            || !(-180.0..=180.0).contains(&west)
+            || !(-180.0..=180.0).contains(&east)
+        {
+            return Err("地图范围无效".into());
+        }
+
+        let zoom = zoom.min(MAX_ZOOM);
+        let lon_cell = 360.0 / 2f64.powi(zoom as i32) / CLUSTER_CELLS_PER_TILE;
+        let center_latitude = (north + south) / 2.0;
+        let lat_cell = lon_cell * center_latitude.to_radians().cos().max(0.01);
+
+        // 跨越 180° 经线时拆成两个范围分别查询空间索引
+        let lon_ranges = if west <= east {
+            vec![(west, east)]
+        } else {
+            vec![(west, 180.0), (-180.0, east)]
+        };
+
+        let mut values = vec![Value::Real(lon_cell), Value::Real(lat_cell)];
+        let mut selects = Vec::new();
+        for (min_lon, max_lon) in lon_ranges {
+            selects.push(
+                "SELECT m.id, m.latitude, m.longitude, m.thumbnail_path,
+                        m.favorite, m.rating, m.taken_date
+                 FROM media_locations r
+                 JOIN media_location_ids l ON l.id = r.id
+                 JOIN media_records m ON m.id = l.media_id
+                 WHERE m.trashed_at IS NULL AND r.max_lat >= ? AND r.min_lat <= ?
+                   AND r.max_lon >= ? AND r.min_lon <= ?",
+            );
+            values.extend([
+                Value::Real(south),
+                Value::Real(north),
+                Value::Real(min_lon),
+                Value::Real(max_lon),
+            ]);
+        }
+
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(&format!(
+            "WITH cell AS (SELECT ? AS lon_size, ? AS lat_size),
+             points AS (
+                 SELECT p.*,
+                        CAST((p.longitude + 180.0) / cell.lon_size AS INTEGER) AS gx,
+                        CAST((p.latitude + 90.0) / cell.lat_size AS INTEGER) AS gy
+                 FROM ({}) p, cell
+             ),
+             clusters AS (
+                 SELECT gx, gy, COUNT(*) AS count,
+                        AVG(latitude) AS latitude, AVG(longitude) AS longitude,
+                        MIN(latitude) AS south, MAX(latitude) AS north,
+                        MIN(longitude) AS west, MAX(longitude) AS east
+                 FROM points GROUP BY gx, gy
+             ),
+             representatives AS (
+                 SELECT gx, gy, id, thumbnail_path,
+                        ROW_NUMBER() OVER (
+                            PARTITION BY gx, gy
+                            ORDER BY favorite DESC, rating DESC, taken_date DESC
+                        ) AS rank
+                 FROM points
+             )
+             SELECT c.latitude, c.longitude, c.count, r.id, r.thumbnail_path,
+                    c.north, c.south, c.east, c.west
+             FROM clusters c
+             JOIN representatives r ON r.gx = c.gx AND r.gy = c.gy AND r.rank = 1
+             ORDER BY c.count DESC",
+            selects.join(" UNION ALL ")
+        ))?;
+
+        let clusters = stmt
+            .query_map(params_from_iter(values), |row| {
+                Ok(MapCluster {
+                    latitude: row.get(0)?,
+                    longitude: row.get(1)?,
+                    count: row.get::<_, i64>(2)? as u64,
+                    media_id: row.get(3)?,
+                    thumbnail_path: row.get(4)?,
+                    bounds: MapBounds {
+                        north: row.get(5)?,
+                        south: row.get(6)?,
+                        east: row.get(7)?,
+                        west: row.get(8)?,
+                    },
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(clusters)
+    }
+
+    /// 按内置地名索引重新计算所有媒体的地名，返回地名有变化的媒体数量
+    ///
+    /// 地名索引不可用时返回错误，不修改已有地名。
+    pub async fn refresh_places(&self) -> Result<usize, Box<dyn std::error::Error>> {
+        if !reverse_geocoder::is_available() {
+            return Err("离线地名索引不可用，此版本构建时未包含地名数据".into());
+        }
+        let conn = self.get_connection()?;
+        let tx = conn.unchecked_transaction()?;
+
+        let updated = update_places(&tx)?;
+        tx.commit()?;
+
+        Ok(updated)
+    }
+}
+
+/// 按内置地名索引重新计算所有带 GPS 坐标的媒体的地名，返回地名有变化的媒体数量
+///
+/// 没有坐标的媒体会清除地名；只更新变化的行，避免无谓地重建全文索引。
+/// 地名索引不可用时不做修改，以免清空已有地名。迁移中也会调用，因此不单独开启事务。
+pub(crate) fn update_places(conn: &Connection) -> rusqlite::Result<usize> {
+    if !reverse_geocoder::is_available() {
+        warn!("离线地名索引不可用，跳过地名更新");
+        return Ok(0);
+    }
+
+    let mut updated = conn.execute(
+        "UPDATE media_records SET country = NULL, region = NULL, city = NULL
+         WHERE (latitude IS NULL OR longitude IS NULL)
+           AND (country IS NOT NULL OR region IS NOT NULL OR city IS NOT NULL)",
+        [],
+    )?;
+
+    {
+        let mut select = conn.prepare(
+            "SELECT id, latitude, longitude FROM media_records
+             WHERE latitude IS NOT NULL AND longitude IS NOT NULL",
+        )?;
+        let mut update = conn.prepare(
+            "UPDATE media_records SET country = ?1, region = ?2, city = ?3
+             WHERE id = ?4 AND (country IS NOT ?1 OR region IS NOT ?2 OR city IS NOT ?3)",
+        )?;
+
+        let mut rows = select.query([])?;
+        while let Some(row) = rows.next()? {
+            let media_id: String = row.get(0)?;
+            let place = reverse_geocode(row.get(1)?, row.get(2)?).unwrap_or_default();
+            updated +=
+                update.execute(params![place.country, place.region, place.city, media_id])?;
+        }
+    }
+
+    info!("已更新 {} 条媒体的地名", updated);
+    Ok(updated)
+}
@@ -1,14 +1,22 @@
-use chrono::{DateTime, Utc};
-use log::{error, info};
-use rusqlite::{params, Connection};
+use chrono::{DateTime, Duration, Utc};
+use log::{error, info, warn};
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
 use serde_json;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
 use tauri::AppHandle;
 use uuid::Uuid;
 
-use crate::database::get_database_path;
-use crate::models::image::{MediaKind, MediaRecord};
+use crate::database::journal_repository::{record_journal, value_to_json, JournalChange};
+use crate::database::open_connection;
+use crate::database::smart_album_repository::compile_rule;
+use crate::models::image::{ColorLabel, MediaKind, MediaRecord, PickFlag};
+use crate::models::media_filter::{MediaFilter, MediaSort, MediaSortField};
+use crate::models::search::SearchResult;
+use crate::models::smart_album::SmartRule;
+use crate::models::timeline::{TimelineBucket, TimelineGranularity};
+use crate::utils::search_query::parse_search_query;
 
 /// SQLite 媒体仓库实现
 pub struct MediaRepository {
@@ -27,13 +35,7 @@ impl MediaRepository {
 
     /// 获取数据库连接
     fn get_connection(&self) -> Result<Connection, Box<dyn std::error::Error>> {
-        let db_path = get_database_path(&self.app)?;
-        let conn = Connection::open(db_path)?;
-
-        // 执行迁移脚本
-        conn.execute(include_str!("migrations/001_create_media_records.sql"), [])?;
-
-        Ok(conn)
+        open_connection(&self.app)
     }
 
     /// 通过路径查找媒体记录
@@ -65,6 +67,21 @@ impl MediaRepository {
         }
     }
 
+    /// 通过 ID 查找媒体记录
+    pub async fn find_by_id(
+        &self,
+        id: &str,
+    ) -> Result<Option<MediaRecord>, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+
+        let sql = format!("SELECT {} FROM media_records WHERE id = ?1", MEDIA_COLUMNS);
+        match conn.query_row(&sql, params![id], map_media_row) {
+            Ok(record) => Ok(Some(record)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(Box::new(e)),
+        }
+    }
+
     /// 保存媒体记录到 SQLite
     pub async fn save(&self, record: &MediaRecord) -> Result<(), Box<dyn std::error::Error>> {
         // 保存到数据库
@@ -102,20 +119,6 @@ impl MediaRepository {
         }
     }
 
-    /// 删除媒体记录
-    pub async fn delete_by_path(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
-        // 从数据库删除
-        self.delete_from_database(path).await?;
-
-        // 从缓存删除
-        if let Ok(mut cache) = self.cache.lock() {
-            cache.remove(path);
-        }
-
-        info!("已从 SQLite 数据库删除媒体记录: {}", path);
-        Ok(())
-    }
-
     /// 检查文件是否存在于数据库中
     pub async fn _exists_by_path(&self, path: &str) -> Result<bool, Box<dyn std::error::Error>> {
         // 首先检查缓存
@@ -142,133 +145,361 @@ impl MediaRepository {
         }
     }
 
-    /// 清空所有记录
-    pub async fn clear_all(&self) -> Result<(), Box<dyn std::error::Error>> {
-        // 清空数据库
-        self.clear_database().await?;
+    /// 按筛选条件和排序获取媒体记录
+    pub async fn find_filtered(
+        &self,
+        filter: &MediaFilter,
+        sort: &MediaSort,
+    ) -> Result<Vec<MediaRecord>, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
 
-        // 清空缓存
-        if let Ok(mut cache) = self.cache.lock() {
-            cache.clear();
-        }
+        let mut values = Vec::new();
+        let condition = filter_condition(filter, &mut values);
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM media_records WHERE {} ORDER BY {}",
+            MEDIA_COLUMNS,
+            condition,
+            sort_clause(sort)
+        ))?;
+        let records = stmt
+            .query_map(params_from_iter(values), map_media_row)?
+            .collect::<Result<Vec<_>, _>>()?;
 
-        info!("已清空 SQLite 数据库中的所有媒体记录");
-        Ok(())
+        Ok(records)
     }
 
-    /// 从数据库查询单个记录
-    async fn query_from_database(
+    /// 按本地时间的年、月或日统计媒体数量，并给出每个分组在媒体列表中的起始位置
+    ///
+    /// 分组顺序与 `find_filtered` 使用相同筛选和排序时的顺序一致，仅支持按拍摄时间或导入时间排序；
+    /// 按拍摄时间排序时，没有拍摄时间的媒体按导入时间归组。
+    pub async fn find_timeline(
         &self,
-        path: &str,
-    ) -> Result<Option<MediaRecord>, Box<dyn std::error::Error>> {
-        info!("正在从 SQLite 数据库查询路径: {}", path);
+        granularity: TimelineGranularity,
+        filter: &MediaFilter,
+        sort: &MediaSort,
+    ) -> Result<Vec<TimelineBucket>, Box<dyn std::error::Error>> {
+        let column = match sort.field {
+            MediaSortField::CreatedAt => "media_records.created_at",
+            MediaSortField::TakenDate => {
+                "COALESCE(media_records.taken_date, media_records.created_at)"
+            }
+            _ => return Err("时间线仅支持按拍摄时间或导入时间排序".into()),
+        };
+        let direction = if sort.ascending { "ASC" } else { "DESC" };
 
         let conn = self.get_connection()?;
 
-        let mut stmt = conn.prepare(
-            "SELECT id, path, name, kind, mime_type, size, width, height, duration, 
-             thumbnail_path, taken_date, created_at, updated_at, tags, album_ids, face_count 
-             FROM media_records WHERE path = ?1",
-        )?;
+        let mut values = vec![Value::Text(granularity.strftime_format().to_string())];
+        let condition = filter_condition(filter, &mut values);
+        let mut stmt = conn.prepare(&format!(
+            "SELECT strftime(?, {}, 'localtime') AS bucket, COUNT(*)
+             FROM media_records WHERE {}
+             GROUP BY bucket ORDER BY bucket {}",
+            column, condition, direction
+        ))?;
+        let rows = stmt
+            .query_map(params_from_iter(values), |row| {
+                Ok((row.get::<_, Option<String>>(0)?, row.get::<_, i64>(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut buckets = Vec::with_capacity(rows.len());
+        let mut offset = 0;
+        for (key, count) in rows {
+            let count = count as u64;
+            // 无法解析的时间不会出现在任何分组中，但仍占据列表位置
+            if let Some(key) = key {
+                let mut parts = key.split('-').map(|part| part.parse::<u32>().ok());
+                let year = parts.next().flatten().unwrap_or_default() as i32;
+                buckets.push(TimelineBucket {
+                    year,
+                    month: parts.next().flatten(),
+                    day: parts.next().flatten(),
+                    key,
+                    count,
+                    offset,
+                });
+            }
+            offset += count;
+        }
 
-        let result = stmt.query_row(params![path], |row| {
-            // 解析 UUID
-            let id_str: String = row.get(0)?;
-            let id = Uuid::parse_str(&id_str).map_err(|e| {
-                rusqlite::Error::InvalidColumnType(
-                    0,
-                    format!("Invalid UUID: {}", e),
-                    rusqlite::types::Type::Text,
-                )
-            })?;
+        Ok(buckets)
+    }
 
-            // 解析基本字段
-            let path: String = row.get(1)?;
-            let name: String = row.get(2)?;
-            let kind_str: String = row.get(3)?;
-
-            // 解析 MediaKind
-            let kind = match kind_str.as_str() {
-                "Image" => MediaKind::Image,
-                "Vedio" => MediaKind::Vedio,
-                _ => MediaKind::Other,
-            };
+    /// 按查询语法搜索媒体，并与列表筛选条件组合
+    ///
+    /// 查询中的普通词全文搜索文件名、文件夹、标签、相册名、说明、相机/镜头和地点，
+    /// 字段条件（如 `iso:>3200`）编译为对应的列条件，语法见 `parse_search_query`。
+    /// 未指定排序时按相关度排序；未指定 `limit` 时返回全部结果；查询为空时返回空列表。
+    pub async fn search(
+        &self,
+        query: &str,
+        filter: &MediaFilter,
+        sort: Option<&MediaSort>,
+        limit: Option<usize>,
+    ) -> Result<Vec<SearchResult>, Box<dyn std::error::Error>> {
+        if query.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // 顶层的普通词通过全文索引联接以计算相关度和片段，其余条件编译为 WHERE
+        let (terms, rule) = split_text_terms(parse_search_query(query)?);
+        let match_query = build_match_query(&terms.join(" "));
 
-            // 解析可选字段
-            let mime_type: Option<String> = row.get(4)?;
-            let size: Option<u64> = row.get::<_, Option<i64>>(5)?.map(|s| s as u64);
-            let width: Option<u32> = row.get::<_, Option<i64>>(6)?.map(|w| w as u32);
-            let height: Option<u32> = row.get::<_, Option<i64>>(7)?.map(|h| h as u32);
-            let duration: Option<f64> = row.get(8)?;
-            let thumbnail_path: Option<String> = row.get(9)?;
-
-            // 解析时间字段
-            let taken_date = if let Ok(date_str) = row.get::<_, Option<String>>(10) {
-                date_str.and_then(|s| {
-                    DateTime::parse_from_rfc3339(&s)
-                        .ok()
-                        .map(|dt| dt.with_timezone(&Utc))
+        let conn = self.get_connection()?;
+        refresh_search_index(&conn)?;
+
+        let mut values = Vec::new();
+        if let Some(match_query) = &match_query {
+            values.push(Value::Text(match_query.clone()));
+        }
+        let rule_condition = compile_rule(&rule, &mut values)?;
+        let filter_condition = filter_condition(filter, &mut values);
+        // SQLite 中 LIMIT -1 表示不限制
+        values.push(Value::Integer(limit.map_or(-1, |limit| limit as i64)));
+
+        let sql = match &match_query {
+            Some(_) => format!(
+                "SELECT {}, {} AS search_rank,
+                        snippet(media_search, -1, char(2), char(3), '…', 12)
+                 FROM media_search
+                 JOIN media_records ON media_records.id = media_search.media_id
+                 WHERE media_search MATCH ? AND ({}) AND {}
+                 ORDER BY {} LIMIT ?",
+                MEDIA_COLUMNS,
+                SEARCH_RANK,
+                rule_condition,
+                filter_condition,
+                sort.map(sort_clause)
+                    .unwrap_or_else(|| "search_rank".to_string())
+            ),
+            None => format!(
+                "SELECT {}, 0.0, NULL FROM media_records
+                 WHERE ({}) AND {}
+                 ORDER BY {} LIMIT ?",
+                MEDIA_COLUMNS,
+                rule_condition,
+                filter_condition,
+                sort_clause(&sort.copied().unwrap_or_default())
+            ),
+        };
+
+        let mut stmt = conn.prepare(&sql)?;
+        let results = stmt
+            .query_map(params_from_iter(values), |row| {
+                Ok(SearchResult {
+                    record: map_media_row(row)?,
+                    rank: row.get(MEDIA_COLUMN_COUNT)?,
+                    snippet: row
+                        .get::<_, Option<String>>(MEDIA_COLUMN_COUNT + 1)?
+                        .map(|snippet| highlight_snippet(&snippet)),
                 })
-            } else {
-                None
-            };
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        info!("搜索 \"{}\" 找到 {} 条结果", query, results.len());
+        Ok(results)
+    }
+
+    /// 批量设置星级评分
+    pub async fn set_rating(
+        &self,
+        media_ids: &[String],
+        rating: u8,
+    ) -> Result<usize, Box<dyn std::error::Error>> {
+        if rating > 5 {
+            return Err(format!("评分必须在 0 到 5 之间: {}", rating).into());
+        }
+        self.update_column(
+            "设置评分",
+            media_ids,
+            "rating",
+            Value::Integer(rating as i64),
+        )
+    }
+
+    /// 批量设置收藏
+    pub async fn set_favorite(
+        &self,
+        media_ids: &[String],
+        favorite: bool,
+    ) -> Result<usize, Box<dyn std::error::Error>> {
+        self.update_column(
+            "设置收藏",
+            media_ids,
+            "favorite",
+            Value::Integer(favorite as i64),
+        )
+    }
+
+    /// 批量设置颜色标签，None 为清除
+    pub async fn set_color_label(
+        &self,
+        media_ids: &[String],
+        color_label: Option<ColorLabel>,
+    ) -> Result<usize, Box<dyn std::error::Error>> {
+        self.update_column(
+            "设置颜色标签",
+            media_ids,
+            "color_label",
+            color_label_value(color_label),
+        )
+    }
+
+    /// 批量设置挑选/排除标记
+    pub async fn set_flag(
+        &self,
+        media_ids: &[String],
+        flag: PickFlag,
+    ) -> Result<usize, Box<dyn std::error::Error>> {
+        self.update_column("设置标记", media_ids, "flag", Value::Integer(flag.to_db()))
+    }
+
+    /// 为每个媒体分别设置评分、颜色标签和挑选标记，作为一次操作记录
+    pub async fn set_marks(
+        &self,
+        label: &str,
+        marks: &[(String, u8, Option<ColorLabel>, PickFlag)],
+    ) -> Result<usize, Box<dyn std::error::Error>> {
+        let mut updates = Vec::new();
+        for (media_id, rating, color_label, flag) in marks {
+            if *rating > 5 {
+                return Err(format!("评分必须在 0 到 5 之间: {}", rating).into());
+            }
+            updates.push((media_id.clone(), "rating", Value::Integer(*rating as i64)));
+            updates.push((
+                media_id.clone(),
+                "color_label",
+                color_label_value(*color_label),
+            ));
+            updates.push((media_id.clone(), "flag", Value::Integer(flag.to_db())));
+        }
+        self.update_columns(label, &updates)
+    }
 
-            let created_at_str: String = row.get(11)?;
-            let created_at = DateTime::parse_from_rfc3339(&created_at_str)
-                .map_err(|e| {
-                    rusqlite::Error::InvalidColumnType(
-                        11,
-                        format!("Invalid created_at: {}", e),
-                        rusqlite::types::Type::Text,
-                    )
-                })?
-                .with_timezone(&Utc);
-
-            let updated_at_str: String = row.get(12)?;
-            let updated_at = DateTime::parse_from_rfc3339(&updated_at_str)
-                .map_err(|e| {
-                    rusqlite::Error::InvalidColumnType(
-                        12,
-                        format!("Invalid updated_at: {}", e),
-                        rusqlite::types::Type::Text,
-                    )
-                })?
-                .with_timezone(&Utc);
-
-            // 解析 JSON 字段
-            let tags = if let Ok(tags_str) = row.get::<_, Option<String>>(13) {
-                tags_str.and_then(|s| serde_json::from_str::<Vec<String>>(&s).ok())
-            } else {
-                None
+    /// 批量调整拍摄时间，`offset_seconds` 为负数时提前，没有拍摄时间的媒体保持不变
+    pub async fn shift_taken_dates(
+        &self,
+        media_ids: &[String],
+        offset_seconds: i64,
+    ) -> Result<usize, Box<dyn std::error::Error>> {
+        let offset = Duration::try_seconds(offset_seconds)
+            .ok_or_else(|| format!("时间偏移超出范围: {}", offset_seconds))?;
+        let conn = self.get_connection()?;
+
+        let mut updates = Vec::new();
+        for media_id in media_ids {
+            let taken_date: Option<Option<String>> = conn
+                .query_row(
+                    "SELECT taken_date FROM media_records WHERE id = ?1",
+                    params![media_id],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            // 旧版本会把空值写成字符串 "null"，无法解析的拍摄时间同样跳过
+            let Some(taken_date) = taken_date
+                .flatten()
+                .and_then(|date| DateTime::parse_from_rfc3339(&date).ok())
+            else {
+                continue;
             };
+            let shifted = taken_date
+                .with_timezone(&Utc)
+                .checked_add_signed(offset)
+                .ok_or_else(|| format!("调整后的拍摄时间超出范围: {}", media_id))?;
+            updates.push((
+                media_id.clone(),
+                "taken_date",
+                Value::Text(shifted.to_rfc3339()),
+            ));
+        }
+
+        self.update_columns("调整拍摄时间", &updates)
+    }
+
+    /// 将选中媒体的某一列设置为同一个值，返回实际更新的数量
+    fn update_column(
+        &self,
+        label: &str,
+        media_ids: &[String],
+        column: &'static str,
+        value: Value,
+    ) -> Result<usize, Box<dyn std::error::Error>> {
+        let updates: Vec<_> = media_ids
+            .iter()
+            .map(|media_id| (media_id.clone(), column, value.clone()))
+            .collect();
+        self.update_columns(label, &updates)
+    }
 
-            let album_ids = if let Ok(album_ids_str) = row.get::<_, Option<String>>(14) {
-                album_ids_str.and_then(|s| serde_json::from_str::<Vec<Uuid>>(&s).ok())
-            } else {
-                None
+    /// 在一个事务中更新媒体的列并记录到操作日志，返回实际更新的媒体数量
+    ///
+    /// `updates` 中每一项为 (媒体 ID, 列名, 新值)，值没有变化的列不记录到操作日志。
+    fn update_columns(
+        &self,
+        label: &str,
+        updates: &[(String, &'static str, Value)],
+    ) -> Result<usize, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+        let tx = conn.unchecked_transaction()?;
+
+        let now = Utc::now().to_rfc3339();
+        let mut updated = HashSet::new();
+        let mut changes = Vec::new();
+        for (media_id, column, value) in updates {
+            let before: Option<Value> = tx
+                .prepare_cached(&format!(
+                    "SELECT {} FROM media_records WHERE id = ?1",
+                    column
+                ))?
+                .query_row(params![media_id], |row| row.get(0))
+                .optional()?;
+            let Some(before) = before else {
+                continue;
             };
 
-            let face_count: Option<u32> = row.get::<_, Option<i64>>(15)?.map(|f| f as u32);
-
-            Ok(MediaRecord {
-                id,
-                path,
-                name,
-                kind,
-                mime_type,
-                size,
-                width,
-                height,
-                duration,
-                thumbnail_path,
-                taken_date,
-                created_at,
-                updated_at,
-                tags,
-                album_ids,
-                face_count,
-            })
-        });
+            tx.prepare_cached(&format!(
+                "UPDATE media_records SET {} = ?1, updated_at = ?2 WHERE id = ?3",
+                column
+            ))?
+            .execute(params![value, now, media_id])?;
+            updated.insert(media_id.as_str());
+
+            if before != *value {
+                changes.push(JournalChange::MediaField {
+                    media_id: media_id.clone(),
+                    column: column.to_string(),
+                    before: value_to_json(&before),
+                    after: value_to_json(value),
+                });
+            }
+        }
+        record_journal(&tx, label, &changes)?;
+        tx.commit()?;
+
+        // 缓存以路径为键，直接清空以免返回旧值
+        if let Ok(mut cache) = self.cache.lock() {
+            cache.clear();
+        }
+
+        info!("{}：已更新 {} 个媒体", label, updated.len());
+        Ok(updated.len())
+    }
+
+    /// 从数据库查询单个记录
+    async fn query_from_database(
+        &self,
+        path: &str,
+    ) -> Result<Option<MediaRecord>, Box<dyn std::error::Error>> {
+        info!("正在从 SQLite 数据库查询路径: {}", path);
+
+        let conn = self.get_connection()?;
+
+        let sql = format!(
+            "SELECT {} FROM media_records WHERE path = ?1",
+            MEDIA_COLUMNS
+        );
+        let result = conn.query_row(&sql, params![path], map_media_row);
 
         match result {
             Ok(record) => Ok(Some(record)),
@@ -286,71 +517,93 @@ impl MediaRepository {
 
         let conn = self.get_connection()?;
 
-        // 将 JSON 字段序列化
-        let tags_json = record
-            .tags
-            .as_ref()
-            .map(|tags| serde_json::to_string(tags).unwrap_or_else(|_| "[]".to_string()))
-            .unwrap_or_else(|| "null".to_string());
-
-        let album_ids_json = record
-            .album_ids
-            .as_ref()
-            .map(|ids| serde_json::to_string(ids).unwrap_or_else(|_| "[]".to_string()))
-            .unwrap_or_else(|| "null".to_string());
-
+        // 标签和相册分别保存在 media_tags / album_media 表中，这里不再写入
         // 将时间字段转换为 RFC3339 字符串
-        let taken_date_str = record
-            .taken_date
-            .as_ref()
-            .map(|dt| dt.to_rfc3339())
-            .unwrap_or_else(|| "null".to_string());
-
+        let taken_date_str = record.taken_date.as_ref().map(|dt| dt.to_rfc3339());
         let created_at_str = record.created_at.to_rfc3339();
         let updated_at_str = record.updated_at.to_rfc3339();
 
-        // 使用 REPLACE 实现 UPSERT 操作，根据路径去重
+        // 同一路径的媒体在回收站中时，重新导入会将其移出回收站，应用回收站中的旧文件不再需要
+        let stale_trash_path: Option<String> = conn
+            .query_row(
+                "SELECT trash_path FROM media_records WHERE path = ?1 AND trashed_at IS NOT NULL",
+                params![record.path],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten();
+
+        // 根据路径去重；冲突时原地更新，保留原有 ID 以免级联删除标签等关联数据，
+        // 评分、收藏、标签等用户编辑的字段也不会被覆盖；拍摄时间可能已被用户调整，只在为空时写入；
+        // 导入时未检测人脸，保留已有的 face_count；回收站标记被清除
         conn.execute(
-            "REPLACE INTO media_records 
+            "INSERT INTO media_records 
              (id, path, name, kind, mime_type, size, width, height, duration, thumbnail_path, 
-              taken_date, created_at, updated_at, tags, album_ids, face_count) 
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)",
+              taken_date, created_at, updated_at, face_count, 
+              motion_path, motion_offset, motion_length, camera_make, camera_model, 
+              lens_model, iso, aperture, focal_length, latitude, longitude, rating, 
+              favorite, color_label, flag, caption, country, region, city) 
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, 
+                     ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27, ?28, ?29, ?30, 
+                     ?31, ?32, ?33)
+             ON CONFLICT(path) DO UPDATE SET 
+                 name = excluded.name, kind = excluded.kind, mime_type = excluded.mime_type, 
+                 size = excluded.size, width = excluded.width, height = excluded.height, 
+                 duration = excluded.duration, thumbnail_path = excluded.thumbnail_path, 
+                 taken_date = COALESCE(media_records.taken_date, excluded.taken_date), 
+                 updated_at = excluded.updated_at, 
+                 face_count = COALESCE(excluded.face_count, media_records.face_count), 
+                 motion_path = excluded.motion_path, motion_offset = excluded.motion_offset, 
+                 motion_length = excluded.motion_length, camera_make = excluded.camera_make, 
+                 camera_model = excluded.camera_model, lens_model = excluded.lens_model, 
+                 iso = excluded.iso, aperture = excluded.aperture, 
+                 focal_length = excluded.focal_length, latitude = excluded.latitude, 
+                 longitude = excluded.longitude, caption = excluded.caption, 
+                 country = excluded.country, region = excluded.region, city = excluded.city, 
+                 trashed_at = NULL, trash_action = NULL, trash_path = NULL",
             params![
                 record.id.to_string(),
                 record.path,
                 record.name,
-                match record.kind {
-                    MediaKind::Image => "Image",
-                    MediaKind::Vedio => "Vedio",
-                    MediaKind::Other => "Other",
-                },
+                media_kind_to_str(&record.kind),
                 record.mime_type,
                 record.size.map(|s| s as i64),
                 record.width.map(|w| w as i64),
                 record.height.map(|h| h as i64),
                 record.duration,
                 record.thumbnail_path,
-                if record.taken_date.is_some() {
-                    Some(taken_date_str)
-                } else {
-                    None
-                },
+                taken_date_str,
                 created_at_str,
                 updated_at_str,
-                if record.tags.is_some() {
-                    Some(tags_json)
-                } else {
-                    None
-                },
-                if record.album_ids.is_some() {
-                    Some(album_ids_json)
-                } else {
-                    None
-                },
                 record.face_count.map(|f| f as i64),
+                record.motion_path,
+                record.motion_offset.map(|o| o as i64),
+                record.motion_length.map(|l| l as i64),
+                record.camera_make,
+                record.camera_model,
+                record.lens_model,
+                record.iso.map(|i| i as i64),
+                record.aperture,
+                record.focal_length,
+                record.latitude,
+                record.longitude,
+                record.rating as i64,
+                record.favorite,
+                record.color_label.map(|label| label.as_str()),
+                record.flag.to_db(),
+                record.caption,
+                record.country,
+                record.region,
+                record.city,
             ],
         )?;
 
+        if let Some(trash_path) = stale_trash_path {
+            if let Err(e) = std::fs::remove_file(&trash_path) {
+                warn!("删除回收站文件失败 {}: {}", trash_path, e);
+            }
+        }
+
         info!("已成功保存记录到 SQLite 数据库: {}", record.name);
         Ok(())
     }
@@ -363,126 +616,12 @@ impl MediaRepository {
 
         let conn = self.get_connection()?;
 
-        let mut stmt = conn.prepare(
-            "SELECT id, path, name, kind, mime_type, size, width, height, duration, 
-             thumbnail_path, taken_date, created_at, updated_at, tags, album_ids, face_count 
-             FROM media_records ORDER BY created_at DESC",
-        )?;
-
-        let record_iter = stmt.query_map([], |row| {
-            // 解析 UUID
-            let id_str: String = row.get(0)?;
-            let id = Uuid::parse_str(&id_str).map_err(|e| {
-                rusqlite::Error::InvalidColumnType(
-                    0,
-                    format!("Invalid UUID: {}", e),
-                    rusqlite::types::Type::Text,
-                )
-            })?;
-
-            // 解析基本字段
-            let path: String = row.get(1)?;
-            let name: String = row.get(2)?;
-            let kind_str: String = row.get(3)?;
-
-            // 解析 MediaKind
-            let kind = match kind_str.as_str() {
-                "Image" => MediaKind::Image,
-                "Vedio" => MediaKind::Vedio,
-                _ => MediaKind::Other,
-            };
-
-            // 解析可选字段
-            let mime_type: Option<String> = row.get(4)?;
-            let size: Option<u64> = row.get::<_, Option<i64>>(5)?.map(|s| s as u64);
-            let width: Option<u32> = row.get::<_, Option<i64>>(6)?.map(|w| w as u32);
-            let height: Option<u32> = row.get::<_, Option<i64>>(7)?.map(|h| h as u32);
-            let duration: Option<f64> = row.get(8)?;
-            let thumbnail_path: Option<String> = row.get(9)?;
-
-            // 解析时间字段
-            let taken_date = if let Ok(date_str) = row.get::<_, Option<String>>(10) {
-                date_str.and_then(|s| {
-                    if s == "null" {
-                        None
-                    } else {
-                        DateTime::parse_from_rfc3339(&s)
-                            .ok()
-                            .map(|dt| dt.with_timezone(&Utc))
-                    }
-                })
-            } else {
-                None
-            };
-
-            let created_at_str: String = row.get(11)?;
-            let created_at = DateTime::parse_from_rfc3339(&created_at_str)
-                .map_err(|e| {
-                    rusqlite::Error::InvalidColumnType(
-                        11,
-                        format!("Invalid created_at: {}", e),
-                        rusqlite::types::Type::Text,
-                    )
-                })?
-                .with_timezone(&Utc);
-
-            let updated_at_str: String = row.get(12)?;
-            let updated_at = DateTime::parse_from_rfc3339(&updated_at_str)
-                .map_err(|e| {
-                    rusqlite::Error::InvalidColumnType(
-                        12,
-                        format!("Invalid updated_at: {}", e),
-                        rusqlite::types::Type::Text,
-                    )
-                })?
-                .with_timezone(&Utc);
-
-            // 解析 JSON 字段
-            let tags = if let Ok(tags_str) = row.get::<_, Option<String>>(13) {
-                tags_str.and_then(|s| {
-                    if s == "null" {
-                        None
-                    } else {
-                        serde_json::from_str::<Vec<String>>(&s).ok()
-                    }
-                })
-            } else {
-                None
-            };
-
-            let album_ids = if let Ok(album_ids_str) = row.get::<_, Option<String>>(14) {
-                album_ids_str.and_then(|s| {
-                    if s == "null" {
-                        None
-                    } else {
-                        serde_json::from_str::<Vec<Uuid>>(&s).ok()
-                    }
-                })
-            } else {
-                None
-            };
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM media_records WHERE trashed_at IS NULL ORDER BY created_at DESC",
+            MEDIA_COLUMNS
+        ))?;
 
-            let face_count: Option<u32> = row.get::<_, Option<i64>>(15)?.map(|f| f as u32);
-
-            Ok(MediaRecord {
-                id,
-                path,
-                name,
-                kind,
-                mime_type,
-                size,
-                width,
-                height,
-                duration,
-                thumbnail_path,
-                taken_date,
-                created_at,
-                updated_at,
-                tags,
-                album_ids,
-                face_count,
-            })
-        })?;
+        let record_iter = stmt.query_map([], map_media_row)?;
 
         let mut records = Vec::new();
         for record_result in record_iter {
@@ -492,35 +631,368 @@ impl MediaRepository {
         info!("从 SQLite 数据库查询到 {} 条记录", records.len());
         Ok(records)
     }
+}
 
-    /// 从数据库删除记录
-    async fn delete_from_database(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
-        info!("正在从 SQLite 数据库删除记录: {}", path);
+/// 查询媒体记录时使用的列，顺序与 `map_media_row` 保持一致
+pub(crate) const MEDIA_COLUMNS: &str =
+    "media_records.id, media_records.path, media_records.name, media_records.kind, 
+     media_records.mime_type, media_records.size, media_records.width, media_records.height, 
+     media_records.duration, media_records.thumbnail_path, media_records.taken_date, 
+     media_records.created_at, media_records.updated_at, 
+     (SELECT json_group_array(tags.path) FROM media_tags 
+      JOIN tags ON tags.id = media_tags.tag_id 
+      WHERE media_tags.media_id = media_records.id HAVING COUNT(*) > 0), 
+     (SELECT json_group_array(album_media.album_id) FROM album_media 
+      WHERE album_media.media_id = media_records.id HAVING COUNT(*) > 0), media_records.face_count, media_records.motion_path, 
+     media_records.motion_offset, media_records.motion_length, media_records.camera_make, 
+     media_records.camera_model, media_records.lens_model, media_records.iso, 
+     media_records.aperture, media_records.focal_length, media_records.latitude, 
+     media_records.longitude, media_records.rating, media_records.favorite, 
+     media_records.color_label, media_records.flag, media_records.caption, 
+     media_records.country, media_records.region, media_records.city";
+
+/// 将列表筛选条件编译为针对 `media_records` 的 WHERE 条件，参数追加到 `values`
+pub(crate) fn filter_condition(filter: &MediaFilter, values: &mut Vec<Value>) -> String {
+    // 回收站中的媒体不出现在列表、搜索和时间线中
+    let mut parts = vec!["media_records.trashed_at IS NULL".to_string()];
+
+    if let Some(min_rating) = filter.min_rating {
+        parts.push("media_records.rating >= ?".to_string());
+        values.push(Value::Integer(min_rating as i64));
+    }
+    if let Some(favorite) = filter.favorite {
+        parts.push("media_records.favorite = ?".to_string());
+        values.push(Value::Integer(favorite as i64));
+    }
+    if !filter.color_labels.is_empty() {
+        let placeholders = vec!["?"; filter.color_labels.len()].join(", ");
+        parts.push(format!("media_records.color_label IN ({})", placeholders));
+        values.extend(
+            filter
+                .color_labels
+                .iter()
+                .map(|label| Value::Text(label.as_str().to_string())),
+        );
+    }
+    if !filter.flags.is_empty() {
+        let placeholders = vec!["?"; filter.flags.len()].join(", ");
+        parts.push(format!("media_records.flag IN ({})", placeholders));
+        values.extend(filter.flags.iter().map(|flag| Value::Integer(flag.to_db())));
+    }
 
-        let conn = self.get_connection()?;
+    parts.join(" AND ")
+}
 
-        let affected_rows =
-            conn.execute("DELETE FROM media_records WHERE path = ?1", params![path])?;
+/// 列表排序对应的 ORDER BY 子句，相同值时按导入时间和 ID 保持稳定顺序
+pub(crate) fn sort_clause(sort: &MediaSort) -> String {
+    let column = match sort.field {
+        MediaSortField::CreatedAt => "media_records.created_at",
+        MediaSortField::TakenDate => "COALESCE(media_records.taken_date, media_records.created_at)",
+        MediaSortField::Name => "media_records.name COLLATE NOCASE",
+        MediaSortField::Size => "media_records.size",
+        MediaSortField::Rating => "media_records.rating",
+    };
+    let direction = if sort.ascending { "ASC" } else { "DESC" };
+    format!(
+        "{column} {direction}, media_records.created_at {direction}, media_records.id {direction}"
+    )
+}
 
-        if affected_rows > 0 {
-            info!("已成功从 SQLite 数据库删除记录: {}", path);
-        } else {
-            info!("未找到要删除的记录: {}", path);
-        }
+/// `MEDIA_COLUMNS` 中的列数，附加列从该索引开始
+pub(crate) const MEDIA_COLUMN_COUNT: usize = 35;
 
-        Ok(())
+/// 搜索相关度，各列权重依次为 media_id、文件名、文件夹、标签、相册、说明、相机、地点
+const SEARCH_RANK: &str = "bm25(media_search, 0.0, 10.0, 2.0, 5.0, 4.0, 3.0, 2.0, 4.0)";
+
+/// 重建 `media_search_dirty` 中记录的媒体的全文索引
+///
+/// 触发器会在媒体、标签或相册变化时把相关媒体加入队列，搜索前调用即可保证索引最新。
+pub(crate) fn refresh_search_index(conn: &Connection) -> rusqlite::Result<usize> {
+    let tx = conn.unchecked_transaction()?;
+
+    let pending: i64 = tx.query_row("SELECT COUNT(*) FROM media_search_dirty", [], |row| {
+        row.get(0)
+    })?;
+    if pending == 0 {
+        return Ok(0);
     }
 
-    /// 清空数据库
-    async fn clear_database(&self) -> Result<(), Box<dyn std::error::Error>> {
-        info!("正在清空 SQLite 数据库");
+    tx.execute(
+        "DELETE FROM media_search
+         WHERE media_id IN (SELECT media_id FROM media_search_dirty)",
+        [],
+    )?;
+
+    let mut indexed = 0;
+    {
+        let mut select = tx.prepare(
+            "SELECT m.id, m.path, m.name,
+                    (SELECT group_concat(tags.path, ' ') FROM media_tags
+                     JOIN tags ON tags.id = media_tags.tag_id
+                     WHERE media_tags.media_id = m.id),
+                    (SELECT group_concat(albums.name, ' ') FROM album_media
+                     JOIN albums ON albums.id = album_media.album_id
+                     WHERE album_media.media_id = m.id),
+                    m.caption, m.camera_make, m.camera_model, m.lens_model,
+                    m.city, m.region, m.country
+             FROM media_search_dirty d JOIN media_records m ON m.id = d.media_id",
+        )?;
+        let mut insert = tx.prepare(
+            "INSERT INTO media_search (media_id, name, folder, tags, albums, caption, camera, places)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        )?;
 
-        let conn = self.get_connection()?;
+        let mut rows = select.query([])?;
+        while let Some(row) = rows.next()? {
+            let media_id: String = row.get(0)?;
+            let path: String = row.get(1)?;
+            let folder = std::path::Path::new(&path)
+                .parent()
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let camera = [row.get::<_, Option<String>>(6)?, row.get(7)?, row.get(8)?]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+                .join(" ");
+            let places = [row.get::<_, Option<String>>(9)?, row.get(10)?, row.get(11)?]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            insert.execute(params![
+                media_id,
+                row.get::<_, String>(2)?,
+                folder,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, Option<String>>(4)?,
+                row.get::<_, Option<String>>(5)?,
+                camera,
+                places,
+            ])?;
+            indexed += 1;
+        }
+    }
 
-        let affected_rows = conn.execute("DELETE FROM media_records", [])?;
+    tx.execute("DELETE FROM media_search_dirty", [])?;
+    tx.commit()?;
 
-        info!("已清空 SQLite 数据库，删除了 {} 条记录", affected_rows);
+    info!("已更新 {} 条媒体的搜索索引", indexed);
+    Ok(indexed)
+}
 
-        Ok(())
+/// 取出规则树顶层（未取反、不在 OR 中）的全文搜索词，返回剩余规则
+fn split_text_terms(rule: SmartRule) -> (Vec<String>, SmartRule) {
+    match rule {
+        SmartRule::Text { query } => (vec![query], SmartRule::All { rules: Vec::new() }),
+        SmartRule::All { rules } => {
+            let mut terms = Vec::new();
+            let mut rest = Vec::new();
+            for rule in rules {
+                match rule {
+                    SmartRule::Text { query } => terms.push(query),
+                    rule => rest.push(rule),
+                }
+            }
+            (terms, SmartRule::All { rules: rest })
+        }
+        rule => (Vec::new(), rule),
+    }
+}
+
+/// 将用户输入转换为 FTS5 查询：每个词都做前缀匹配，多个词需同时匹配
+pub(crate) fn build_match_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|term| format!("\"{}\" *", term.replace('"', "\"\"")))
+        .collect();
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" AND "))
+    }
+}
+
+/// 将 snippet 中的 \u{2} / \u{3} 标记替换为 `<mark>`，其余内容做 HTML 转义
+fn highlight_snippet(snippet: &str) -> String {
+    let mut html = String::with_capacity(snippet.len() + 16);
+    for c in snippet.chars() {
+        match c {
+            '\u{2}' => html.push_str("<mark>"),
+            '\u{3}' => html.push_str("</mark>"),
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            '\'' => html.push_str("&#39;"),
+            c => html.push(c),
+        }
+    }
+    html
+}
+
+/// 媒体类型在数据库中的存储值
+pub(crate) fn media_kind_to_str(kind: &MediaKind) -> &'static str {
+    match kind {
+        MediaKind::Image => "Image",
+        MediaKind::Vedio => "Vedio",
+        MediaKind::Other => "Other",
     }
 }
+
+/// 颜色标签在数据库中的值，None 为 NULL
+fn color_label_value(color_label: Option<ColorLabel>) -> Value {
+    match color_label {
+        Some(label) => Value::Text(label.as_str().to_string()),
+        None => Value::Null,
+    }
+}
+
+/// 将一行查询结果解析为媒体记录
+pub(crate) fn map_media_row(row: &rusqlite::Row) -> rusqlite::Result<MediaRecord> {
+    // 解析 UUID
+    let id_str: String = row.get(0)?;
+    let id = Uuid::parse_str(&id_str).map_err(|e| {
+        rusqlite::Error::InvalidColumnType(
+            0,
+            format!("Invalid UUID: {}", e),
+            rusqlite::types::Type::Text,
+        )
+    })?;
+
+    // 解析基本字段
+    let path: String = row.get(1)?;
+    let name: String = row.get(2)?;
+    let kind_str: String = row.get(3)?;
+
+    // 解析 MediaKind
+    let kind = match kind_str.as_str() {
+        "Image" => MediaKind::Image,
+        "Vedio" => MediaKind::Vedio,
+        _ => MediaKind::Other,
+    };
+
+    // 解析可选字段
+    let mime_type: Option<String> = row.get(4)?;
+    let size: Option<u64> = row.get::<_, Option<i64>>(5)?.map(|s| s as u64);
+    let width: Option<u32> = row.get::<_, Option<i64>>(6)?.map(|w| w as u32);
+    let height: Option<u32> = row.get::<_, Option<i64>>(7)?.map(|h| h as u32);
+    let duration: Option<f64> = row.get(8)?;
+    let thumbnail_path: Option<String> = row.get(9)?;
+
+    // 解析时间字段（旧版本会把空值写成字符串 "null"）
+    let taken_date = if let Ok(date_str) = row.get::<_, Option<String>>(10) {
+        date_str.and_then(|s| {
+            DateTime::parse_from_rfc3339(&s)
+                .ok()
+                .map(|dt| dt.with_timezone(&Utc))
+        })
+    } else {
+        None
+    };
+
+    let created_at_str: String = row.get(11)?;
+    let created_at = DateTime::parse_from_rfc3339(&created_at_str)
+        .map_err(|e| {
+            rusqlite::Error::InvalidColumnType(
+                11,
+                format!("Invalid created_at: {}", e),
+                rusqlite::types::Type::Text,
+            )
+        })?
+        .with_timezone(&Utc);
+
+    let updated_at_str: String = row.get(12)?;
+    let updated_at = DateTime::parse_from_rfc3339(&updated_at_str)
+        .map_err(|e| {
+            rusqlite::Error::InvalidColumnType(
+                12,
+                format!("Invalid updated_at: {}", e),
+                rusqlite::types::Type::Text,
+            )
+        })?
+        .with_timezone(&Utc);
+
+    // 解析 JSON 字段（标签为 media_tags 关联的完整标签路径）
+    let tags = if let Ok(tags_str) = row.get::<_, Option<String>>(13) {
+        tags_str.and_then(|s| serde_json::from_str::<Vec<String>>(&s).ok())
+    } else {
+        None
+    };
+
+    let album_ids = if let Ok(album_ids_str) = row.get::<_, Option<String>>(14) {
+        album_ids_str.and_then(|s| serde_json::from_str::<Vec<Uuid>>(&s).ok())
+    } else {
+        None
+    };
+
+    let face_count: Option<u32> = row.get::<_, Option<i64>>(15)?.map(|f| f as u32);
+
+    // 解析实况照片 / 动态照片字段
+    let motion_path: Option<String> = row.get(16)?;
+    let motion_offset: Option<u64> = row.get::<_, Option<i64>>(17)?.map(|o| o as u64);
+    let motion_length: Option<u64> = row.get::<_, Option<i64>>(18)?.map(|l| l as u64);
+
+    // 解析拍摄信息和评分
+    let camera_make: Option<String> = row.get(19)?;
+    let camera_model: Option<String> = row.get(20)?;
+    let lens_model: Option<String> = row.get(21)?;
+    let iso: Option<u32> = row.get::<_, Option<i64>>(22)?.map(|i| i as u32);
+    let aperture: Option<f64> = row.get(23)?;
+    let focal_length: Option<f64> = row.get(24)?;
+    let latitude: Option<f64> = row.get(25)?;
+    let longitude: Option<f64> = row.get(26)?;
+    let rating = row.get::<_, i64>(27)?.clamp(0, 5) as u8;
+
+    // 解析收藏、颜色标签和挑选标记
+    let favorite: bool = row.get(28)?;
+    let color_label = row
+        .get::<_, Option<String>>(29)?
+        .and_then(|label| ColorLabel::parse(&label));
+    let flag = PickFlag::from_db(row.get(30)?);
+    let caption: Option<String> = row.get(31)?;
+
+    // 解析地名
+    let country: Option<String> = row.get(32)?;
+    let region: Option<String> = row.get(33)?;
+    let city: Option<String> = row.get(34)?;
+
+    Ok(MediaRecord {
+        id,
+        path,
+        name,
+        kind,
+        mime_type,
+        size,
+        width,
+        height,
+        duration,
+        thumbnail_path,
+        taken_date,
+        created_at,
+        updated_at,
+        tags,
+        album_ids,
+        face_count,
+        motion_path,
+        motion_offset,
+        motion_length,
+        camera_make,
+        camera_model,
+        lens_model,
+        iso,
+        aperture,
+        focal_length,
+        latitude,
+        longitude,
+        country,
+        region,
+        city,
+        rating,
+        favorite,
+        color_label,
+        flag,
+        caption,
+    })
+}
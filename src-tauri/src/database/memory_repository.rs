@@ -0,0 +1,210 @@
+use std::collections::HashSet;
+
+use chrono::{Local, Utc};
+use log::info;
+use rusqlite::{params, Connection};
+use tauri::AppHandle;
+use uuid::Uuid;
+
+use crate::database::media_repository::{map_media_row, MEDIA_COLUMNS};
+use crate::database::{open_connection, parse_time_column, parse_uuid_column};
+use crate::models::image::MediaRecord;
+use crate::models::memory::{MemoryKind, MemoryRecord};
+use crate::utils::memory_planner::{plan_memories, MemoryPhoto};
+
+/// 查询回忆时使用的列，顺序与 `map_memory_row` 保持一致
+const MEMORY_COLUMNS: &str = "mem.id, mem.kind, mem.title, mem.subtitle,
+     mem.start_date, mem.end_date, c.id, c.thumbnail_path,
+     (SELECT COUNT(*) FROM memory_media mm
+      JOIN media_records m ON m.id = mm.media_id
+      WHERE mm.memory_id = mem.id AND m.trashed_at IS NULL),
+     mem.created_at, mem.updated_at";
+
+/// SQLite 回忆仓库实现
+pub struct MemoryRepository {
+    app: AppHandle,
+}
+
+impl MemoryRepository {
+    pub fn new(app: AppHandle) -> Self {
+        Self { app }
+    }
+
+    /// 获取数据库连接
+    fn get_connection(&self) -> Result<Connection, Box<dyn std::error::Error>> {
+        open_connection(&self.app)
+    }
+
+    /// 获取所有回忆，那年今日在前，其余按时间倒序
+    pub async fn find_all(&self) -> Result<Vec<MemoryRecord>, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM memories mem
+             LEFT JOIN media_records c ON c.id = mem.cover_media_id
+             ORDER BY mem.kind = ?1 DESC, mem.start_date DESC",
+            MEMORY_COLUMNS
+        ))?;
+        let memories = stmt
+            .query_map(params![MemoryKind::OnThisDay.as_str()], map_memory_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(memories)
+    }
+
+    /// 获取回忆中的媒体，按拍摄时间排序
+    pub async fn find_media(
+        &self,
+        memory_id: &str,
+    ) -> Result<Vec<MediaRecord>, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM media_records
+             JOIN memory_media mm ON mm.media_id = media_records.id
+             WHERE mm.memory_id = ?1 AND media_records.trashed_at IS NULL
+             ORDER BY mm.position",
+            MEDIA_COLUMNS
+        ))?;
+        let records = stmt
+            .query_map(params![memory_id], map_media_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(records)
+    }
+
+    /// 根据当前媒体重新生成回忆，返回回忆数量
+    ///
+    /// 已有回忆按生成规则的 key 原地更新并保留 ID，不再满足条件的回忆被删除。
+    pub async fn refresh(&self) -> Result<usize, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+
+        let photos = {
+            let mut stmt = conn.prepare(
+                "SELECT id, taken_date, latitude, longitude, city, country, favorite * 10 + rating
+                 FROM media_records WHERE taken_date IS NOT NULL AND trashed_at IS NULL
+                 ORDER BY taken_date, id",
+            )?;
+            let photos = stmt
+                .query_map([], |row| {
+                    // 旧版本会把空值写成字符串 "null"，无法解析的拍摄时间不参与回忆
+                    let Ok(taken) = parse_time_column(1, row.get(1)?) else {
+                        return Ok(None);
+                    };
+                    let location = match (row.get(2)?, row.get(3)?) {
+                        (Some(latitude), Some(longitude)) => Some((latitude, longitude)),
+                        _ => None,
+                    };
+                    Ok(Some(MemoryPhoto {
+                        id: row.get(0)?,
+                        taken,
+                        local: taken.with_timezone(&Local).naive_local(),
+                        location,
+                        city: row.get(4)?,
+                        country: row.get(5)?,
+                        score: row.get(6)?,
+                    }))
+                })?
+                .filter_map(Result::transpose)
+                .collect::<Result<Vec<_>, _>>()?;
+            photos
+        };
+
+        let memories = plan_memories(&photos, Local::now().date_naive());
+
+        let tx = conn.unchecked_transaction()?;
+        let now = Utc::now().to_rfc3339();
+        let mut keys = HashSet::new();
+
+        for memory in &memories {
+            tx.execute(
+                "INSERT INTO memories
+                 (id, key, kind, title, subtitle, start_date, end_date, cover_media_id,
+                  created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?9)
+                 ON CONFLICT(key) DO UPDATE SET
+                     kind = excluded.kind, title = excluded.title, subtitle = excluded.subtitle,
+                     start_date = excluded.start_date, end_date = excluded.end_date,
+                     cover_media_id = excluded.cover_media_id, updated_at = excluded.updated_at",
+                params![
+                    Uuid::new_v4().to_string(),
+                    memory.key,
+                    memory.kind.as_str(),
+                    memory.title,
+                    memory.subtitle,
+                    memory.start.to_rfc3339(),
+                    memory.end.to_rfc3339(),
+                    memory.cover_id,
+                    now,
+                ],
+            )?;
+            let memory_id: String = tx.query_row(
+                "SELECT id FROM memories WHERE key = ?1",
+                params![memory.key],
+                |row| row.get(0),
+            )?;
+
+            tx.execute(
+                "DELETE FROM memory_media WHERE memory_id = ?1",
+                params![memory_id],
+            )?;
+            let mut insert = tx.prepare_cached(
+                "INSERT INTO memory_media (memory_id, media_id, position) VALUES (?1, ?2, ?3)",
+            )?;
+            for (position, media_id) in memory.media_ids.iter().enumerate() {
+                insert.execute(params![memory_id, media_id, position as i64])?;
+            }
+
+            keys.insert(memory.key.as_str());
+        }
+
+        // 删除不再满足生成条件的回忆
+        let existing = {
+            let mut stmt = tx.prepare("SELECT key FROM memories")?;
+            let existing = stmt
+                .query_map([], |row| row.get::<_, String>(0))?
+                .collect::<Result<Vec<_>, _>>()?;
+            existing
+        };
+        let mut removed = 0;
+        for key in existing.iter().filter(|key| !keys.contains(key.as_str())) {
+            removed += tx.execute("DELETE FROM memories WHERE key = ?1", params![key])?;
+        }
+
+        tx.commit()?;
+
+        info!(
+            "回忆已刷新，共 {} 个回忆，删除 {} 个",
+            memories.len(),
+            removed
+        );
+        Ok(memories.len())
+    }
+}
+
+fn map_memory_row(row: &rusqlite::Row) -> rusqlite::Result<MemoryRecord> {
+    let kind: String = row.get(1)?;
+
+    Ok(MemoryRecord {
+        id: parse_uuid_column(0, row.get(0)?)?,
+        kind: MemoryKind::parse(&kind).ok_or_else(|| {
+            rusqlite::Error::InvalidColumnType(
+                1,
+                format!("Invalid memory kind: {}", kind),
+                rusqlite::types::Type::Text,
+            )
+        })?,
+        title: row.get(2)?,
+        subtitle: row.get(3)?,
+        start_date: parse_time_column(4, row.get(4)?)?,
+        end_date: parse_time_column(5, row.get(5)?)?,
+        cover_media_id: row
+            .get::<_, Option<String>>(6)?
+            .map(|id| parse_uuid_column(6, id))
+            .transpose()?,
+        cover_thumbnail_path: row.get(7)?,
+        media_count: row.get::<_, i64>(8)? as u64,
+        created_at: parse_time_column(9, row.get(9)?)?,
+        updated_at: parse_time_column(10, row.get(10)?)?,
+    })
+}
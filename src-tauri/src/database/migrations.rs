@@ -1 +1,131 @@
 // 数据库迁移相关代码
+use chrono::Utc;
+use rusqlite::{params, Connection, Transaction, TransactionBehavior};
+use uuid::Uuid;
+
+use crate::database::location_repository::update_places;
+use crate::database::tag_repository::{ensure_tag_path, normalize_tag_path};
+
+/// 单个迁移步骤：SQL 脚本或需要在 Rust 中完成的数据迁移
+enum Migration {
+    Sql(&'static str),
+    Rust(fn(&Connection) -> rusqlite::Result<()>),
+}
+
+/// 按顺序排列的迁移步骤，第 N 个步骤执行完成后 `user_version` 记为 N
+const MIGRATIONS: &[Migration] = &[
+    Migration::Sql(include_str!("migrations/001_create_media_records.sql")),
+    Migration::Sql(include_str!("migrations/002_add_motion_components.sql")),
+    Migration::Sql(include_str!("migrations/003_create_tags.sql")),
+    Migration::Rust(migrate_legacy_tags),
+    Migration::Sql(include_str!("migrations/004_create_albums.sql")),
+    Migration::Rust(migrate_legacy_album_ids),
+    Migration::Sql(include_str!("migrations/005_add_capture_metadata.sql")),
+    Migration::Sql(include_str!("migrations/006_create_smart_albums.sql")),
+    Migration::Sql(include_str!("migrations/007_add_media_marks.sql")),
+    Migration::Sql(include_str!("migrations/008_create_media_search.sql")),
+    Migration::Sql(include_str!("migrations/009_create_saved_searches.sql")),
+    Migration::Sql(include_str!("migrations/010_create_faces.sql")),
+    Migration::Sql(include_str!("migrations/011_create_people.sql")),
+    Migration::Sql(include_str!("migrations/012_add_media_places.sql")),
+    Migration::Rust(geocode_existing_media),
+    Migration::Sql(include_str!("migrations/013_create_media_locations.sql")),
+    Migration::Sql(include_str!("migrations/014_create_memories.sql")),
+    Migration::Sql(include_str!(
+        "migrations/015_create_library_stats_cache.sql"
+    )),
+    Migration::Sql(include_str!("migrations/016_add_media_trash.sql")),
+    Migration::Sql(include_str!("migrations/017_create_journal.sql")),
+    Migration::Sql(include_str!("migrations/018_add_media_file_identity.sql")),
+    Migration::Sql(include_str!("migrations/019_add_media_source_path.sql")),
+    Migration::Sql(include_str!("migrations/020_create_export_presets.sql")),
+    Migration::Sql(include_str!("migrations/021_normalize_null_taken_date.sql")),
+    Migration::Sql(include_str!("migrations/022_add_media_location_ids.sql")),
+];
+
+/// 全部迁移执行完成后的 `user_version`
+pub fn latest_version() -> i64 {
+    MIGRATIONS.len() as i64
+}
+
+/// 执行所有尚未应用的迁移步骤
+///
+/// 每个步骤在 IMMEDIATE 事务中重新读取 `user_version`，多个连接同时打开数据库时
+/// 只有一个连接执行同一步骤，其他连接等待写锁后看到已更新的版本并跳过。
+pub fn run_migrations(conn: &Connection) -> rusqlite::Result<()> {
+    loop {
+        let tx = Transaction::new_unchecked(conn, TransactionBehavior::Immediate)?;
+        let applied: i64 = tx.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        let Some(migration) = MIGRATIONS.get(applied.max(0) as usize) else {
+            return Ok(());
+        };
+
+        match migration {
+            Migration::Sql(sql) => tx.execute_batch(sql)?,
+            Migration::Rust(migrate) => migrate(&tx)?,
+        }
+        tx.pragma_update(None, "user_version", applied.max(0) + 1)?;
+        tx.commit()?;
+    }
+}
+
+/// 将 media_records.tags 中的 JSON 标签数组迁移到 tags / media_tags 表
+fn migrate_legacy_tags(conn: &Connection) -> rusqlite::Result<()> {
+    let legacy: Vec<(String, String)> = conn
+        .prepare("SELECT id, tags FROM media_records WHERE tags IS NOT NULL")?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<_, _>>()?;
+
+    let now = Utc::now().to_rfc3339();
+    for (media_id, tags_json) in legacy {
+        // 无法解析的旧数据直接丢弃
+        let tags = serde_json::from_str::<Vec<String>>(&tags_json).unwrap_or_default();
+        for path in tags.iter().filter_map(|t| normalize_tag_path(t)) {
+            let tag = ensure_tag_path(conn, &path)?;
+            conn.execute(
+                "INSERT OR IGNORE INTO media_tags (media_id, tag_id, created_at) VALUES (?1, ?2, ?3)",
+                params![media_id, tag.id.to_string(), now],
+            )?;
+        }
+    }
+
+    conn.execute("UPDATE media_records SET tags = NULL", [])?;
+    Ok(())
+}
+
+/// 将 media_records.album_ids 中的 JSON 相册 ID 迁移到 album_media 表
+///
+/// 旧版本没有相册表，遗留的 ID 会生成占位相册，避免丢失分组信息。
+fn migrate_legacy_album_ids(conn: &Connection) -> rusqlite::Result<()> {
+    let legacy: Vec<(String, String)> = conn
+        .prepare("SELECT id, album_ids FROM media_records WHERE album_ids IS NOT NULL")?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<_, _>>()?;
+
+    let now = Utc::now().to_rfc3339();
+    for (media_id, album_ids_json) in legacy {
+        let album_ids = serde_json::from_str::<Vec<Uuid>>(&album_ids_json).unwrap_or_default();
+        for album_id in album_ids.iter().map(|id| id.to_string()) {
+            conn.execute(
+                "INSERT OR IGNORE INTO albums (id, name, created_at, updated_at)
+                 VALUES (?1, '未命名相册', ?2, ?2)",
+                params![album_id, now],
+            )?;
+            conn.execute(
+                "INSERT OR IGNORE INTO album_media (album_id, media_id, position, added_at)
+                 SELECT ?1, ?2, COALESCE(MAX(position) + 1, 0), ?3
+                 FROM album_media WHERE album_id = ?1",
+                params![album_id, media_id, now],
+            )?;
+        }
+    }
+
+    conn.execute("UPDATE media_records SET album_ids = NULL", [])?;
+    Ok(())
+}
+
+/// 为已有的带 GPS 坐标的媒体填充地名
+fn geocode_existing_media(conn: &Connection) -> rusqlite::Result<()> {
+    update_places(conn)?;
+    Ok(())
+}
@@ -0,0 +1,6 @@
+-- 实况照片 / 动态照片的视频部分
+-- motion_path: 配对的视频文件路径（嵌入式动态照片指向自身）
+-- motion_offset / motion_length: 嵌入式视频在文件中的字节范围
+ALTER TABLE media_records ADD COLUMN motion_path TEXT;
+ALTER TABLE media_records ADD COLUMN motion_offset INTEGER;
+ALTER TABLE media_records ADD COLUMN motion_length INTEGER;
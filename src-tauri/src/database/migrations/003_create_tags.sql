@@ -0,0 +1,20 @@
+-- 创建标签表，path 保存完整层级路径，如 Travel/Japan/Kyoto
+CREATE TABLE IF NOT EXISTS tags (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    parent_id TEXT REFERENCES tags(id) ON DELETE CASCADE,
+    path TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+-- 创建媒体与标签的关联表
+CREATE TABLE IF NOT EXISTS media_tags (
+    media_id TEXT NOT NULL REFERENCES media_records(id) ON DELETE CASCADE,
+    tag_id TEXT NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+    created_at TEXT NOT NULL,
+    PRIMARY KEY (media_id, tag_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_tags_parent_id ON tags(parent_id);
+CREATE INDEX IF NOT EXISTS idx_media_tags_tag_id ON media_tags(tag_id);
@@ -0,0 +1,25 @@
+-- 创建相册表，is_folder 为 1 时表示只包含子相册的文件夹
+CREATE TABLE IF NOT EXISTS albums (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    description TEXT,
+    parent_id TEXT REFERENCES albums(id) ON DELETE CASCADE,
+    is_folder INTEGER NOT NULL DEFAULT 0,
+    cover_media_id TEXT REFERENCES media_records(id) ON DELETE SET NULL,
+    position INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+-- 创建相册与媒体的关联表，position 为相册内的手动排序
+CREATE TABLE IF NOT EXISTS album_media (
+    album_id TEXT NOT NULL REFERENCES albums(id) ON DELETE CASCADE,
+    media_id TEXT NOT NULL REFERENCES media_records(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    added_at TEXT NOT NULL,
+    PRIMARY KEY (album_id, media_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_albums_parent_id ON albums(parent_id);
+CREATE INDEX IF NOT EXISTS idx_album_media_media_id ON album_media(media_id);
+CREATE INDEX IF NOT EXISTS idx_album_media_position ON album_media(album_id, position);
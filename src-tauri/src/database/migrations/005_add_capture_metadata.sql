@@ -0,0 +1,15 @@
+-- 从 EXIF 中提取的拍摄信息
+ALTER TABLE media_records ADD COLUMN camera_make TEXT;
+ALTER TABLE media_records ADD COLUMN camera_model TEXT;
+ALTER TABLE media_records ADD COLUMN lens_model TEXT;
+ALTER TABLE media_records ADD COLUMN iso INTEGER;
+ALTER TABLE media_records ADD COLUMN aperture REAL;
+ALTER TABLE media_records ADD COLUMN focal_length REAL;
+ALTER TABLE media_records ADD COLUMN latitude REAL;
+ALTER TABLE media_records ADD COLUMN longitude REAL;
+
+-- 星级评分 0-5，0 表示未评分
+ALTER TABLE media_records ADD COLUMN rating INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_media_records_camera_model ON media_records(camera_model);
+CREATE INDEX IF NOT EXISTS idx_media_records_rating ON media_records(rating);
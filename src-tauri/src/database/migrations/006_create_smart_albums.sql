@@ -0,0 +1,9 @@
+-- 保存智能相册的规则树（JSON）
+CREATE TABLE IF NOT EXISTS smart_albums (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    rule TEXT NOT NULL,
+    position INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
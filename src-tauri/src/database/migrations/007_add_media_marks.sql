@@ -0,0 +1,9 @@
+-- 收藏、颜色标签和挑选/排除标记
+ALTER TABLE media_records ADD COLUMN favorite INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE media_records ADD COLUMN color_label TEXT;
+-- 1 为挑选，-1 为排除，0 为未标记
+ALTER TABLE media_records ADD COLUMN flag INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_media_records_favorite ON media_records(favorite);
+CREATE INDEX IF NOT EXISTS idx_media_records_color_label ON media_records(color_label);
+CREATE INDEX IF NOT EXISTS idx_media_records_flag ON media_records(flag);
@@ -0,0 +1,83 @@
+-- 图片说明（EXIF ImageDescription）
+ALTER TABLE media_records ADD COLUMN caption TEXT;
+
+-- 全文搜索索引，内容由 MediaRepository 根据 media_search_dirty 增量刷新
+CREATE VIRTUAL TABLE IF NOT EXISTS media_search USING fts5(
+    media_id UNINDEXED,
+    name,
+    folder,
+    tags,
+    albums,
+    caption,
+    camera,
+    places,
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+-- 需要重建索引的媒体
+-- 触发器中使用 ON CONFLICT DO NOTHING 而不是 INSERT OR IGNORE，
+-- 否则外层 upsert 的冲突处理会覆盖触发器中的 OR IGNORE
+CREATE TABLE IF NOT EXISTS media_search_dirty (
+    media_id TEXT PRIMARY KEY
+);
+
+CREATE TRIGGER IF NOT EXISTS media_search_media_insert AFTER INSERT ON media_records
+BEGIN
+    INSERT INTO media_search_dirty (media_id) VALUES (new.id)
+    ON CONFLICT (media_id) DO NOTHING;
+END;
+
+CREATE TRIGGER IF NOT EXISTS media_search_media_update
+AFTER UPDATE OF path, name, caption, camera_make, camera_model, lens_model ON media_records
+BEGIN
+    INSERT INTO media_search_dirty (media_id) VALUES (new.id)
+    ON CONFLICT (media_id) DO NOTHING;
+END;
+
+CREATE TRIGGER IF NOT EXISTS media_search_media_delete AFTER DELETE ON media_records
+BEGIN
+    INSERT INTO media_search_dirty (media_id) VALUES (old.id)
+    ON CONFLICT (media_id) DO NOTHING;
+END;
+
+CREATE TRIGGER IF NOT EXISTS media_search_tag_insert AFTER INSERT ON media_tags
+BEGIN
+    INSERT INTO media_search_dirty (media_id) VALUES (new.media_id)
+    ON CONFLICT (media_id) DO NOTHING;
+END;
+
+CREATE TRIGGER IF NOT EXISTS media_search_tag_delete AFTER DELETE ON media_tags
+BEGIN
+    INSERT INTO media_search_dirty (media_id) VALUES (old.media_id)
+    ON CONFLICT (media_id) DO NOTHING;
+END;
+
+CREATE TRIGGER IF NOT EXISTS media_search_tag_rename AFTER UPDATE OF path ON tags
+BEGIN
+    INSERT INTO media_search_dirty (media_id)
+    SELECT media_id FROM media_tags WHERE tag_id = new.id
+    ON CONFLICT (media_id) DO NOTHING;
+END;
+
+CREATE TRIGGER IF NOT EXISTS media_search_album_insert AFTER INSERT ON album_media
+BEGIN
+    INSERT INTO media_search_dirty (media_id) VALUES (new.media_id)
+    ON CONFLICT (media_id) DO NOTHING;
+END;
+
+CREATE TRIGGER IF NOT EXISTS media_search_album_delete AFTER DELETE ON album_media
+BEGIN
+    INSERT INTO media_search_dirty (media_id) VALUES (old.media_id)
+    ON CONFLICT (media_id) DO NOTHING;
+END;
+
+CREATE TRIGGER IF NOT EXISTS media_search_album_rename AFTER UPDATE OF name ON albums
+BEGIN
+    INSERT INTO media_search_dirty (media_id)
+    SELECT media_id FROM album_media WHERE album_id = new.id
+    ON CONFLICT (media_id) DO NOTHING;
+END;
+
+-- 已有媒体全部加入待索引队列
+INSERT INTO media_search_dirty (media_id) SELECT id FROM media_records WHERE true
+ON CONFLICT (media_id) DO NOTHING;
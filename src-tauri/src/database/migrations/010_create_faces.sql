@@ -0,0 +1,16 @@
+-- 检测到的人脸，坐标为相对图片宽高的比例（0-1）
+CREATE TABLE IF NOT EXISTS faces (
+    id TEXT PRIMARY KEY,
+    media_id TEXT NOT NULL REFERENCES media_records(id) ON DELETE CASCADE,
+    x REAL NOT NULL,
+    y REAL NOT NULL,
+    width REAL NOT NULL,
+    height REAL NOT NULL,
+    confidence REAL NOT NULL,
+    -- 人脸特征向量，f32 小端序
+    embedding BLOB NOT NULL,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_faces_media_id ON faces(media_id);
@@ -0,0 +1,17 @@
+-- 人物，由人脸聚类生成，名称由用户指定
+CREATE TABLE IF NOT EXISTS people (
+    id TEXT PRIMARY KEY,
+    name TEXT,
+    -- 用户指定的封面人脸，为空时使用置信度最高的人脸
+    cover_face_id TEXT REFERENCES faces(id) ON DELETE SET NULL,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+-- confirmed 表示人脸归属由用户确认，重新聚类时保留并作为该人物的参考样本；
+-- rejected 表示误检，不参与聚类和计数
+ALTER TABLE faces ADD COLUMN person_id TEXT REFERENCES people(id) ON DELETE SET NULL;
+ALTER TABLE faces ADD COLUMN confirmed INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE faces ADD COLUMN rejected INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_faces_person_id ON faces(person_id);
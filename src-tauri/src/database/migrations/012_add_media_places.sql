@@ -0,0 +1,14 @@
+-- 由 GPS 坐标离线反向地理编码得到的地名
+ALTER TABLE media_records ADD COLUMN country TEXT;
+ALTER TABLE media_records ADD COLUMN region TEXT;
+ALTER TABLE media_records ADD COLUMN city TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_media_records_country ON media_records(country, region, city);
+
+-- 地名变化时重建全文索引
+CREATE TRIGGER IF NOT EXISTS media_search_places_update
+AFTER UPDATE OF country, region, city ON media_records
+BEGIN
+    INSERT INTO media_search_dirty (media_id) VALUES (new.id)
+    ON CONFLICT (media_id) DO NOTHING;
+END;
@@ -0,0 +1,31 @@
+-- 拍摄位置的 R*Tree 空间索引，id 为 media_records 的 rowid
+CREATE VIRTUAL TABLE IF NOT EXISTS media_locations USING rtree(
+    id,
+    min_lat, max_lat,
+    min_lon, max_lon
+);
+
+CREATE TRIGGER IF NOT EXISTS media_locations_insert AFTER INSERT ON media_records
+WHEN new.latitude IS NOT NULL AND new.longitude IS NOT NULL
+BEGIN
+    INSERT INTO media_locations (id, min_lat, max_lat, min_lon, max_lon)
+    VALUES (new.rowid, new.latitude, new.latitude, new.longitude, new.longitude);
+END;
+
+CREATE TRIGGER IF NOT EXISTS media_locations_update
+AFTER UPDATE OF latitude, longitude ON media_records
+BEGIN
+    DELETE FROM media_locations WHERE id = old.rowid;
+    INSERT INTO media_locations (id, min_lat, max_lat, min_lon, max_lon)
+    SELECT new.rowid, new.latitude, new.latitude, new.longitude, new.longitude
+    WHERE new.latitude IS NOT NULL AND new.longitude IS NOT NULL;
+END;
+
+CREATE TRIGGER IF NOT EXISTS media_locations_delete AFTER DELETE ON media_records
+BEGIN
+    DELETE FROM media_locations WHERE id = old.rowid;
+END;
+
+INSERT INTO media_locations (id, min_lat, max_lat, min_lon, max_lon)
+SELECT rowid, latitude, latitude, longitude, longitude FROM media_records
+WHERE latitude IS NOT NULL AND longitude IS NOT NULL;
@@ -0,0 +1,25 @@
+-- 自动生成的回忆（那年今日、旅行、活动），刷新时按 key 更新，保留 id
+CREATE TABLE IF NOT EXISTS memories (
+    id TEXT PRIMARY KEY,
+    -- 生成规则内的稳定标识，例如 trip:2024-05-01
+    key TEXT NOT NULL UNIQUE,
+    kind TEXT NOT NULL,
+    title TEXT NOT NULL,
+    subtitle TEXT,
+    start_date TEXT NOT NULL,
+    end_date TEXT NOT NULL,
+    cover_media_id TEXT REFERENCES media_records(id) ON DELETE SET NULL,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS memory_media (
+    memory_id TEXT NOT NULL REFERENCES memories(id) ON DELETE CASCADE,
+    media_id TEXT NOT NULL REFERENCES media_records(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    PRIMARY KEY (memory_id, media_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_memories_start_date ON memories(start_date);
+CREATE INDEX IF NOT EXISTS idx_memory_media_media_id ON memory_media(media_id);
+CREATE INDEX IF NOT EXISTS idx_memory_media_position ON memory_media(memory_id, position);
@@ -0,0 +1,24 @@
+-- 媒体库统计缓存，只有一行；媒体目录变化时由触发器清除，下次查询时重新统计
+CREATE TABLE IF NOT EXISTS library_stats_cache (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    -- 统计结果（JSON），统计进行中为 NULL
+    stats TEXT,
+    created_at TEXT NOT NULL
+);
+
+CREATE TRIGGER IF NOT EXISTS library_stats_cache_insert AFTER INSERT ON media_records
+BEGIN
+    DELETE FROM library_stats_cache;
+END;
+
+CREATE TRIGGER IF NOT EXISTS library_stats_cache_update
+AFTER UPDATE OF path, kind, mime_type, size, thumbnail_path, taken_date, created_at,
+    camera_make, camera_model ON media_records
+BEGIN
+    DELETE FROM library_stats_cache;
+END;
+
+CREATE TRIGGER IF NOT EXISTS library_stats_cache_delete AFTER DELETE ON media_records
+BEGIN
+    DELETE FROM library_stats_cache;
+END;
@@ -0,0 +1,23 @@
+-- 回收站：删除的媒体标记 trashed_at，保留标签、相册等关联数据以便恢复，保留期过后彻底删除
+ALTER TABLE media_records ADD COLUMN trashed_at TEXT;
+-- 原文件的处理方式：Keep 不处理，System 移到系统回收站，Managed 移到应用回收站目录
+ALTER TABLE media_records ADD COLUMN trash_action TEXT;
+-- 移到应用回收站目录后的文件路径
+ALTER TABLE media_records ADD COLUMN trash_path TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_media_records_trashed_at ON media_records(trashed_at);
+
+-- 应用设置
+CREATE TABLE IF NOT EXISTS settings (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+
+-- 移入或移出回收站同样使统计缓存失效
+DROP TRIGGER IF EXISTS library_stats_cache_update;
+CREATE TRIGGER IF NOT EXISTS library_stats_cache_update
+AFTER UPDATE OF path, kind, mime_type, size, thumbnail_path, taken_date, created_at,
+    camera_make, camera_model, trashed_at ON media_records
+BEGIN
+    DELETE FROM library_stats_cache;
+END;
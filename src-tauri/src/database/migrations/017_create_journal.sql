@@ -0,0 +1,19 @@
+-- 操作日志：每条记录是一次可撤销的目录操作，changes 中保存变更前后的值
+CREATE TABLE IF NOT EXISTS journal_entries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    label TEXT NOT NULL,
+    change_count INTEGER NOT NULL,
+    -- 已撤销的记录可以重做，新的操作会清除所有已撤销的记录
+    undone INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS journal_changes (
+    entry_id INTEGER NOT NULL REFERENCES journal_entries(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    -- JSON 格式的单项变更
+    change TEXT NOT NULL,
+    PRIMARY KEY (entry_id, position)
+);
+
+CREATE INDEX IF NOT EXISTS idx_journal_entries_undone ON journal_entries(undone, id);
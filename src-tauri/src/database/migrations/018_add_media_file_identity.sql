@@ -0,0 +1,5 @@
+-- 原文件的内容哈希（SHA-256）和修改时间，文件在 Lumen 之外被移动或重命名后用于重新关联
+ALTER TABLE media_records ADD COLUMN content_hash TEXT;
+ALTER TABLE media_records ADD COLUMN file_modified_at TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_media_records_content_hash ON media_records(content_hash);
@@ -0,0 +1,2 @@
+-- 复制或移动导入到媒体库文件夹时原文件所在的路径，如存储卡上的位置
+ALTER TABLE media_records ADD COLUMN source_path TEXT;
@@ -0,0 +1,8 @@
+-- 命名的导出预设，settings 为导出设置（JSON），包括格式、尺寸、元数据、水印和边框
+CREATE TABLE IF NOT EXISTS export_presets (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    settings TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
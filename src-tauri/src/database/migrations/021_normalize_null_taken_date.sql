@@ -0,0 +1,3 @@
+-- 旧版本会把空的拍摄时间写成字符串 'null'，统一改为 NULL，
+-- 时间线分组和按拍摄时间排序才会把这些媒体当作没有拍摄时间
+UPDATE media_records SET taken_date = NULL WHERE taken_date = 'null';
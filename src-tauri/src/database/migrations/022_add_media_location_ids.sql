@@ -0,0 +1,51 @@
+-- media_records 的主键为 TEXT，rowid 在 VACUUM 或重建表后可能改变，
+-- 空间索引改为通过显式的整数 ID 映射到媒体，INTEGER PRIMARY KEY 不会被重新分配
+CREATE TABLE IF NOT EXISTS media_location_ids (
+    id INTEGER PRIMARY KEY,
+    media_id TEXT NOT NULL UNIQUE
+);
+
+DROP TRIGGER IF EXISTS media_locations_insert;
+DROP TRIGGER IF EXISTS media_locations_update;
+DROP TRIGGER IF EXISTS media_locations_delete;
+
+-- 触发器中不使用 INSERT OR IGNORE：由 UPSERT 触发时，外层语句的冲突处理会覆盖它
+CREATE TRIGGER IF NOT EXISTS media_locations_insert AFTER INSERT ON media_records
+WHEN new.latitude IS NOT NULL AND new.longitude IS NOT NULL
+BEGIN
+    INSERT INTO media_location_ids (media_id)
+    SELECT new.id WHERE NOT EXISTS (SELECT 1 FROM media_location_ids WHERE media_id = new.id);
+    INSERT INTO media_locations (id, min_lat, max_lat, min_lon, max_lon)
+    SELECT l.id, new.latitude, new.latitude, new.longitude, new.longitude
+    FROM media_location_ids l WHERE l.media_id = new.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS media_locations_update
+AFTER UPDATE OF latitude, longitude ON media_records
+BEGIN
+    DELETE FROM media_locations
+    WHERE id = (SELECT id FROM media_location_ids WHERE media_id = old.id);
+    INSERT INTO media_location_ids (media_id)
+    SELECT new.id WHERE new.latitude IS NOT NULL AND new.longitude IS NOT NULL
+        AND NOT EXISTS (SELECT 1 FROM media_location_ids WHERE media_id = new.id);
+    INSERT INTO media_locations (id, min_lat, max_lat, min_lon, max_lon)
+    SELECT l.id, new.latitude, new.latitude, new.longitude, new.longitude
+    FROM media_location_ids l
+    WHERE l.media_id = new.id AND new.latitude IS NOT NULL AND new.longitude IS NOT NULL;
+END;
+
+CREATE TRIGGER IF NOT EXISTS media_locations_delete AFTER DELETE ON media_records
+BEGIN
+    DELETE FROM media_locations
+    WHERE id = (SELECT id FROM media_location_ids WHERE media_id = old.id);
+    DELETE FROM media_location_ids WHERE media_id = old.id;
+END;
+
+-- 按新的 ID 重建空间索引
+DELETE FROM media_locations;
+DELETE FROM media_location_ids;
+INSERT INTO media_location_ids (media_id)
+SELECT id FROM media_records WHERE latitude IS NOT NULL AND longitude IS NOT NULL;
+INSERT INTO media_locations (id, min_lat, max_lat, min_lon, max_lon)
+SELECT l.id, m.latitude, m.latitude, m.longitude, m.longitude
+FROM media_location_ids l JOIN media_records m ON m.id = l.media_id;
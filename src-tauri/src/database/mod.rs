@@ -1,9 +1,32 @@
+pub mod album_repository;
+pub mod backup_repository;
+pub mod contact_sheet_repository;
+pub mod export_preset_repository;
+pub mod export_repository;
+pub mod face_repository;
+pub mod gallery_repository;
+pub mod journal_repository;
+pub mod library_import_repository;
+pub mod location_repository;
 pub mod media_repository;
+pub mod memory_repository;
 pub mod migrations;
+pub mod organize_repository;
+pub mod person_repository;
+pub mod relink_repository;
+pub mod rename_repository;
+pub mod saved_search_repository;
+pub mod smart_album_repository;
+pub mod stats_repository;
+pub mod tag_repository;
+pub mod trash_repository;
 
+use chrono::{DateTime, Utc};
 use log::info;
+use rusqlite::{Connection, OptionalExtension};
 use std::path::PathBuf;
 use tauri::{path::BaseDirectory, AppHandle, Manager};
+use uuid::Uuid;
 
 /// 初始化数据库
 pub async fn _init_database(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
@@ -23,3 +46,57 @@ pub fn get_database_path(app: &AppHandle) -> Result<PathBuf, Box<dyn std::error:
 
     Ok(app_dir.join("lumen.db"))
 }
+
+/// 打开数据库连接并确保迁移已全部执行
+pub fn open_connection(app: &AppHandle) -> Result<Connection, Box<dyn std::error::Error>> {
+    let conn = Connection::open(get_database_path(app)?)?;
+    conn.pragma_update(None, "foreign_keys", true)?;
+
+    migrations::run_migrations(&conn)?;
+
+    Ok(conn)
+}
+
+/// 解析 TEXT 列中保存的 UUID
+pub(crate) fn parse_uuid_column(index: usize, value: String) -> rusqlite::Result<Uuid> {
+    Uuid::parse_str(&value).map_err(|e| {
+        rusqlite::Error::InvalidColumnType(
+            index,
+            format!("Invalid UUID: {}", e),
+            rusqlite::types::Type::Text,
+        )
+    })
+}
+
+/// 解析 TEXT 列中保存的 RFC3339 时间
+pub(crate) fn parse_time_column(index: usize, value: String) -> rusqlite::Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(&value)
+        .map(|dt| dt.with_timezone(&Utc))
+        .map_err(|e| {
+            rusqlite::Error::InvalidColumnType(
+                index,
+                format!("Invalid timestamp: {}", e),
+                rusqlite::types::Type::Text,
+            )
+        })
+}
+
+/// 读取应用设置，未设置时返回 None
+pub(crate) fn read_setting(conn: &Connection, key: &str) -> rusqlite::Result<Option<String>> {
+    conn.query_row(
+        "SELECT value FROM settings WHERE key = ?1",
+        rusqlite::params![key],
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+/// 写入应用设置
+pub(crate) fn write_setting(conn: &Connection, key: &str, value: &str) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO settings (key, value) VALUES (?1, ?2)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        rusqlite::params![key, value],
+    )?;
+    Ok(())
+}
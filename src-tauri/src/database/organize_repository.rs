@@ -0,0 +1,196 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use log::{error, info};
+use rusqlite::{params, Connection, OptionalExtension};
+use tauri::AppHandle;
+
+use crate::database::open_connection;
+use crate::database::rename_repository::{
+    apply_media_moves, load_media_files, plan_media_move, MediaMove,
+};
+use crate::models::organize::{CollisionPolicy, OrganizeOptions, OrganizeResult};
+use crate::models::rename::{RenameConflict, RenamePreview};
+use crate::utils::file_ops::numbered_path;
+use crate::utils::image_processor::get_thumbnail_dir;
+use crate::utils::path_template::{assign_sequences, render_path_template};
+
+/// 每次移动并更新数据库的媒体数量，失败时只回滚当前这一批
+const ORGANIZE_BATCH_SIZE: usize = 50;
+
+/// SQLite 文件夹整理仓库实现
+pub struct OrganizeRepository {
+    app: AppHandle,
+}
+
+impl OrganizeRepository {
+    pub fn new(app: AppHandle) -> Self {
+        Self { app }
+    }
+
+    /// 获取数据库连接
+    fn get_connection(&self) -> Result<Connection, Box<dyn std::error::Error>> {
+        open_connection(&self.app)
+    }
+
+    /// 按文件夹模板将媒体文件移到目标文件夹中，文件名保持不变
+    ///
+    /// 新位置已有文件（包括同一批中尚未移走的文件）时按冲突策略跳过或加编号。
+    /// 每 `ORGANIZE_BATCH_SIZE` 个媒体作为一批移动并更新路径，某一批失败时移回该批文件后继续。
+    /// 每完成一批调用一次 `on_progress`，参数为已处理的数量和总数。
+    pub async fn organize(
+        &self,
+        options: &OrganizeOptions,
+        on_progress: impl Fn(usize, usize),
+    ) -> Result<OrganizeResult, Box<dyn std::error::Error>> {
+        let target_root = Path::new(&options.target_root);
+        if !target_root.is_absolute() {
+            return Err(format!("目标文件夹必须是绝对路径: {}", options.target_root).into());
+        }
+
+        let conn = self.get_connection()?;
+        let thumbnail_dir = get_thumbnail_dir(&self.app);
+        let media_ids = match &options.media_ids {
+            Some(media_ids) => media_ids.clone(),
+            None => {
+                let mut stmt =
+                    conn.prepare("SELECT id FROM media_records WHERE trashed_at IS NULL")?;
+                let media_ids = stmt
+                    .query_map([], |row| row.get(0))?
+                    .collect::<Result<Vec<String>, _>>()?;
+                media_ids
+            }
+        };
+
+        let mut files = load_media_files(&conn, &media_ids)?;
+        let uses_album = options.folder_template.contains("{album}");
+        let uses_person = options.folder_template.contains("{person}");
+        for file in &mut files {
+            if uses_album {
+                file.values.album = first_album_name(&conn, &file.media_id)?;
+            }
+            if uses_person {
+                file.values.person = first_person_name(&conn, &file.media_id)?;
+            }
+        }
+        files.sort_by(|a, b| {
+            a.values
+                .taken_date
+                .cmp(&b.values.taken_date)
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        assign_sequences(files.iter_mut().map(|file| &mut file.values));
+
+        let mut result = OrganizeResult {
+            dry_run: options.dry_run,
+            ..Default::default()
+        };
+        let mut claimed: HashSet<PathBuf> = HashSet::new();
+        let mut planned: Vec<MediaMove> = Vec::new();
+        for file in files {
+            let folder = render_path_template(&options.folder_template, &file.values)?;
+            let Some(file_name) = file.path.file_name() else {
+                continue;
+            };
+            let target = target_root.join(folder).join(file_name);
+            if target == file.path {
+                result.unchanged += 1;
+                continue;
+            }
+
+            let mut preview = RenamePreview {
+                media_id: file.media_id.clone(),
+                old_path: file.path.to_string_lossy().into_owned(),
+                new_path: target.to_string_lossy().into_owned(),
+                conflict: None,
+            };
+            if !file.path.is_file() {
+                preview.conflict = Some(RenameConflict::SourceMissing);
+                result.skipped += 1;
+                result.moves.push(preview);
+                continue;
+            }
+
+            let mut number = 0;
+            loop {
+                let candidate = numbered_path(&target, number);
+                let media_move =
+                    plan_media_move(&conn, &thumbnail_dir, file.clone(), candidate, &claimed)?;
+                let conflict = media_move.file_moves.iter().find_map(|(_, to)| {
+                    if claimed.contains(to) {
+                        Some(RenameConflict::DuplicateTarget)
+                    } else if to.exists() {
+                        Some(RenameConflict::TargetExists)
+                    } else {
+                        None
+                    }
+                });
+
+                match (conflict, options.collision_policy) {
+                    (None, _) => {
+                        claimed.extend(media_move.file_moves.iter().map(|(_, to)| to.clone()));
+                        preview.new_path = media_move.new_path.to_string_lossy().into_owned();
+                        planned.push(media_move);
+                        break;
+                    }
+                    (Some(conflict), CollisionPolicy::Skip) => {
+                        preview.conflict = Some(conflict);
+                        result.skipped += 1;
+                        break;
+                    }
+                    (Some(_), CollisionPolicy::Rename) => number += 1,
+                }
+            }
+            result.moves.push(preview);
+        }
+
+        if options.dry_run {
+            return Ok(result);
+        }
+
+        let total = planned.len();
+        let mut processed = 0;
+        for batch in planned.chunks(ORGANIZE_BATCH_SIZE) {
+            match apply_media_moves(&conn, batch) {
+                Ok(moved) => result.moved += moved,
+                Err(e) => {
+                    error!("整理文件失败，已移回本批 {} 个文件: {}", batch.len(), e);
+                    result.failed += batch.len();
+                }
+            }
+            processed += batch.len();
+            on_progress(processed, total);
+        }
+
+        info!(
+            "已整理 {} 个文件到 {}，跳过 {} 个，{} 个失败",
+            result.moved, options.target_root, result.skipped, result.failed
+        );
+        Ok(result)
+    }
+}
+
+/// 媒体所在的相册中按名称排序的第一个
+fn first_album_name(conn: &Connection, media_id: &str) -> rusqlite::Result<Option<String>> {
+    conn.query_row(
+        "SELECT a.name FROM album_media am JOIN albums a ON a.id = am.album_id
+         WHERE am.media_id = ?1 ORDER BY a.name LIMIT 1",
+        params![media_id],
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+/// 照片中已命名的人物中按名称排序的第一个
+fn first_person_name(conn: &Connection, media_id: &str) -> rusqlite::Result<Option<String>> {
+    conn.query_row(
+        "SELECT p.name FROM faces f JOIN people p ON p.id = f.person_id
+         WHERE f.media_id = ?1 AND f.rejected = 0 AND p.name IS NOT NULL AND p.name != ''
+         ORDER BY p.name LIMIT 1",
+        params![media_id],
+        |row| row.get(0),
+    )
+    .optional()
+}
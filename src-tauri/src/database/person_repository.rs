@@ -0,0 +1,476 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+use log::info;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use tauri::AppHandle;
+use uuid::Uuid;
+
+use crate::database::face_repository::{blob_to_embedding, map_face_row, FACE_COLUMNS};
+use crate::database::media_repository::{map_media_row, MEDIA_COLUMNS};
+use crate::database::{open_connection, parse_time_column, parse_uuid_column};
+use crate::models::face::{ClusterSummary, FaceRecord, PersonRecord};
+use crate::models::image::MediaRecord;
+use crate::utils::face_clustering::{centroid, plan_clusters};
+
+/// 查询人物时使用的列，顺序与 `map_person_row` 保持一致
+const PERSON_COLUMNS: &str = "p.id, p.name, c.id, c.media_id, m.thumbnail_path,
+     c.x, c.y, c.width, c.height,
+     (SELECT COUNT(*) FROM faces f
+      JOIN media_records fm ON fm.id = f.media_id
+      WHERE f.person_id = p.id AND f.rejected = 0 AND fm.trashed_at IS NULL),
+     (SELECT COUNT(DISTINCT f.media_id) FROM faces f
+      JOIN media_records fm ON fm.id = f.media_id
+      WHERE f.person_id = p.id AND f.rejected = 0 AND fm.trashed_at IS NULL)
+         AS photo_count,
+     p.created_at, p.updated_at";
+
+/// 人物查询的 FROM 子句，未指定封面时优先使用已确认、置信度最高的人脸
+const PERSON_FROM: &str = "people p
+     LEFT JOIN faces c ON c.id = COALESCE(
+         p.cover_face_id,
+         (SELECT f.id FROM faces f WHERE f.person_id = p.id AND f.rejected = 0
+          ORDER BY f.confirmed DESC, f.confidence DESC LIMIT 1)
+     )
+     LEFT JOIN media_records m ON m.id = c.media_id";
+
+/// SQLite 人物仓库实现
+pub struct PersonRepository {
+    app: AppHandle,
+}
+
+impl PersonRepository {
+    pub fn new(app: AppHandle) -> Self {
+        Self { app }
+    }
+
+    /// 获取数据库连接
+    fn get_connection(&self) -> Result<Connection, Box<dyn std::error::Error>> {
+        open_connection(&self.app)
+    }
+
+    /// 获取所有人物，已命名的在前，其余按照片数量排序
+    pub async fn find_all(&self) -> Result<Vec<PersonRecord>, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM {} ORDER BY p.name IS NULL, photo_count DESC, p.name, p.created_at",
+            PERSON_COLUMNS, PERSON_FROM
+        ))?;
+        let people = stmt
+            .query_map([], map_person_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(people)
+    }
+
+    /// 获取人物的所有人脸，已确认的在前
+    pub async fn find_faces(
+        &self,
+        person_id: &str,
+    ) -> Result<Vec<FaceRecord>, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM faces f WHERE f.person_id = ?1 AND f.rejected = 0
+             ORDER BY f.confirmed DESC, f.confidence DESC",
+            FACE_COLUMNS
+        ))?;
+        let faces = stmt
+            .query_map(params![person_id], map_face_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(faces)
+    }
+
+    /// 获取包含该人物的媒体，按拍摄时间倒序
+    pub async fn find_media(
+        &self,
+        person_id: &str,
+    ) -> Result<Vec<MediaRecord>, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM media_records
+             WHERE media_records.id IN (
+                 SELECT media_id FROM faces WHERE person_id = ?1 AND rejected = 0
+             ) AND media_records.trashed_at IS NULL
+             ORDER BY COALESCE(media_records.taken_date, media_records.created_at) DESC",
+            MEDIA_COLUMNS
+        ))?;
+        let records = stmt
+            .query_map(params![person_id], map_media_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(records)
+    }
+
+    /// 设置或清除人物名称
+    ///
+    /// 命名表示用户认可了当前的分组，人物现有的人脸会被标记为已确认，重新聚类时不再变动。
+    pub async fn rename(
+        &self,
+        person_id: &str,
+        name: Option<&str>,
+    ) -> Result<PersonRecord, Box<dyn std::error::Error>> {
+        let name = name.map(str::trim).filter(|name| !name.is_empty());
+
+        let conn = self.get_connection()?;
+        let tx = conn.unchecked_transaction()?;
+        let now = Utc::now().to_rfc3339();
+
+        let updated = tx.execute(
+            "UPDATE people SET name = ?1, updated_at = ?2 WHERE id = ?3",
+            params![name, now, person_id],
+        )?;
+        if updated == 0 {
+            return Err(format!("未找到人物: {}", person_id).into());
+        }
+        if name.is_some() {
+            tx.execute(
+                "UPDATE faces SET confirmed = 1, updated_at = ?1
+                 WHERE person_id = ?2 AND confirmed = 0",
+                params![now, person_id],
+            )?;
+        }
+
+        let person =
+            find_person(&tx, person_id)?.ok_or_else(|| format!("未找到人物: {}", person_id))?;
+        tx.commit()?;
+
+        Ok(person)
+    }
+
+    /// 将 `source_id` 合并到 `target_id`，目标未命名时沿用来源的名称
+    pub async fn merge(
+        &self,
+        source_id: &str,
+        target_id: &str,
+    ) -> Result<PersonRecord, Box<dyn std::error::Error>> {
+        if source_id == target_id {
+            return Err("不能将人物合并到自身".into());
+        }
+
+        let conn = self.get_connection()?;
+        let tx = conn.unchecked_transaction()?;
+        let now = Utc::now().to_rfc3339();
+
+        let source =
+            find_person(&tx, source_id)?.ok_or_else(|| format!("未找到人物: {}", source_id))?;
+        find_person(&tx, target_id)?.ok_or_else(|| format!("未找到人物: {}", target_id))?;
+
+        // 合并是用户对归属的确认
+        tx.execute(
+            "UPDATE faces SET person_id = ?1, confirmed = 1, updated_at = ?2
+             WHERE person_id = ?3",
+            params![target_id, now, source_id],
+        )?;
+        tx.execute(
+            "UPDATE people SET name = COALESCE(name, ?1), updated_at = ?2 WHERE id = ?3",
+            params![source.name, now, target_id],
+        )?;
+        tx.execute("DELETE FROM people WHERE id = ?1", params![source_id])?;
+
+        let person =
+            find_person(&tx, target_id)?.ok_or_else(|| format!("未找到人物: {}", target_id))?;
+        tx.commit()?;
+
+        info!("已将人物 {} 合并到 {}", source_id, target_id);
+        Ok(person)
+    }
+
+    /// 将人脸移动到指定人物，未指定人物时新建一个人物（用于拆分）
+    ///
+    /// 移动后的人脸标记为已确认；原人物不再包含人脸且未命名时会被删除。
+    pub async fn move_faces(
+        &self,
+        face_ids: &[String],
+        person_id: Option<&str>,
+    ) -> Result<PersonRecord, Box<dyn std::error::Error>> {
+        if face_ids.is_empty() {
+            return Err("未选择人脸".into());
+        }
+
+        let conn = self.get_connection()?;
+        let tx = conn.unchecked_transaction()?;
+        let now = Utc::now().to_rfc3339();
+
+        let person_id = match person_id {
+            Some(person_id) => {
+                find_person(&tx, person_id)?.ok_or_else(|| format!("未找到人物: {}", person_id))?;
+                person_id.to_string()
+            }
+            None => insert_person(&tx, &now)?,
+        };
+
+        for face_id in face_ids {
+            let updated = tx.execute(
+                "UPDATE faces SET person_id = ?1, confirmed = 1, rejected = 0, updated_at = ?2
+                 WHERE id = ?3",
+                params![person_id, now, face_id],
+            )?;
+            if updated == 0 {
+                return Err(format!("未找到人脸: {}", face_id).into());
+            }
+        }
+        // 之前被标记为误检的人脸恢复后需要重新计入
+        refresh_face_counts(&tx, face_ids)?;
+        remove_empty_people(&tx)?;
+
+        let person =
+            find_person(&tx, &person_id)?.ok_or_else(|| format!("未找到人物: {}", person_id))?;
+        tx.commit()?;
+
+        Ok(person)
+    }
+
+    /// 将人脸标记为误检，从所属人物中移除并更新媒体的人脸数量
+    pub async fn reject_faces(
+        &self,
+        face_ids: &[String],
+    ) -> Result<usize, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+        let tx = conn.unchecked_transaction()?;
+        let now = Utc::now().to_rfc3339();
+
+        let mut rejected = 0;
+        for face_id in face_ids {
+            rejected += tx.execute(
+                "UPDATE faces SET person_id = NULL, confirmed = 0, rejected = 1, updated_at = ?1
+                 WHERE id = ?2",
+                params![now, face_id],
+            )?;
+        }
+        refresh_face_counts(&tx, face_ids)?;
+        remove_empty_people(&tx)?;
+        tx.commit()?;
+
+        info!("已将 {} 张人脸标记为误检", rejected);
+        Ok(rejected)
+    }
+
+    /// 设置人物封面，封面必须是该人物的人脸；为空时恢复自动选择
+    pub async fn set_cover(
+        &self,
+        person_id: &str,
+        face_id: Option<&str>,
+    ) -> Result<PersonRecord, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+
+        if let Some(face_id) = face_id {
+            let belongs: bool = conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM faces
+                               WHERE id = ?1 AND person_id = ?2 AND rejected = 0)",
+                params![face_id, person_id],
+                |row| row.get(0),
+            )?;
+            if !belongs {
+                return Err(format!("人脸不属于该人物: {}", face_id).into());
+            }
+        }
+
+        let updated = conn.execute(
+            "UPDATE people SET cover_face_id = ?1, updated_at = ?2 WHERE id = ?3",
+            params![face_id, Utc::now().to_rfc3339(), person_id],
+        )?;
+        if updated == 0 {
+            return Err(format!("未找到人物: {}", person_id).into());
+        }
+
+        let person =
+            find_person(&conn, person_id)?.ok_or_else(|| format!("未找到人物: {}", person_id))?;
+        Ok(person)
+    }
+
+    /// 对未归属的人脸进行聚类
+    ///
+    /// 人脸优先归入已有人物，人物的参考中心由已确认的人脸计算（没有已确认的人脸时使用全部人脸）；
+    /// 其余人脸之间聚成新的人物。`recluster` 为 true 时先将所有未确认的人脸移出人物再重新聚类。
+    pub async fn cluster(
+        &self,
+        recluster: bool,
+    ) -> Result<ClusterSummary, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+        let tx = conn.unchecked_transaction()?;
+        let now = Utc::now().to_rfc3339();
+
+        if recluster {
+            tx.execute(
+                "UPDATE faces SET person_id = NULL WHERE confirmed = 0 AND person_id IS NOT NULL",
+                [],
+            )?;
+            remove_empty_people(&tx)?;
+        }
+
+        // 每个人物的参考向量，已确认的人脸排在前面
+        let mut examples: HashMap<String, (bool, Vec<Vec<f32>>)> = HashMap::new();
+        {
+            let mut stmt = tx.prepare(
+                "SELECT person_id, confirmed, embedding FROM faces
+                 WHERE person_id IS NOT NULL AND rejected = 0
+                 ORDER BY confirmed DESC",
+            )?;
+            let mut rows = stmt.query([])?;
+            while let Some(row) = rows.next()? {
+                let person_id: String = row.get(0)?;
+                let confirmed: bool = row.get(1)?;
+                let embedding = blob_to_embedding(&row.get::<_, Vec<u8>>(2)?);
+
+                let (has_confirmed, embeddings) = examples.entry(person_id).or_default();
+                if confirmed {
+                    *has_confirmed = true;
+                } else if *has_confirmed {
+                    continue;
+                }
+                embeddings.push(embedding);
+            }
+        }
+        let (person_ids, centroids): (Vec<String>, Vec<Vec<f32>>) = examples
+            .into_iter()
+            .map(|(person_id, (_, embeddings))| (person_id, centroid(&embeddings)))
+            .unzip();
+
+        let (face_ids, embeddings): (Vec<String>, Vec<Vec<f32>>) = {
+            let mut stmt = tx.prepare(
+                "SELECT id, embedding FROM faces
+                 WHERE person_id IS NULL AND rejected = 0
+                 ORDER BY confidence DESC",
+            )?;
+            let rows = stmt
+                .query_map([], |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        blob_to_embedding(&row.get::<_, Vec<u8>>(1)?),
+                    ))
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            rows.into_iter().unzip()
+        };
+
+        let plan = plan_clusters(&centroids, &embeddings);
+        let mut summary = ClusterSummary {
+            assigned_faces: plan.assignments.len(),
+            new_people: plan.new_clusters.len(),
+            ..Default::default()
+        };
+
+        for (face, person) in &plan.assignments {
+            assign_face(&tx, &face_ids[*face], &person_ids[*person], &now)?;
+        }
+        for members in &plan.new_clusters {
+            let person_id = insert_person(&tx, &now)?;
+            for face in members {
+                assign_face(&tx, &face_ids[*face], &person_id, &now)?;
+            }
+            summary.clustered_faces += members.len();
+        }
+        summary.unassigned_faces =
+            face_ids.len() - summary.assigned_faces - summary.clustered_faces;
+
+        remove_empty_people(&tx)?;
+        tx.commit()?;
+
+        info!(
+            "人脸聚类完成: 归入已有人物 {} 张，新建人物 {} 个，未归属 {} 张",
+            summary.assigned_faces, summary.new_people, summary.unassigned_faces
+        );
+        Ok(summary)
+    }
+}
+
+fn find_person(conn: &Connection, person_id: &str) -> rusqlite::Result<Option<PersonRecord>> {
+    conn.query_row(
+        &format!(
+            "SELECT {} FROM {} WHERE p.id = ?1",
+            PERSON_COLUMNS, PERSON_FROM
+        ),
+        params![person_id],
+        map_person_row,
+    )
+    .optional()
+}
+
+/// 新建一个未命名的人物，返回其 ID
+fn insert_person(conn: &Connection, now: &str) -> rusqlite::Result<String> {
+    let id = Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO people (id, created_at, updated_at) VALUES (?1, ?2, ?2)",
+        params![id, now],
+    )?;
+    Ok(id)
+}
+
+fn assign_face(
+    conn: &Connection,
+    face_id: &str,
+    person_id: &str,
+    now: &str,
+) -> rusqlite::Result<()> {
+    conn.execute(
+        "UPDATE faces SET person_id = ?1, updated_at = ?2 WHERE id = ?3",
+        params![person_id, now, face_id],
+    )?;
+    Ok(())
+}
+
+/// 删除不再包含人脸的未命名人物，已命名的人物保留
+fn remove_empty_people(conn: &Connection) -> rusqlite::Result<usize> {
+    conn.execute(
+        "DELETE FROM people WHERE name IS NULL AND NOT EXISTS (
+             SELECT 1 FROM faces f WHERE f.person_id = people.id AND f.rejected = 0
+         )",
+        [],
+    )
+}
+
+/// 按未标记为误检的人脸重新计算相关媒体的 `face_count`
+fn refresh_face_counts(conn: &Connection, face_ids: &[String]) -> rusqlite::Result<()> {
+    if face_ids.is_empty() {
+        return Ok(());
+    }
+
+    let placeholders = vec!["?"; face_ids.len()].join(", ");
+    conn.execute(
+        &format!(
+            "UPDATE media_records SET face_count = (
+                 SELECT COUNT(*) FROM faces f WHERE f.media_id = media_records.id AND f.rejected = 0
+             )
+             WHERE id IN (SELECT media_id FROM faces WHERE id IN ({}))",
+            placeholders
+        ),
+        params_from_iter(face_ids),
+    )?;
+    Ok(())
+}
+
+/// 将一行查询结果解析为人物记录，列顺序与 `PERSON_COLUMNS` 一致
+fn map_person_row(row: &rusqlite::Row) -> rusqlite::Result<PersonRecord> {
+    let cover_bbox = match (
+        row.get::<_, Option<f64>>(5)?,
+        row.get::<_, Option<f64>>(6)?,
+        row.get::<_, Option<f64>>(7)?,
+        row.get::<_, Option<f64>>(8)?,
+    ) {
+        (Some(x), Some(y), Some(width), Some(height)) => Some([x, y, width, height]),
+        _ => None,
+    };
+
+    Ok(PersonRecord {
+        id: parse_uuid_column(0, row.get(0)?)?,
+        name: row.get(1)?,
+        cover_face_id: row
+            .get::<_, Option<String>>(2)?
+            .map(|id| parse_uuid_column(2, id))
+            .transpose()?,
+        cover_media_id: row
+            .get::<_, Option<String>>(3)?
+            .map(|id| parse_uuid_column(3, id))
+            .transpose()?,
+        cover_thumbnail_path: row.get(4)?,
+        cover_bbox,
+        face_count: row.get::<_, i64>(9)? as u64,
+        photo_count: row.get::<_, i64>(10)? as u64,
+        created_at: parse_time_column(11, row.get(11)?)?,
+        updated_at: parse_time_column(12, row.get(12)?)?,
+    })
+}
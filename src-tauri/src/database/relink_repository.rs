@@ -0,0 +1,384 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use log::{info, warn};
+use rusqlite::{params, Connection, OptionalExtension};
+use tauri::AppHandle;
+
+use crate::database::open_connection;
+use crate::models::relink::{MissingMedia, RelinkMatch, RelinkMatchKind, RelinkProposal};
+use crate::utils::file_ops::{file_sha256, walk_files};
+
+/// 丢失的媒体及匹配时使用的文件信息
+struct MissingFile {
+    media: MissingMedia,
+    name: String,
+    size: Option<u64>,
+    content_hash: Option<String>,
+    modified_at: Option<String>,
+}
+
+/// 新文件夹中尚未加入媒体库的文件
+struct Candidate {
+    path: PathBuf,
+    name: String,
+    size: u64,
+    modified_at: Option<String>,
+}
+
+/// SQLite 媒体重新关联仓库实现
+pub struct RelinkRepository {
+    app: AppHandle,
+}
+
+impl RelinkRepository {
+    pub fn new(app: AppHandle) -> Self {
+        Self { app }
+    }
+
+    /// 获取数据库连接
+    fn get_connection(&self) -> Result<Connection, Box<dyn std::error::Error>> {
+        open_connection(&self.app)
+    }
+
+    /// 获取原文件已不存在的媒体（含离线），不含回收站
+    pub async fn find_missing(&self) -> Result<Vec<MissingMedia>, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+
+        Ok(find_missing_media(&conn)?)
+    }
+
+    /// 记录原文件的内容哈希和修改时间，返回更新的数量
+    ///
+    /// 只处理尚未记录或修改时间已变化的文件，文件丢失后才能按内容匹配新位置。
+    pub async fn index_files(&self) -> Result<usize, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+
+        let rows = {
+            let mut stmt = conn.prepare(
+                "SELECT id, path, content_hash, file_modified_at FROM media_records
+                 WHERE trashed_at IS NULL",
+            )?;
+            let rows = stmt
+                .query_map([], |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, Option<String>>(2)?,
+                        row.get::<_, Option<String>>(3)?,
+                    ))
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            rows
+        };
+
+        let mut indexed = 0;
+        for (media_id, path, content_hash, modified_at) in rows {
+            let Some(current) = modified_time(Path::new(&path)) else {
+                continue;
+            };
+            if content_hash.is_some() && modified_at.as_deref() == Some(current.as_str()) {
+                continue;
+            }
+
+            // 不使用事务，计算哈希期间不锁定数据库
+            match file_sha256(Path::new(&path)) {
+                Ok(hash) => {
+                    conn.execute(
+                        "UPDATE media_records SET content_hash = ?1, file_modified_at = ?2
+                         WHERE id = ?3",
+                        params![hash, current, media_id],
+                    )?;
+                    indexed += 1;
+                }
+                Err(e) => warn!("计算文件哈希失败 {}: {}", path, e),
+            }
+        }
+
+        if indexed > 0 {
+            info!("已记录 {} 个文件的内容哈希", indexed);
+        }
+        Ok(indexed)
+    }
+
+    /// 在 `root` 下为丢失的媒体查找新位置，每个媒体最多一个建议
+    ///
+    /// 依次按内容哈希、文件大小和修改时间、文件名匹配，同一个文件只会建议给一个媒体；
+    /// 只有文件名相同且无法区分的多个文件不作建议。
+    pub async fn propose(
+        &self,
+        root: &str,
+    ) -> Result<Vec<RelinkProposal>, Box<dyn std::error::Error>> {
+        let root = Path::new(root);
+        if !root.is_dir() {
+            return Err(format!("文件夹不存在: {}", root.display()).into());
+        }
+
+        let conn = self.get_connection()?;
+        let missing = find_missing_files(&conn)?;
+        if missing.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let catalogued: HashSet<String> = {
+            let mut stmt = conn.prepare("SELECT path FROM media_records")?;
+            let paths = stmt
+                .query_map([], |row| row.get(0))?
+                .collect::<Result<HashSet<_>, _>>()?;
+            paths
+        };
+        let candidates: Vec<Candidate> = walk_files(root)
+            .into_iter()
+            .filter(|path| !catalogued.contains(path.to_string_lossy().as_ref()))
+            .filter_map(|path| {
+                let metadata = fs::metadata(&path).ok()?;
+                Some(Candidate {
+                    name: file_name_key(&path),
+                    size: metadata.len(),
+                    modified_at: modified_time(&path),
+                    path,
+                })
+            })
+            .collect();
+
+        let mut matched: HashMap<usize, (usize, RelinkMatchKind)> = HashMap::new();
+        let mut used: HashSet<usize> = HashSet::new();
+        let mut hashes: HashMap<usize, Option<String>> = HashMap::new();
+
+        for kind in [
+            RelinkMatchKind::Hash,
+            RelinkMatchKind::SizeAndModified,
+            RelinkMatchKind::Name,
+        ] {
+            for (index, file) in missing.iter().enumerate() {
+                if matched.contains_key(&index) {
+                    continue;
+                }
+                let available = || {
+                    candidates
+                        .iter()
+                        .enumerate()
+                        .filter(|(candidate, _)| !used.contains(candidate))
+                };
+
+                let found = match kind {
+                    RelinkMatchKind::Hash => {
+                        let (Some(size), Some(content_hash)) = (file.size, &file.content_hash)
+                        else {
+                            continue;
+                        };
+                        let same_size: Vec<usize> = available()
+                            .filter(|(_, candidate)| candidate.size == size)
+                            .map(|(candidate, _)| candidate)
+                            .collect();
+                        same_size.into_iter().find(|candidate| {
+                            let hash = hashes
+                                .entry(*candidate)
+                                .or_insert_with(|| file_sha256(&candidates[*candidate].path).ok());
+                            hash.as_ref() == Some(content_hash)
+                        })
+                    }
+                    RelinkMatchKind::SizeAndModified => {
+                        let (Some(size), Some(modified_at)) = (file.size, &file.modified_at) else {
+                            continue;
+                        };
+                        available()
+                            .find(|(_, candidate)| {
+                                candidate.size == size
+                                    && candidate.modified_at.as_ref() == Some(modified_at)
+                            })
+                            .map(|(candidate, _)| candidate)
+                    }
+                    RelinkMatchKind::Name => {
+                        let same_name: Vec<(usize, &Candidate)> = available()
+                            .filter(|(_, candidate)| candidate.name == file.name)
+                            .collect();
+                        match same_name.as_slice() {
+                            [(candidate, _)] => Some(*candidate),
+                            _ => {
+                                // 多个同名文件时只接受大小也相同的唯一一个
+                                let same_size: Vec<usize> = same_name
+                                    .iter()
+                                    .filter(|(_, candidate)| Some(candidate.size) == file.size)
+                                    .map(|(candidate, _)| *candidate)
+                                    .collect();
+                                match same_size.as_slice() {
+                                    [candidate] => Some(*candidate),
+                                    _ => None,
+                                }
+                            }
+                        }
+                    }
+                };
+
+                if let Some(candidate) = found {
+                    used.insert(candidate);
+                    matched.insert(index, (candidate, kind));
+                }
+            }
+        }
+
+        let mut proposals: Vec<RelinkProposal> = matched
+            .into_iter()
+            .map(|(index, (candidate, kind))| RelinkProposal {
+                media_id: missing[index].media.media_id.clone(),
+                old_path: missing[index].media.path.clone(),
+                new_path: candidates[candidate].path.to_string_lossy().into_owned(),
+                match_kind: kind,
+            })
+            .collect();
+        proposals.sort_by(|a, b| a.old_path.cmp(&b.old_path));
+
+        info!(
+            "在 {} 中为 {} 个丢失的媒体找到 {} 个匹配",
+            root.display(),
+            missing.len(),
+            proposals.len()
+        );
+        Ok(proposals)
+    }
+
+    /// 将媒体关联到新的文件路径，保留 ID、标签和相册等数据，返回更新的数量
+    ///
+    /// 新文件不存在或已被其他媒体使用时跳过；实况照片的视频在新位置旁边时一起更新。
+    pub async fn relink(
+        &self,
+        matches: &[RelinkMatch],
+    ) -> Result<usize, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+        let tx = conn.unchecked_transaction()?;
+        let now = Utc::now().to_rfc3339();
+
+        let mut relinked = 0;
+        for relink in matches {
+            let new_path = Path::new(&relink.new_path);
+            if !new_path.is_file() {
+                warn!("新文件不存在: {}", relink.new_path);
+                continue;
+            }
+            let in_use: bool = tx.query_row(
+                "SELECT EXISTS(SELECT 1 FROM media_records WHERE path = ?1 AND id != ?2)",
+                params![relink.new_path, relink.media_id],
+                |row| row.get(0),
+            )?;
+            if in_use {
+                warn!("文件已被其他媒体使用: {}", relink.new_path);
+                continue;
+            }
+
+            let motion_path: Option<Option<String>> = tx
+                .query_row(
+                    "SELECT motion_path FROM media_records WHERE id = ?1",
+                    params![relink.media_id],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            let Some(motion_path) = motion_path else {
+                warn!("未找到媒体: {}", relink.media_id);
+                continue;
+            };
+            let motion_path = motion_path.map(|motion_path| {
+                let motion = Path::new(&motion_path);
+                let moved = motion
+                    .file_name()
+                    .map(|name| new_path.with_file_name(name))
+                    .filter(|moved| !motion.exists() && moved.is_file());
+                match moved {
+                    Some(moved) => moved.to_string_lossy().into_owned(),
+                    None => motion_path,
+                }
+            });
+
+            let name = new_path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            tx.execute(
+                "UPDATE media_records SET path = ?1, name = ?2, motion_path = ?3, updated_at = ?4
+                 WHERE id = ?5",
+                params![relink.new_path, name, motion_path, now, relink.media_id],
+            )?;
+            relinked += 1;
+        }
+        tx.commit()?;
+
+        info!("已重新关联 {} 个媒体", relinked);
+        Ok(relinked)
+    }
+}
+
+/// 原文件已不存在的媒体，不含回收站；所在文件夹也不存在时标记为离线
+pub(crate) fn find_missing_media(conn: &Connection) -> rusqlite::Result<Vec<MissingMedia>> {
+    Ok(find_missing_files(conn)?
+        .into_iter()
+        .map(|file| file.media)
+        .collect())
+}
+
+fn find_missing_files(conn: &Connection) -> rusqlite::Result<Vec<MissingFile>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, path, size, content_hash, file_modified_at FROM media_records
+         WHERE trashed_at IS NULL ORDER BY path",
+    )?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<i64>>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, Option<String>>(4)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // 同一文件夹下的媒体只检查一次文件夹是否存在
+    let mut folders: HashMap<PathBuf, bool> = HashMap::new();
+    let mut missing = Vec::new();
+    for (media_id, path, size, content_hash, modified_at) in rows {
+        let file = Path::new(&path);
+        if file.exists() {
+            continue;
+        }
+        let offline = match file.parent() {
+            Some(folder) => !*folders
+                .entry(folder.to_path_buf())
+                .or_insert_with(|| folder.is_dir()),
+            None => true,
+        };
+
+        missing.push(MissingFile {
+            name: file_name_key(file),
+            size: size.map(|size| size as u64),
+            content_hash,
+            modified_at,
+            media: MissingMedia {
+                media_id,
+                path,
+                offline,
+            },
+        });
+    }
+
+    Ok(missing)
+}
+
+/// 按文件名匹配时使用的键，不区分大小写
+fn file_name_key(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+/// 文件的修改时间，精确到秒，便于与不同文件系统上的复制件比较
+pub(crate) fn modified_time(path: &Path) -> Option<String> {
+    let modified = fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()?;
+
+    Some(DateTime::<Utc>::from(modified).to_rfc3339_opts(SecondsFormat::Secs, true))
+}
@@ -0,0 +1,379 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use chrono::Utc;
+use log::{error, info};
+use rusqlite::{params, Connection, OptionalExtension};
+use tauri::AppHandle;
+
+use crate::database::{open_connection, parse_time_column};
+use crate::models::rename::{RenameConflict, RenamePreview};
+use crate::utils::file_ops::move_files_atomically;
+use crate::utils::image_processor::get_thumbnail_dir;
+use crate::utils::path_template::{assign_sequences, render_path_template, TemplateValues};
+use crate::utils::xmp_sidecar::{find_sidecar, moved_sidecar_path};
+
+/// 重命名或移动媒体时需要一起处理的文件
+#[derive(Clone)]
+pub(crate) struct MediaFiles {
+    pub media_id: String,
+    pub path: PathBuf,
+    pub motion_path: Option<PathBuf>,
+    pub thumbnail_path: Option<PathBuf>,
+    /// 拍摄时间、相机和地点，用于按模板生成新路径
+    pub values: TemplateValues,
+}
+
+/// 单个媒体的移动计划
+pub(crate) struct MediaMove {
+    pub media_id: String,
+    pub old_path: PathBuf,
+    pub new_path: PathBuf,
+    /// 需要移动的文件，第一项是媒体文件本身，其后是实况照片视频、边车文件和缩略图
+    pub file_moves: Vec<(PathBuf, PathBuf)>,
+    pub motion_path: Option<PathBuf>,
+    pub thumbnail_path: Option<PathBuf>,
+}
+
+/// SQLite 媒体文件重命名仓库实现
+pub struct RenameRepository {
+    app: AppHandle,
+}
+
+impl RenameRepository {
+    pub fn new(app: AppHandle) -> Self {
+        Self { app }
+    }
+
+    /// 获取数据库连接
+    fn get_connection(&self) -> Result<Connection, Box<dyn std::error::Error>> {
+        open_connection(&self.app)
+    }
+
+    /// 预览按模板重命名的结果，按拍摄时间排序，冲突的文件带有冲突原因
+    pub async fn preview(
+        &self,
+        media_ids: &[String],
+        template: &str,
+    ) -> Result<Vec<RenamePreview>, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+        let (previews, _) =
+            plan_renames(&conn, &get_thumbnail_dir(&self.app), media_ids, template)?;
+
+        Ok(previews)
+    }
+
+    /// 按模板重命名媒体文件，返回重命名的数量
+    ///
+    /// 存在任何冲突时不做修改；重命名中途失败时已重命名的文件会被改回原名。
+    pub async fn rename(
+        &self,
+        media_ids: &[String],
+        template: &str,
+    ) -> Result<usize, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+        let (previews, moves) =
+            plan_renames(&conn, &get_thumbnail_dir(&self.app), media_ids, template)?;
+
+        let conflicts = previews.iter().filter(|p| p.conflict.is_some()).count();
+        if conflicts > 0 {
+            return Err(format!("{} 个文件存在冲突，未重命名任何文件", conflicts).into());
+        }
+
+        let renamed = apply_media_moves(&conn, &moves)?;
+        info!("已重命名 {} 个文件", renamed);
+        Ok(renamed)
+    }
+}
+
+/// 生成重命名计划，文件名不变的媒体只出现在预览中
+///
+/// 模板生成的是不含扩展名的文件名，原扩展名保持不变；模板中不能包含文件夹。
+fn plan_renames(
+    conn: &Connection,
+    thumbnail_dir: &Path,
+    media_ids: &[String],
+    template: &str,
+) -> Result<(Vec<RenamePreview>, Vec<MediaMove>), Box<dyn std::error::Error>> {
+    let mut files = load_media_files(conn, media_ids)?;
+    files.sort_by(|a, b| {
+        a.values
+            .taken_date
+            .cmp(&b.values.taken_date)
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    assign_sequences(files.iter_mut().map(|file| &mut file.values));
+
+    let mut targets = Vec::new();
+    for file in files {
+        let name = render_path_template(template, &file.values)?;
+        if name.components().count() != 1 {
+            return Err(format!("重命名模板不能包含文件夹: {}", template).into());
+        }
+        let mut name = name.into_os_string();
+        if let Some(ext) = file.path.extension() {
+            name.push(".");
+            name.push(ext);
+        }
+        let new_path = file.path.with_file_name(name);
+        targets.push((file, new_path));
+    }
+
+    let order: HashMap<String, usize> = targets
+        .iter()
+        .enumerate()
+        .map(|(index, (file, _))| (file.media_id.clone(), index))
+        .collect();
+    let (unchanged, changed): (Vec<_>, Vec<_>) = targets
+        .into_iter()
+        .partition(|(file, new_path)| file.path == *new_path);
+    let mut claimed = HashSet::new();
+    let mut moves = Vec::new();
+    for (file, new_path) in changed {
+        let media_move = plan_media_move(conn, thumbnail_dir, file, new_path, &claimed)?;
+        claimed.extend(media_move.file_moves.iter().map(|(_, to)| to.clone()));
+        moves.push(media_move);
+    }
+    let conflicts = find_move_conflicts(&moves);
+
+    let mut previews: Vec<RenamePreview> = moves
+        .iter()
+        .zip(conflicts)
+        .map(|(media_move, conflict)| RenamePreview {
+            media_id: media_move.media_id.clone(),
+            old_path: media_move.old_path.to_string_lossy().into_owned(),
+            new_path: media_move.new_path.to_string_lossy().into_owned(),
+            conflict,
+        })
+        .chain(unchanged.into_iter().map(|(file, _)| {
+            let path = file.path.to_string_lossy().into_owned();
+            RenamePreview {
+                media_id: file.media_id,
+                old_path: path.clone(),
+                new_path: path,
+                conflict: None,
+            }
+        }))
+        .collect();
+    previews.sort_by_key(|preview| order.get(&preview.media_id).copied());
+
+    Ok((previews, moves))
+}
+
+/// 读取媒体的文件路径及生成新路径所需的信息，不存在或在回收站中的媒体被忽略
+pub(crate) fn load_media_files(
+    conn: &Connection,
+    media_ids: &[String],
+) -> rusqlite::Result<Vec<MediaFiles>> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT id, path, motion_path, thumbnail_path, taken_date, camera_make, camera_model,
+                country, region, city
+         FROM media_records WHERE id = ?1 AND trashed_at IS NULL",
+    )?;
+
+    let mut files = Vec::new();
+    for media_id in media_ids {
+        let file = stmt
+            .query_row(params![media_id], |row| {
+                let path = PathBuf::from(row.get::<_, String>(1)?);
+                // 旧版本会把空值写成字符串 "null"，无法解析的拍摄时间按没有拍摄时间处理
+                let taken_date = row
+                    .get::<_, Option<String>>(4)?
+                    .and_then(|value| parse_time_column(4, value).ok());
+                Ok(MediaFiles {
+                    media_id: row.get(0)?,
+                    motion_path: row.get::<_, Option<String>>(2)?.map(PathBuf::from),
+                    thumbnail_path: row.get::<_, Option<String>>(3)?.map(PathBuf::from),
+                    values: TemplateValues {
+                        taken_date,
+                        camera_make: row.get(5)?,
+                        camera_model: row.get(6)?,
+                        country: row.get(7)?,
+                        region: row.get(8)?,
+                        city: row.get(9)?,
+                        ..TemplateValues::for_path(&path)
+                    },
+                    path,
+                })
+            })
+            .optional()?;
+        files.extend(file);
+    }
+
+    Ok(files)
+}
+
+/// 生成单个媒体的移动计划，实况照片视频和边车文件使用与媒体文件相同的文件名
+///
+/// 缩略图按文件名命名，文件名改变时一起重命名；缩略图被其他媒体共用、新名称已被占用
+/// 或在 `claimed` 中（同一批中其他文件将要使用）时保持不变。
+pub(crate) fn plan_media_move(
+    conn: &Connection,
+    thumbnail_dir: &Path,
+    file: MediaFiles,
+    new_path: PathBuf,
+    claimed: &HashSet<PathBuf>,
+) -> rusqlite::Result<MediaMove> {
+    let thumbnail_key = |path: &Path| {
+        path.file_stem()
+            .map(|stem| thumbnail_dir.join(format!("{}.jpg", stem.to_string_lossy())))
+    };
+    let mut file_moves = vec![(file.path.clone(), new_path.clone())];
+
+    let motion_path = match &file.motion_path {
+        // 动态照片的视频内嵌在照片中
+        Some(motion) if *motion == file.path => Some(new_path.clone()),
+        Some(motion) if motion.is_file() => {
+            let moved = match motion.extension() {
+                Some(ext) => new_path.with_extension(ext),
+                None => new_path.with_extension(""),
+            };
+            file_moves.push((motion.clone(), moved.clone()));
+            Some(moved)
+        }
+        other => other.clone(),
+    };
+
+    if let Some(sidecar) = find_sidecar(&file.path) {
+        let moved = moved_sidecar_path(&file.path, &sidecar, &new_path);
+        file_moves.push((sidecar, moved));
+    }
+
+    let mut thumbnail_path = file.thumbnail_path.clone();
+    if let (Some(thumbnail), Some(old_key), Some(new_key)) = (
+        &file.thumbnail_path,
+        thumbnail_key(&file.path),
+        thumbnail_key(&new_path),
+    ) {
+        let movable = *thumbnail == old_key
+            && old_key != new_key
+            && thumbnail.is_file()
+            && !new_key.exists()
+            && !claimed.contains(&new_key);
+        if movable {
+            let shared: bool = conn.query_row(
+                "SELECT COUNT(*) > 1 FROM media_records WHERE thumbnail_path = ?1",
+                params![thumbnail.to_string_lossy()],
+                |row| row.get(0),
+            )?;
+            if !shared {
+                file_moves.push((thumbnail.clone(), new_key.clone()));
+                thumbnail_path = Some(new_key);
+            }
+        }
+    }
+
+    Ok(MediaMove {
+        media_id: file.media_id,
+        old_path: file.path,
+        new_path,
+        file_moves,
+        motion_path,
+        thumbnail_path,
+    })
+}
+
+/// 检查每个移动计划的冲突，顺序与 `moves` 一致
+///
+/// 新路径被同一批中将要移走的文件占用时不算冲突。
+pub(crate) fn find_move_conflicts(moves: &[MediaMove]) -> Vec<Option<RenameConflict>> {
+    let sources: HashSet<&PathBuf> = moves
+        .iter()
+        .flat_map(|media_move| media_move.file_moves.iter().map(|(from, _)| from))
+        .collect();
+    let mut target_counts: HashMap<&PathBuf, usize> = HashMap::new();
+    for (_, to) in moves.iter().flat_map(|media_move| &media_move.file_moves) {
+        *target_counts.entry(to).or_insert(0) += 1;
+    }
+
+    moves
+        .iter()
+        .map(|media_move| {
+            if !media_move.old_path.is_file() {
+                return Some(RenameConflict::SourceMissing);
+            }
+            for (_, to) in &media_move.file_moves {
+                if target_counts.get(to).copied().unwrap_or_default() > 1 {
+                    return Some(RenameConflict::DuplicateTarget);
+                }
+                if to.exists() && !sources.contains(to) {
+                    return Some(RenameConflict::TargetExists);
+                }
+            }
+            None
+        })
+        .collect()
+}
+
+/// 移动文件并更新媒体的路径、文件名、实况照片视频和缩略图，返回移动的媒体数量
+///
+/// 任何文件移动失败时已移动的文件会被移回；数据库更新失败时同样移回所有文件。
+pub(crate) fn apply_media_moves(
+    conn: &Connection,
+    moves: &[MediaMove],
+) -> Result<usize, Box<dyn std::error::Error>> {
+    let file_moves: Vec<(PathBuf, PathBuf)> = moves
+        .iter()
+        .flat_map(|media_move| media_move.file_moves.iter().cloned())
+        .collect();
+    move_files_atomically(&file_moves)?;
+
+    let result = (|| -> rusqlite::Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        let now = Utc::now().to_rfc3339();
+        // path 列唯一，互换或顺延文件名时新路径可能是另一条记录的旧路径，
+        // 先把所有记录改为按 id 生成的临时路径，再写入最终路径
+        for media_move in moves {
+            tx.execute(
+                "UPDATE media_records SET path = ?1 WHERE id = ?2",
+                params![
+                    format!("rename-pending:{}", media_move.media_id),
+                    media_move.media_id
+                ],
+            )?;
+        }
+        for media_move in moves {
+            let name = media_move
+                .new_path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            tx.execute(
+                "UPDATE media_records
+                 SET path = ?1, name = ?2, motion_path = ?3, thumbnail_path = ?4, updated_at = ?5
+                 WHERE id = ?6",
+                params![
+                    media_move.new_path.to_string_lossy(),
+                    name,
+                    media_move
+                        .motion_path
+                        .as_ref()
+                        .map(|path| path.to_string_lossy()),
+                    media_move
+                        .thumbnail_path
+                        .as_ref()
+                        .map(|path| path.to_string_lossy()),
+                    now,
+                    media_move.media_id
+                ],
+            )?;
+        }
+        tx.commit()
+    })();
+
+    if let Err(e) = result {
+        let reverse: Vec<(PathBuf, PathBuf)> = file_moves
+            .into_iter()
+            .rev()
+            .map(|(from, to)| (to, from))
+            .collect();
+        if let Err(undo) = move_files_atomically(&reverse) {
+            error!("更新数据库失败后移回文件失败: {}", undo);
+        }
+        return Err(e.into());
+    }
+
+    Ok(moves.len())
+}
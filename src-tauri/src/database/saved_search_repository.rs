@@ -0,0 +1,146 @@
+use chrono::Utc;
+use log::info;
+use rusqlite::{params, Connection, OptionalExtension};
+use tauri::AppHandle;
+use uuid::Uuid;
+
+use crate::database::{open_connection, parse_time_column, parse_uuid_column};
+use crate::models::search::SavedSearchRecord;
+use crate::utils::search_query::parse_search_query;
+
+/// SQLite 保存搜索仓库实现
+pub struct SavedSearchRepository {
+    app: AppHandle,
+}
+
+impl SavedSearchRepository {
+    pub fn new(app: AppHandle) -> Self {
+        Self { app }
+    }
+
+    /// 获取数据库连接
+    fn get_connection(&self) -> Result<Connection, Box<dyn std::error::Error>> {
+        open_connection(&self.app)
+    }
+
+    /// 获取所有保存的搜索
+    pub async fn find_all(&self) -> Result<Vec<SavedSearchRecord>, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT id, name, query, position, created_at, updated_at
+             FROM saved_searches ORDER BY position, name",
+        )?;
+        let searches = stmt
+            .query_map([], map_saved_search_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(searches)
+    }
+
+    /// 保存搜索，查询必须能被正确解析
+    pub async fn create(
+        &self,
+        name: &str,
+        query: &str,
+    ) -> Result<SavedSearchRecord, Box<dyn std::error::Error>> {
+        let name = validate_name(name)?;
+        let query = validate_query(query)?;
+
+        let conn = self.get_connection()?;
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO saved_searches (id, name, query, position, created_at, updated_at)
+             VALUES (?1, ?2, ?3, (SELECT COALESCE(MAX(position) + 1, 0) FROM saved_searches),
+                     ?4, ?4)",
+            params![id, name, query, now],
+        )?;
+
+        let search = find_saved_search(&conn, &id)?.ok_or("保存搜索后未能读取记录")?;
+        info!("已保存搜索: {}", search.name);
+        Ok(search)
+    }
+
+    /// 修改保存搜索的名称和/或查询
+    pub async fn update(
+        &self,
+        search_id: &str,
+        name: Option<&str>,
+        query: Option<&str>,
+    ) -> Result<SavedSearchRecord, Box<dyn std::error::Error>> {
+        let name = name.map(validate_name).transpose()?;
+        let query = query.map(validate_query).transpose()?;
+
+        let conn = self.get_connection()?;
+        let updated = conn.execute(
+            "UPDATE saved_searches SET name = COALESCE(?1, name), query = COALESCE(?2, query),
+                                       updated_at = ?3
+             WHERE id = ?4",
+            params![name, query, Utc::now().to_rfc3339(), search_id],
+        )?;
+        if updated == 0 {
+            return Err(format!("未找到保存的搜索: {}", search_id).into());
+        }
+
+        let search = find_saved_search(&conn, search_id)?
+            .ok_or_else(|| format!("未找到保存的搜索: {}", search_id))?;
+        Ok(search)
+    }
+
+    /// 删除保存的搜索
+    pub async fn delete(&self, search_id: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+
+        let deleted = conn.execute(
+            "DELETE FROM saved_searches WHERE id = ?1",
+            params![search_id],
+        )?;
+        if deleted == 0 {
+            return Err(format!("未找到保存的搜索: {}", search_id).into());
+        }
+
+        Ok(())
+    }
+}
+
+fn validate_name(name: &str) -> Result<&str, Box<dyn std::error::Error>> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("搜索名称不能为空".into());
+    }
+    Ok(name)
+}
+
+fn validate_query(query: &str) -> Result<&str, Box<dyn std::error::Error>> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Err("搜索内容不能为空".into());
+    }
+    parse_search_query(query)?;
+    Ok(query)
+}
+
+fn find_saved_search(
+    conn: &Connection,
+    search_id: &str,
+) -> rusqlite::Result<Option<SavedSearchRecord>> {
+    conn.query_row(
+        "SELECT id, name, query, position, created_at, updated_at
+         FROM saved_searches WHERE id = ?1",
+        params![search_id],
+        map_saved_search_row,
+    )
+    .optional()
+}
+
+fn map_saved_search_row(row: &rusqlite::Row) -> rusqlite::Result<SavedSearchRecord> {
+    Ok(SavedSearchRecord {
+        id: parse_uuid_column(0, row.get(0)?)?,
+        name: row.get(1)?,
+        query: row.get(2)?,
+        position: row.get::<_, i64>(3)? as u32,
+        created_at: parse_time_column(4, row.get(4)?)?,
+        updated_at: parse_time_column(5, row.get(5)?)?,
+    })
+}
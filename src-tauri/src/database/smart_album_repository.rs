@@ -0,0 +1,561 @@
+use chrono::{Duration, Utc};
+use log::info;
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use tauri::AppHandle;
+use uuid::Uuid;
+
+use crate::database::media_repository::{
+    build_match_query, map_media_row, media_kind_to_str, refresh_search_index, MEDIA_COLUMNS,
+};
+use crate::database::{open_connection, parse_time_column, parse_uuid_column};
+use crate::models::image::MediaRecord;
+use crate::models::smart_album::{CompareOp, NumericField, SmartAlbumRecord, SmartRule};
+
+/// 每纬度对应的距离（公里）
+const KM_PER_DEGREE: f64 = 111.32;
+
+/// SQLite 智能相册仓库实现
+///
+/// 智能相册只保存规则，成员在每次查询时由规则实时计算，因此目录变化后无需额外维护。
+pub struct SmartAlbumRepository {
+    app: AppHandle,
+}
+
+impl SmartAlbumRepository {
+    pub fn new(app: AppHandle) -> Self {
+        Self { app }
+    }
+
+    /// 获取数据库连接，规则中可能包含全文搜索，先刷新搜索索引
+    fn get_connection(&self) -> Result<Connection, Box<dyn std::error::Error>> {
+        let conn = open_connection(&self.app)?;
+        refresh_search_index(&conn)?;
+        Ok(conn)
+    }
+
+    /// 获取所有智能相册及当前匹配的媒体数量
+    pub async fn find_all(&self) -> Result<Vec<SmartAlbumRecord>, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT id, name, rule, position, created_at, updated_at
+             FROM smart_albums ORDER BY position, name",
+        )?;
+        let rows = stmt
+            .query_map([], map_smart_album_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut albums = Vec::with_capacity(rows.len());
+        for row in rows {
+            albums.push(row.into_record(&conn)?);
+        }
+
+        Ok(albums)
+    }
+
+    /// 通过 ID 查找智能相册
+    pub async fn find_by_id(
+        &self,
+        album_id: &str,
+    ) -> Result<Option<SmartAlbumRecord>, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+        match find_smart_album(&conn, album_id)? {
+            Some(row) => Ok(Some(row.into_record(&conn)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// 创建智能相册，追加到列表末尾
+    pub async fn create(
+        &self,
+        name: &str,
+        rule: &SmartRule,
+    ) -> Result<SmartAlbumRecord, Box<dyn std::error::Error>> {
+        let name = validate_name(name)?;
+        // 保存前先确认规则可以编译
+        compile_rule(rule, &mut Vec::new())?;
+
+        let conn = self.get_connection()?;
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO smart_albums (id, name, rule, position, created_at, updated_at)
+             VALUES (?1, ?2, ?3, (SELECT COALESCE(MAX(position) + 1, 0) FROM smart_albums),
+                     ?4, ?4)",
+            params![id, name, serde_json::to_string(rule)?, now],
+        )?;
+
+        let album = find_smart_album(&conn, &id)?
+            .ok_or("创建智能相册后未能读取记录")?
+            .into_record(&conn)?;
+
+        info!("已创建智能相册: {}", album.name);
+        Ok(album)
+    }
+
+    /// 更新智能相册的名称和/或规则
+    pub async fn update(
+        &self,
+        album_id: &str,
+        name: Option<&str>,
+        rule: Option<&SmartRule>,
+    ) -> Result<SmartAlbumRecord, Box<dyn std::error::Error>> {
+        let name = name.map(validate_name).transpose()?;
+        let rule = match rule {
+            Some(rule) => {
+                compile_rule(rule, &mut Vec::new())?;
+                Some(serde_json::to_string(rule)?)
+            }
+            None => None,
+        };
+
+        let conn = self.get_connection()?;
+        let updated = conn.execute(
+            "UPDATE smart_albums SET name = COALESCE(?1, name), rule = COALESCE(?2, rule),
+                                     updated_at = ?3
+             WHERE id = ?4",
+            params![name, rule, Utc::now().to_rfc3339(), album_id],
+        )?;
+        if updated == 0 {
+            return Err(format!("未找到智能相册: {}", album_id).into());
+        }
+
+        find_smart_album(&conn, album_id)?
+            .ok_or_else(|| format!("未找到智能相册: {}", album_id))?
+            .into_record(&conn)
+    }
+
+    /// 删除智能相册，不影响其中的媒体
+    pub async fn delete(&self, album_id: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+
+        let deleted = conn.execute("DELETE FROM smart_albums WHERE id = ?1", params![album_id])?;
+        if deleted == 0 {
+            return Err(format!("未找到智能相册: {}", album_id).into());
+        }
+
+        info!("已删除智能相册: {}", album_id);
+        Ok(())
+    }
+
+    /// 获取智能相册当前匹配的媒体，按拍摄时间倒序
+    pub async fn find_media(
+        &self,
+        album_id: &str,
+    ) -> Result<Vec<MediaRecord>, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+        let row = find_smart_album(&conn, album_id)?
+            .ok_or_else(|| format!("未找到智能相册: {}", album_id))?;
+
+        let mut values = Vec::new();
+        let condition = compile_rule(&row.rule, &mut values)?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM media_records WHERE media_records.trashed_at IS NULL AND ({})
+             ORDER BY COALESCE(media_records.taken_date, media_records.created_at) DESC",
+            MEDIA_COLUMNS, condition
+        ))?;
+        let records = stmt
+            .query_map(params_from_iter(values), map_media_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(records)
+    }
+
+    /// 统计规则匹配的媒体数量，用于编辑规则时预览
+    pub async fn count_matching(
+        &self,
+        rule: &SmartRule,
+    ) -> Result<u64, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+        count_matching(&conn, rule)
+    }
+}
+
+/// 从数据库读取的智能相册，计数需要另外计算
+struct SmartAlbumRow {
+    id: Uuid,
+    name: String,
+    rule: SmartRule,
+    position: u32,
+    created_at: chrono::DateTime<Utc>,
+    updated_at: chrono::DateTime<Utc>,
+}
+
+impl SmartAlbumRow {
+    fn into_record(
+        self,
+        conn: &Connection,
+    ) -> Result<SmartAlbumRecord, Box<dyn std::error::Error>> {
+        let media_count = count_matching(conn, &self.rule)?;
+        Ok(SmartAlbumRecord {
+            id: self.id,
+            name: self.name,
+            rule: self.rule,
+            position: self.position,
+            media_count,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        })
+    }
+}
+
+fn validate_name(name: &str) -> Result<&str, Box<dyn std::error::Error>> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("智能相册名称不能为空".into());
+    }
+    Ok(name)
+}
+
+fn find_smart_album(conn: &Connection, album_id: &str) -> rusqlite::Result<Option<SmartAlbumRow>> {
+    conn.query_row(
+        "SELECT id, name, rule, position, created_at, updated_at
+         FROM smart_albums WHERE id = ?1",
+        params![album_id],
+        map_smart_album_row,
+    )
+    .optional()
+}
+
+fn map_smart_album_row(row: &rusqlite::Row) -> rusqlite::Result<SmartAlbumRow> {
+    let rule: String = row.get(2)?;
+    let rule = serde_json::from_str(&rule).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(2, rusqlite::types::Type::Text, Box::new(e))
+    })?;
+
+    Ok(SmartAlbumRow {
+        id: parse_uuid_column(0, row.get(0)?)?,
+        name: row.get(1)?,
+        rule,
+        position: row.get::<_, i64>(3)? as u32,
+        created_at: parse_time_column(4, row.get(4)?)?,
+        updated_at: parse_time_column(5, row.get(5)?)?,
+    })
+}
+
+fn count_matching(conn: &Connection, rule: &SmartRule) -> Result<u64, Box<dyn std::error::Error>> {
+    let mut values = Vec::new();
+    let condition = compile_rule(rule, &mut values)?;
+    let count: i64 = conn.query_row(
+        &format!(
+            "SELECT COUNT(*) FROM media_records
+             WHERE media_records.trashed_at IS NULL AND ({})",
+            condition
+        ),
+        params_from_iter(values),
+        |row| row.get(0),
+    )?;
+    Ok(count as u64)
+}
+
+/// 将规则树编译为针对 `media_records` 的 WHERE 条件
+///
+/// 所有用户输入都作为匿名参数追加到 `values`，顺序与条件中的 `?` 一致。
+pub(crate) fn compile_rule(
+    rule: &SmartRule,
+    values: &mut Vec<Value>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let condition = match rule {
+        SmartRule::All { rules } => join_rules(rules, " AND ", "1", values)?,
+        SmartRule::Any { rules } => join_rules(rules, " OR ", "0", values)?,
+        // 元数据为空时条件的结果为 NULL，NOT NULL 仍为 NULL，媒体会同时被规则及其否定排除；
+        // AND / OR 中的 NULL 等同于不满足，因此只需在取反前把 NULL 视为 0
+        SmartRule::Not { rule } => format!("NOT COALESCE(({}), 0)", compile_rule(rule, values)?),
+        SmartRule::DateRange { from, to, before } => {
+            let mut parts = Vec::new();
+            if let Some(from) = from {
+                parts.push("COALESCE(media_records.taken_date, media_records.created_at) >= ?");
+                values.push(Value::Text(from.to_rfc3339()));
+            }
+            if let Some(to) = to {
+                parts.push("COALESCE(media_records.taken_date, media_records.created_at) <= ?");
+                values.push(Value::Text(to.to_rfc3339()));
+            }
+            if let Some(before) = before {
+                parts.push("COALESCE(media_records.taken_date, media_records.created_at) < ?");
+                values.push(Value::Text(before.to_rfc3339()));
+            }
+            if parts.is_empty() {
+                "1".to_string()
+            } else {
+                parts.join(" AND ")
+            }
+        }
+        SmartRule::RecentDays { days } => {
+            let since = Utc::now() - Duration::days(*days as i64);
+            values.push(Value::Text(since.to_rfc3339()));
+            "COALESCE(media_records.taken_date, media_records.created_at) >= ?".to_string()
+        }
+        SmartRule::Camera { value } => {
+            let pattern = contains_pattern(value)?;
+            values.push(Value::Text(pattern.clone()));
+            values.push(Value::Text(pattern));
+            "(media_records.camera_make LIKE ? ESCAPE '\\' \
+             OR media_records.camera_model LIKE ? ESCAPE '\\')"
+                .to_string()
+        }
+        SmartRule::Lens { value } => {
+            values.push(Value::Text(contains_pattern(value)?));
+            "media_records.lens_model LIKE ? ESCAPE '\\'".to_string()
+        }
+        SmartRule::Tag {
+            path,
+            include_children,
+        } => {
+            let path = path
+                .split('/')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .collect::<Vec<_>>()
+                .join("/");
+            if path.is_empty() {
+                return Err("标签规则缺少标签路径".into());
+            }
+            let mut condition = "tags.path = ? COLLATE NOCASE".to_string();
+            values.push(Value::Text(path.clone()));
+            if *include_children {
+                condition.push_str(" OR tags.path LIKE ? ESCAPE '\\'");
+                values.push(Value::Text(format!("{}/%", escape_like(&path))));
+            }
+            format!(
+                "media_records.id IN (SELECT media_tags.media_id FROM media_tags
+                 JOIN tags ON tags.id = media_tags.tag_id WHERE {})",
+                condition
+            )
+        }
+        SmartRule::Kind { kind } => {
+            values.push(Value::Text(media_kind_to_str(kind).to_string()));
+            "media_records.kind = ?".to_string()
+        }
+        SmartRule::Extension { extensions } => {
+            let extensions: Vec<String> = extensions
+                .iter()
+                .map(|e| e.trim().trim_start_matches('.').to_lowercase())
+                .filter(|e| !e.is_empty())
+                .collect();
+            if extensions.is_empty() {
+                "0".to_string()
+            } else {
+                let parts: Vec<&str> = extensions
+                    .iter()
+                    .map(|_| "lower(media_records.name) LIKE ? ESCAPE '\\'")
+                    .collect();
+                for extension in &extensions {
+                    values.push(Value::Text(format!("%.{}", escape_like(extension))));
+                }
+                format!("({})", parts.join(" OR "))
+            }
+        }
+        SmartRule::Size { min, max } => {
+            let mut parts = vec!["media_records.size IS NOT NULL"];
+            if let Some(min) = min {
+                parts.push("media_records.size >= ?");
+                values.push(Value::Integer(*min as i64));
+            }
+            if let Some(max) = max {
+                parts.push("media_records.size <= ?");
+                values.push(Value::Integer(*max as i64));
+            }
+            parts.join(" AND ")
+        }
+        SmartRule::Rating { min, max } => {
+            let mut parts = Vec::new();
+            if let Some(min) = min {
+                parts.push("media_records.rating >= ?");
+                values.push(Value::Integer(*min as i64));
+            }
+            if let Some(max) = max {
+                parts.push("media_records.rating <= ?");
+                values.push(Value::Integer(*max as i64));
+            }
+            if parts.is_empty() {
+                "1".to_string()
+            } else {
+                parts.join(" AND ")
+            }
+        }
+        SmartRule::Favorite => "media_records.favorite = 1".to_string(),
+        SmartRule::ColorLabel { labels } => {
+            if labels.is_empty() {
+                "0".to_string()
+            } else {
+                values.extend(
+                    labels
+                        .iter()
+                        .map(|label| Value::Text(label.as_str().to_string())),
+                );
+                format!(
+                    "media_records.color_label IN ({})",
+                    vec!["?"; labels.len()].join(", ")
+                )
+            }
+        }
+        SmartRule::Flag { flag } => {
+            values.push(Value::Integer(flag.to_db()));
+            "media_records.flag = ?".to_string()
+        }
+        SmartRule::Location {
+            latitude,
+            longitude,
+            radius_km,
+        } => {
+            if !(-90.0..=90.0).contains(latitude)
+                || !(-180.0..=180.0).contains(longitude)
+                || !radius_km.is_finite()
+                || *radius_km <= 0.0
+            {
+                return Err("位置规则的坐标或半径无效".into());
+            }
+            // SQLite 默认没有三角函数，使用等距矩形投影近似计算距离，
+            // 先按经纬度范围筛选以便利用索引，再精确比较距离；经度差按跨越 ±180° 的较短方向计算
+            let lat_delta = radius_km / KM_PER_DEGREE;
+            let lon_scale = latitude.to_radians().cos().max(0.01);
+            let lon_delta = lat_delta / lon_scale;
+            values.extend([
+                Value::Real(latitude - lat_delta),
+                Value::Real(latitude + lat_delta),
+            ]);
+            let (west, east) = (longitude - lon_delta, longitude + lon_delta);
+            let lon_filter = if lon_delta >= 180.0 {
+                "1"
+            } else if west < -180.0 || east > 180.0 {
+                let (west, east) = if west < -180.0 {
+                    (west + 360.0, east)
+                } else {
+                    (west, east - 360.0)
+                };
+                values.extend([Value::Real(west), Value::Real(east)]);
+                "(media_records.longitude >= ? OR media_records.longitude <= ?)"
+            } else {
+                values.extend([Value::Real(west), Value::Real(east)]);
+                "media_records.longitude BETWEEN ? AND ?"
+            };
+            values.extend([
+                Value::Real(*latitude),
+                Value::Real(*latitude),
+                Value::Real(*longitude),
+                Value::Real(*longitude),
+                Value::Real(*longitude),
+                Value::Real(*longitude),
+                Value::Real(lon_scale * lon_scale),
+                Value::Real(lat_delta * lat_delta),
+            ]);
+            let lon_diff = "min(abs(media_records.longitude - ?), \
+                            360 - abs(media_records.longitude - ?))";
+            format!(
+                "(media_records.latitude BETWEEN ? AND ? AND {} \
+                 AND (media_records.latitude - ?) * (media_records.latitude - ?) \
+                   + {} * {} * ? <= ?)",
+                lon_filter, lon_diff, lon_diff
+            )
+        }
+        SmartRule::HasLocation => {
+            "(media_records.latitude IS NOT NULL AND media_records.longitude IS NOT NULL)"
+                .to_string()
+        }
+        SmartRule::Place { value } => {
+            let pattern = contains_pattern(value)?;
+            values.extend([
+                Value::Text(pattern.clone()),
+                Value::Text(pattern.clone()),
+                Value::Text(pattern),
+            ]);
+            "(media_records.country LIKE ? ESCAPE '\\' \
+             OR media_records.region LIKE ? ESCAPE '\\' \
+             OR media_records.city LIKE ? ESCAPE '\\')"
+                .to_string()
+        }
+        SmartRule::NotInAnyAlbum => "NOT EXISTS (SELECT 1 FROM album_media
+             WHERE album_media.media_id = media_records.id)"
+            .to_string(),
+        SmartRule::InAlbum { album_id } => {
+            values.push(Value::Text(album_id.clone()));
+            "media_records.id IN (SELECT media_id FROM album_media WHERE album_id = ?)".to_string()
+        }
+        SmartRule::AlbumName { name } => {
+            let name = name.trim();
+            if name.is_empty() {
+                return Err("相册规则缺少相册名称".into());
+            }
+            values.push(Value::Text(name.to_string()));
+            "media_records.id IN (SELECT album_media.media_id FROM album_media
+             JOIN albums ON albums.id = album_media.album_id
+             WHERE albums.name = ? COLLATE NOCASE)"
+                .to_string()
+        }
+        SmartRule::Folder { value } => {
+            values.push(Value::Text(contains_pattern(value)?));
+            "media_records.path LIKE ? ESCAPE '\\'".to_string()
+        }
+        SmartRule::Compare { field, op, value } => {
+            if !value.is_finite() {
+                return Err("比较规则的数值无效".into());
+            }
+            let (column, is_real) = match field {
+                NumericField::Iso => ("media_records.iso", false),
+                NumericField::Aperture => ("media_records.aperture", true),
+                NumericField::FocalLength => ("media_records.focal_length", true),
+                NumericField::Rating => ("media_records.rating", false),
+                NumericField::Size => ("media_records.size", false),
+                NumericField::Width => ("media_records.width", false),
+                NumericField::Height => ("media_records.height", false),
+            };
+            values.push(Value::Real(*value));
+            match op {
+                // 光圈、焦距等小数按近似值比较
+                CompareOp::Eq if is_real => format!("ABS({} - ?) < 0.05", column),
+                CompareOp::Eq => format!("{} = ?", column),
+                CompareOp::Gt => format!("{} > ?", column),
+                CompareOp::Gte => format!("{} >= ?", column),
+                CompareOp::Lt => format!("{} < ?", column),
+                CompareOp::Lte => format!("{} <= ?", column),
+            }
+        }
+        SmartRule::Text { query } => {
+            let match_query = build_match_query(query).ok_or("全文搜索规则缺少搜索内容")?;
+            values.push(Value::Text(match_query));
+            "media_records.id IN (SELECT media_id FROM media_search WHERE media_search MATCH ?)"
+                .to_string()
+        }
+        SmartRule::NameContains { value } => {
+            values.push(Value::Text(contains_pattern(value)?));
+            "media_records.name LIKE ? ESCAPE '\\'".to_string()
+        }
+    };
+
+    Ok(condition)
+}
+
+fn join_rules(
+    rules: &[SmartRule],
+    separator: &str,
+    empty: &str,
+    values: &mut Vec<Value>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    if rules.is_empty() {
+        return Ok(empty.to_string());
+    }
+    let parts = rules
+        .iter()
+        .map(|rule| compile_rule(rule, values).map(|c| format!("({})", c)))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(parts.join(separator))
+}
+
+/// 生成 LIKE 的“包含”匹配模式，空文本视为无效规则
+fn contains_pattern(value: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err("规则的匹配文本不能为空".into());
+    }
+    Ok(format!("%{}%", escape_like(value)))
+}
+
+/// 转义 LIKE 中的通配符
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
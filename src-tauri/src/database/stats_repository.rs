@@ -0,0 +1,194 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use chrono::Utc;
+use log::{info, warn};
+use rusqlite::{params, Connection, OptionalExtension};
+use tauri::AppHandle;
+
+use crate::database::open_connection;
+use crate::models::stats::{LibraryStats, StatGroup};
+use crate::utils::image_processor::get_thumbnail_dir;
+
+/// 文件所在文件夹（含结尾分隔符），SQLite 没有路径函数，去掉最后一个分隔符之后的部分
+const FOLDER_EXPR: &str = "rtrim(path, replace(replace(path, '/', ''), '\\', ''))";
+
+/// 相机名称，型号已包含厂商名时（如 "Canon" / "Canon EOS R5"）只使用型号
+const CAMERA_EXPR: &str = "NULLIF(TRIM(CASE
+         WHEN camera_make IS NOT NULL AND camera_model LIKE camera_make || '%' THEN camera_model
+         ELSE COALESCE(camera_make, '') || ' ' || COALESCE(camera_model, '')
+     END), '')";
+
+/// SQLite 统计仓库实现
+pub struct StatsRepository {
+    app: AppHandle,
+}
+
+impl StatsRepository {
+    pub fn new(app: AppHandle) -> Self {
+        Self { app }
+    }
+
+    /// 获取数据库连接
+    fn get_connection(&self) -> Result<Connection, Box<dyn std::error::Error>> {
+        open_connection(&self.app)
+    }
+
+    /// 获取媒体库统计，优先使用缓存；`refresh` 为 true 时重新统计
+    ///
+    /// 缓存在媒体目录变化时由触发器清除。丢失和离线文件数量不随目录变化，需要时应强制刷新。
+    pub async fn find_stats(
+        &self,
+        refresh: bool,
+    ) -> Result<LibraryStats, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+
+        if !refresh {
+            let cached: Option<String> = conn
+                .query_row(
+                    "SELECT stats FROM library_stats_cache WHERE id = 1 AND stats IS NOT NULL",
+                    [],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            if let Some(stats) = cached.and_then(|json| serde_json::from_str(&json).ok()) {
+                return Ok(stats);
+            }
+        }
+
+        // 先写入占位行，统计期间媒体目录发生变化时触发器会删除该行，过期的结果不会被缓存
+        let now = Utc::now();
+        conn.execute(
+            "INSERT OR REPLACE INTO library_stats_cache (id, stats, created_at)
+             VALUES (1, NULL, ?1)",
+            params![now.to_rfc3339()],
+        )?;
+
+        let (total_count, total_bytes) = conn.query_row(
+            "SELECT COUNT(*), COALESCE(SUM(size), 0) FROM media_records WHERE trashed_at IS NULL",
+            [],
+            |row| Ok((row.get::<_, i64>(0)? as u64, row.get::<_, i64>(1)? as u64)),
+        )?;
+        let (trashed_count, trashed_bytes) = conn.query_row(
+            "SELECT COUNT(*), COALESCE(SUM(size), 0) FROM media_records
+             WHERE trashed_at IS NOT NULL",
+            [],
+            |row| Ok((row.get::<_, i64>(0)? as u64, row.get::<_, i64>(1)? as u64)),
+        )?;
+        let (missing_count, offline_count) = count_unavailable(&conn)?;
+
+        let stats = LibraryStats {
+            total_count,
+            total_bytes,
+            by_kind: query_groups(&conn, "kind", "COUNT(*) DESC")?,
+            by_format: query_groups(&conn, "mime_type", "COUNT(*) DESC")?,
+            by_year: query_groups(
+                &conn,
+                "strftime('%Y', COALESCE(taken_date, created_at), 'localtime')",
+                "key DESC",
+            )?,
+            by_camera: query_groups(&conn, CAMERA_EXPR, "COUNT(*) DESC")?,
+            by_folder: query_groups(&conn, FOLDER_EXPR, "COUNT(*) DESC")?
+                .into_iter()
+                .map(|group| StatGroup {
+                    key: group.key.map(|folder| trim_separator(&folder)),
+                    ..group
+                })
+                .collect(),
+            thumbnail_bytes: directory_size(&get_thumbnail_dir(&self.app)),
+            missing_count,
+            offline_count,
+            trashed_count,
+            trashed_bytes,
+            generated_at: now,
+        };
+
+        let stored = conn.execute(
+            "UPDATE library_stats_cache SET stats = ?1 WHERE id = 1 AND stats IS NULL",
+            params![serde_json::to_string(&stats)?],
+        )?;
+        if stored == 0 {
+            warn!("统计期间媒体库发生变化，本次统计结果不缓存");
+        }
+
+        info!(
+            "媒体库统计完成：{} 个文件，{} 字节",
+            stats.total_count, stats.total_bytes
+        );
+        Ok(stats)
+    }
+}
+
+/// 按表达式分组统计数量和大小（不含回收站），分组键统一转换为文本
+fn query_groups(conn: &Connection, key: &str, order: &str) -> rusqlite::Result<Vec<StatGroup>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT CAST({} AS TEXT) AS key, COUNT(*), COALESCE(SUM(size), 0)
+         FROM media_records WHERE trashed_at IS NULL
+         GROUP BY key ORDER BY {}, key",
+        key, order
+    ))?;
+    let groups = stmt
+        .query_map([], |row| {
+            Ok(StatGroup {
+                key: row.get(0)?,
+                count: row.get::<_, i64>(1)? as u64,
+                bytes: row.get::<_, i64>(2)? as u64,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(groups)
+}
+
+/// 统计 (丢失, 离线) 的文件数量：文件夹不存在视为离线，文件夹存在但文件不存在视为丢失
+fn count_unavailable(conn: &Connection) -> rusqlite::Result<(u64, u64)> {
+    let mut stmt = conn.prepare("SELECT path FROM media_records WHERE trashed_at IS NULL")?;
+    let paths = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut folders: HashMap<&Path, bool> = HashMap::new();
+    let (mut missing, mut offline) = (0, 0);
+    for path in &paths {
+        let path = Path::new(path);
+        let folder_exists = match path.parent() {
+            Some(folder) => *folders.entry(folder).or_insert_with(|| folder.is_dir()),
+            None => false,
+        };
+        if !folder_exists {
+            offline += 1;
+        } else if !path.exists() {
+            missing += 1;
+        }
+    }
+
+    Ok((missing, offline))
+}
+
+/// 目录下所有文件的总大小，目录不存在时为 0
+fn directory_size(dir: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            if metadata.is_dir() {
+                Some(directory_size(&entry.path()))
+            } else {
+                Some(metadata.len())
+            }
+        })
+        .sum()
+}
+
+/// 去掉文件夹结尾的分隔符，根目录保持不变
+fn trim_separator(folder: &str) -> String {
+    let trimmed = folder.trim_end_matches(['/', '\\']);
+    if trimmed.is_empty() || trimmed.ends_with(':') {
+        folder.to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
@@ -0,0 +1,417 @@
+use chrono::Utc;
+use log::info;
+use rusqlite::{params, Connection, OptionalExtension};
+use tauri::AppHandle;
+use uuid::Uuid;
+
+use crate::database::journal_repository::{record_journal, JournalChange};
+use crate::database::{open_connection, parse_time_column, parse_uuid_column};
+use crate::models::tag::{TagRecord, TagWithCount};
+
+/// 层级标签路径的分隔符
+pub const TAG_PATH_SEPARATOR: char = '/';
+
+/// SQLite 标签仓库实现
+pub struct TagRepository {
+    app: AppHandle,
+}
+
+impl TagRepository {
+    pub fn new(app: AppHandle) -> Self {
+        Self { app }
+    }
+
+    /// 获取数据库连接
+    fn get_connection(&self) -> Result<Connection, Box<dyn std::error::Error>> {
+        open_connection(&self.app)
+    }
+
+    /// 获取所有标签及其媒体数量，按路径排序
+    pub async fn find_all_with_counts(
+        &self,
+    ) -> Result<Vec<TagWithCount>, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+
+        // total_count 统计自身及所有子标签下的媒体（去重），均不含回收站中的媒体
+        let mut stmt = conn.prepare(
+            "SELECT t.id, t.name, t.parent_id, t.path, t.created_at, t.updated_at,
+                    (SELECT COUNT(*) FROM media_tags mt
+                     JOIN media_records m ON m.id = mt.media_id
+                     WHERE mt.tag_id = t.id AND m.trashed_at IS NULL),
+                    (SELECT COUNT(DISTINCT mt.media_id) FROM media_tags mt
+                     JOIN media_records m ON m.id = mt.media_id
+                     JOIN tags d ON d.id = mt.tag_id
+                     WHERE (d.id = t.id
+                            OR substr(d.path, 1, length(t.path) + 1) = t.path || '/')
+                       AND m.trashed_at IS NULL)
+             FROM tags t ORDER BY t.path",
+        )?;
+
+        let tags = stmt
+            .query_map([], |row| {
+                Ok(TagWithCount {
+                    tag: map_tag_row(row)?,
+                    media_count: row.get::<_, i64>(6)? as u64,
+                    total_count: row.get::<_, i64>(7)? as u64,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(tags)
+    }
+
+    /// 按层级路径创建标签，缺失的上级标签会一并创建；已存在时直接返回
+    pub async fn create(&self, path: &str) -> Result<TagRecord, Box<dyn std::error::Error>> {
+        let path = normalize_tag_path(path).ok_or("标签名称不能为空")?;
+
+        let conn = self.get_connection()?;
+        let tx = conn.unchecked_transaction()?;
+
+        let tag = ensure_tag_path(&tx, &path)?;
+        tx.commit()?;
+
+        info!("已创建标签: {}", tag.path);
+        Ok(tag)
+    }
+
+    /// 重命名标签，子标签的路径同步更新
+    pub async fn rename(
+        &self,
+        tag_id: &str,
+        name: &str,
+    ) -> Result<TagRecord, Box<dyn std::error::Error>> {
+        let name = name.trim();
+        if name.is_empty() || name.contains(TAG_PATH_SEPARATOR) {
+            return Err(format!("标签名称无效: {}", name).into());
+        }
+
+        let conn = self.get_connection()?;
+        let tx = conn.unchecked_transaction()?;
+
+        let tag = find_tag(&tx, tag_id)?.ok_or_else(|| format!("未找到标签: {}", tag_id))?;
+        let new_path = match tag.parent_id {
+            Some(parent_id) => {
+                let parent = find_tag(&tx, &parent_id.to_string())?
+                    .ok_or_else(|| format!("未找到上级标签: {}", parent_id))?;
+                format!("{}{}{}", parent.path, TAG_PATH_SEPARATOR, name)
+            }
+            None => name.to_string(),
+        };
+
+        if let Some(existing) = find_tag_by_path(&tx, &new_path)? {
+            if existing.id != tag.id {
+                return Err(format!("标签 {} 已存在，请使用合并", existing.path).into());
+            }
+        }
+
+        let now = Utc::now().to_rfc3339();
+        tx.execute(
+            "UPDATE tags SET name = ?1, updated_at = ?2 WHERE id = ?3",
+            params![name, now, tag_id],
+        )?;
+        move_tag_subtree(&tx, &tag.path, &new_path, &now)?;
+
+        let renamed = find_tag(&tx, tag_id)?.ok_or_else(|| format!("未找到标签: {}", tag_id))?;
+        tx.commit()?;
+
+        info!("已重命名标签: {} -> {}", tag.path, renamed.path);
+        Ok(renamed)
+    }
+
+    /// 将源标签合并到目标标签，子标签按名称递归合并，源标签随后删除
+    pub async fn merge(
+        &self,
+        source_id: &str,
+        target_id: &str,
+    ) -> Result<TagRecord, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+        let tx = conn.unchecked_transaction()?;
+
+        let source =
+            find_tag(&tx, source_id)?.ok_or_else(|| format!("未找到标签: {}", source_id))?;
+        let target =
+            find_tag(&tx, target_id)?.ok_or_else(|| format!("未找到标签: {}", target_id))?;
+
+        if source.id == target.id || is_descendant_path(&target.path, &source.path) {
+            return Err(format!("不能将标签 {} 合并到 {}", source.path, target.path).into());
+        }
+
+        merge_into(&tx, &source, &target)?;
+
+        let merged =
+            find_tag(&tx, target_id)?.ok_or_else(|| format!("未找到标签: {}", target_id))?;
+        tx.commit()?;
+
+        info!("已合并标签: {} -> {}", source.path, merged.path);
+        Ok(merged)
+    }
+
+    /// 删除标签及其所有子标签，返回删除的标签数量
+    pub async fn delete(&self, tag_id: &str) -> Result<usize, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+
+        let tag = find_tag(&conn, tag_id)?.ok_or_else(|| format!("未找到标签: {}", tag_id))?;
+        let subtree_count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM tags WHERE id = ?1 OR substr(path, 1, length(?2) + 1) = ?2 || '/'",
+            params![tag_id, tag.path],
+            |row| row.get(0),
+        )?;
+
+        // 子标签和媒体关联通过外键级联删除
+        conn.execute("DELETE FROM tags WHERE id = ?1", params![tag_id])?;
+
+        info!(
+            "已删除标签 {} 及其 {} 个子标签",
+            tag.path,
+            subtree_count - 1
+        );
+        Ok(subtree_count as usize)
+    }
+
+    /// 为选中的媒体批量添加标签，返回新增的关联数量
+    pub async fn add_to_media(
+        &self,
+        media_ids: &[String],
+        tag_ids: &[String],
+    ) -> Result<usize, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+        let tx = conn.unchecked_transaction()?;
+        let now = Utc::now().to_rfc3339();
+
+        let mut changes = Vec::new();
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR IGNORE INTO media_tags (media_id, tag_id, created_at)
+                 SELECT m.id, t.id, ?3 FROM media_records m, tags t WHERE m.id = ?1 AND t.id = ?2",
+            )?;
+            for media_id in media_ids {
+                for tag_id in tag_ids {
+                    if stmt.execute(params![media_id, tag_id, now])? > 0 {
+                        changes.push(JournalChange::MediaTag {
+                            media_id: media_id.clone(),
+                            tag_id: tag_id.clone(),
+                            before: false,
+                            after: true,
+                        });
+                    }
+                }
+            }
+        }
+        record_journal(&tx, "添加标签", &changes)?;
+        tx.commit()?;
+
+        let added = changes.len();
+
+        info!(
+            "已为 {} 个媒体添加 {} 个标签，新增 {} 条关联",
+            media_ids.len(),
+            tag_ids.len(),
+            added
+        );
+        Ok(added)
+    }
+
+    /// 从选中的媒体批量移除标签，返回移除的关联数量
+    pub async fn remove_from_media(
+        &self,
+        media_ids: &[String],
+        tag_ids: &[String],
+    ) -> Result<usize, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+        let tx = conn.unchecked_transaction()?;
+
+        let mut changes = Vec::new();
+        {
+            let mut stmt =
+                tx.prepare("DELETE FROM media_tags WHERE media_id = ?1 AND tag_id = ?2")?;
+            for media_id in media_ids {
+                for tag_id in tag_ids {
+                    if stmt.execute(params![media_id, tag_id])? > 0 {
+                        changes.push(JournalChange::MediaTag {
+                            media_id: media_id.clone(),
+                            tag_id: tag_id.clone(),
+                            before: true,
+                            after: false,
+                        });
+                    }
+                }
+            }
+        }
+        record_journal(&tx, "移除标签", &changes)?;
+        tx.commit()?;
+
+        let removed = changes.len();
+
+        info!(
+            "已从 {} 个媒体移除 {} 个标签，删除 {} 条关联",
+            media_ids.len(),
+            tag_ids.len(),
+            removed
+        );
+        Ok(removed)
+    }
+}
+
+/// 规范化层级标签路径：去除每一级首尾空白并忽略空层级
+pub fn normalize_tag_path(path: &str) -> Option<String> {
+    let segments: Vec<&str> = path
+        .split(TAG_PATH_SEPARATOR)
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    (!segments.is_empty()).then(|| segments.join(&TAG_PATH_SEPARATOR.to_string()))
+}
+
+/// 确保层级路径上的每一级标签都存在，返回最末级标签
+///
+/// `path` 需已经过 `normalize_tag_path` 规范化。
+pub(crate) fn ensure_tag_path(conn: &Connection, path: &str) -> rusqlite::Result<TagRecord> {
+    let mut parent: Option<TagRecord> = None;
+    let mut current_path = String::new();
+    for segment in path.split(TAG_PATH_SEPARATOR) {
+        if !current_path.is_empty() {
+            current_path.push(TAG_PATH_SEPARATOR);
+        }
+        current_path.push_str(segment);
+
+        let tag = match find_tag_by_path(conn, &current_path)? {
+            Some(tag) => tag,
+            None => {
+                let now = Utc::now();
+                let tag = TagRecord {
+                    id: Uuid::new_v4(),
+                    name: segment.to_string(),
+                    parent_id: parent.as_ref().map(|p| p.id),
+                    path: current_path.clone(),
+                    created_at: now,
+                    updated_at: now,
+                };
+                conn.execute(
+                    "INSERT INTO tags (id, name, parent_id, path, created_at, updated_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![
+                        tag.id.to_string(),
+                        tag.name,
+                        tag.parent_id.map(|id| id.to_string()),
+                        tag.path,
+                        tag.created_at.to_rfc3339(),
+                        tag.updated_at.to_rfc3339(),
+                    ],
+                )?;
+                tag
+            }
+        };
+        // 已存在的标签以数据库中的大小写为准
+        current_path = tag.path.clone();
+        parent = Some(tag);
+    }
+
+    parent.ok_or(rusqlite::Error::QueryReturnedNoRows)
+}
+
+/// 递归地将源标签并入目标标签
+fn merge_into(conn: &Connection, source: &TagRecord, target: &TagRecord) -> rusqlite::Result<()> {
+    let now = Utc::now().to_rfc3339();
+
+    conn.execute(
+        "INSERT OR IGNORE INTO media_tags (media_id, tag_id, created_at)
+         SELECT media_id, ?2, created_at FROM media_tags WHERE tag_id = ?1",
+        params![source.id.to_string(), target.id.to_string()],
+    )?;
+
+    for child in find_children(conn, &source.id.to_string())? {
+        let target_child_path = format!("{}{}{}", target.path, TAG_PATH_SEPARATOR, child.name);
+        match find_tag_by_path(conn, &target_child_path)? {
+            Some(existing) => merge_into(conn, &child, &existing)?,
+            None => {
+                conn.execute(
+                    "UPDATE tags SET parent_id = ?1, updated_at = ?2 WHERE id = ?3",
+                    params![target.id.to_string(), now, child.id.to_string()],
+                )?;
+                move_tag_subtree(conn, &child.path, &target_child_path, &now)?;
+            }
+        }
+    }
+
+    conn.execute(
+        "DELETE FROM tags WHERE id = ?1",
+        params![source.id.to_string()],
+    )?;
+    conn.execute(
+        "UPDATE tags SET updated_at = ?1 WHERE id = ?2",
+        params![now, target.id.to_string()],
+    )?;
+
+    Ok(())
+}
+
+/// 将某个标签及其子标签的路径前缀从 old_path 替换为 new_path
+fn move_tag_subtree(
+    conn: &Connection,
+    old_path: &str,
+    new_path: &str,
+    now: &str,
+) -> rusqlite::Result<usize> {
+    conn.execute(
+        "UPDATE tags SET path = ?2 || substr(path, length(?1) + 1), updated_at = ?3
+         WHERE path = ?1 OR substr(path, 1, length(?1) + 1) = ?1 || '/'",
+        params![old_path, new_path, now],
+    )
+}
+
+/// 判断 path 是否为 ancestor 的子孙路径
+fn is_descendant_path(path: &str, ancestor: &str) -> bool {
+    match (path.get(..ancestor.len()), path.get(ancestor.len()..)) {
+        (Some(prefix), Some(rest)) => {
+            prefix.eq_ignore_ascii_case(ancestor) && rest.starts_with(TAG_PATH_SEPARATOR)
+        }
+        _ => false,
+    }
+}
+
+const TAG_COLUMNS: &str = "id, name, parent_id, path, created_at, updated_at";
+
+fn find_tag(conn: &Connection, id: &str) -> rusqlite::Result<Option<TagRecord>> {
+    conn.query_row(
+        &format!("SELECT {} FROM tags WHERE id = ?1", TAG_COLUMNS),
+        params![id],
+        map_tag_row,
+    )
+    .optional()
+}
+
+fn find_tag_by_path(conn: &Connection, path: &str) -> rusqlite::Result<Option<TagRecord>> {
+    conn.query_row(
+        &format!("SELECT {} FROM tags WHERE path = ?1", TAG_COLUMNS),
+        params![path],
+        map_tag_row,
+    )
+    .optional()
+}
+
+fn find_children(conn: &Connection, parent_id: &str) -> rusqlite::Result<Vec<TagRecord>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM tags WHERE parent_id = ?1",
+        TAG_COLUMNS
+    ))?;
+    let children = stmt
+        .query_map(params![parent_id], map_tag_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(children)
+}
+
+/// 将一行查询结果解析为标签记录，列顺序与 `TAG_COLUMNS` 一致
+fn map_tag_row(row: &rusqlite::Row) -> rusqlite::Result<TagRecord> {
+    Ok(TagRecord {
+        id: parse_uuid_column(0, row.get(0)?)?,
+        name: row.get(1)?,
+        parent_id: row
+            .get::<_, Option<String>>(2)?
+            .map(|id| parse_uuid_column(2, id))
+            .transpose()?,
+        path: row.get(3)?,
+        created_at: parse_time_column(4, row.get(4)?)?,
+        updated_at: parse_time_column(5, row.get(5)?)?,
+    })
+}
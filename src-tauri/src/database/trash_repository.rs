@@ -0,0 +1,421 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::{Duration, Utc};
+use log::{error, info, warn};
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use tauri::{path::BaseDirectory, AppHandle, Manager};
+
+use crate::database::journal_repository::{record_journal, JournalChange};
+use crate::database::media_repository::{map_media_row, MEDIA_COLUMNS, MEDIA_COLUMN_COUNT};
+use crate::database::{open_connection, parse_time_column, read_setting, write_setting};
+use crate::models::trash::{TrashFileAction, TrashedMedia};
+use crate::utils::file_ops::move_file;
+
+/// 回收站保留天数的设置项
+const RETENTION_SETTING: &str = "trash.retention_days";
+
+/// 默认保留天数
+const DEFAULT_RETENTION_DAYS: u32 = 30;
+
+/// 应用回收站目录（相对应用数据目录）
+const TRASH_DIR: &str = "trash";
+
+/// 单个媒体移入或移出回收站的结果
+pub(crate) struct TrashOutcome {
+    /// 原文件的处理方式
+    pub action: TrashFileAction,
+    /// 在原位置和应用回收站之间移动过的文件（从，到），后续操作失败时可以移回
+    pub moved: Option<(PathBuf, PathBuf)>,
+}
+
+/// 待彻底删除的媒体
+struct PurgeRow {
+    id: String,
+    action: Option<TrashFileAction>,
+    trash_path: Option<String>,
+    thumbnail_path: Option<String>,
+}
+
+/// SQLite 回收站仓库实现
+pub struct TrashRepository {
+    app: AppHandle,
+}
+
+impl TrashRepository {
+    pub fn new(app: AppHandle) -> Self {
+        Self { app }
+    }
+
+    /// 获取数据库连接
+    fn get_connection(&self) -> Result<Connection, Box<dyn std::error::Error>> {
+        open_connection(&self.app)
+    }
+
+    /// 将媒体移入回收站并记录到操作日志，返回成功移入的数量
+    ///
+    /// 原文件按 `action` 处理，文件已不存在时只移除记录；文件移动失败的媒体保持不变。
+    /// 每处理一个媒体调用一次 `on_progress`，参数为已处理的数量。
+    pub async fn trash(
+        &self,
+        media_ids: &[String],
+        action: TrashFileAction,
+        on_progress: impl Fn(usize),
+    ) -> Result<usize, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+        let trash_dir = trash_dir(&self.app)?;
+        let mut changes = Vec::new();
+
+        for (index, media_id) in media_ids.iter().enumerate() {
+            match trash_media(&conn, media_id, action, &trash_dir) {
+                Ok(Some(outcome)) => changes.push(JournalChange::MediaTrash {
+                    media_id: media_id.clone(),
+                    before: None,
+                    after: Some(outcome.action),
+                }),
+                Ok(None) => warn!("未找到媒体或已在回收站中: {}", media_id),
+                Err(e) => error!("移入回收站失败 {}: {}", media_id, e),
+            }
+            on_progress(index + 1);
+        }
+        record_journal(&conn, "删除", &changes)?;
+
+        info!("已将 {} 个媒体移入回收站", changes.len());
+        Ok(changes.len())
+    }
+
+    /// 将所有不在回收站中的媒体移入回收站
+    pub async fn trash_all(
+        &self,
+        action: TrashFileAction,
+    ) -> Result<usize, Box<dyn std::error::Error>> {
+        let media_ids = {
+            let conn = self.get_connection()?;
+            let mut stmt = conn.prepare("SELECT id FROM media_records WHERE trashed_at IS NULL")?;
+            let media_ids = stmt
+                .query_map([], |row| row.get::<_, String>(0))?
+                .collect::<Result<Vec<_>, _>>()?;
+            media_ids
+        };
+
+        self.trash(&media_ids, action, |_| {}).await
+    }
+
+    /// 获取回收站中的媒体，最近删除的在前
+    pub async fn find_trashed(&self) -> Result<Vec<TrashedMedia>, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+        let retention_days = retention_days(&conn)?;
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {}, media_records.trashed_at, media_records.trash_action
+             FROM media_records WHERE media_records.trashed_at IS NOT NULL
+             ORDER BY media_records.trashed_at DESC",
+            MEDIA_COLUMNS
+        ))?;
+        let media = stmt
+            .query_map([], |row| {
+                let trashed_at =
+                    parse_time_column(MEDIA_COLUMN_COUNT, row.get(MEDIA_COLUMN_COUNT)?)?;
+                Ok(TrashedMedia {
+                    record: map_media_row(row)?,
+                    trashed_at,
+                    file_action: row
+                        .get::<_, Option<String>>(MEDIA_COLUMN_COUNT + 1)?
+                        .and_then(|action| TrashFileAction::parse(&action))
+                        .unwrap_or_default(),
+                    expires_at: (retention_days > 0)
+                        .then(|| trashed_at + Duration::days(retention_days as i64)),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(media)
+    }
+
+    /// 从回收站恢复媒体并记录到操作日志，返回恢复的数量
+    ///
+    /// 移到应用回收站目录的文件移回原位置，原位置已有文件时该媒体保持在回收站中；
+    /// 系统回收站中的文件需用户先在系统中还原，否则该媒体保持在回收站中。
+    pub async fn restore(&self, media_ids: &[String]) -> Result<usize, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+        let mut changes = Vec::new();
+
+        for media_id in media_ids {
+            match restore_trashed_media(&conn, media_id) {
+                Ok(Some(outcome)) => changes.push(JournalChange::MediaTrash {
+                    media_id: media_id.clone(),
+                    before: Some(outcome.action),
+                    after: None,
+                }),
+                Ok(None) => warn!("回收站中未找到媒体: {}", media_id),
+                Err(e) => error!("恢复媒体失败 {}: {}", media_id, e),
+            }
+        }
+        record_journal(&conn, "从回收站恢复", &changes)?;
+
+        info!("已从回收站恢复 {} 个媒体", changes.len());
+        Ok(changes.len())
+    }
+
+    /// 彻底删除回收站中的媒体，`media_ids` 为空时清空回收站，返回删除的数量
+    pub async fn purge(
+        &self,
+        media_ids: Option<&[String]>,
+    ) -> Result<usize, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+
+        let (condition, values) = match media_ids {
+            Some([]) => return Ok(0),
+            Some(ids) => (
+                format!("AND id IN ({})", vec!["?"; ids.len()].join(", ")),
+                ids.to_vec(),
+            ),
+            None => (String::new(), Vec::new()),
+        };
+        let rows = find_purge_rows(&conn, &condition, values)?;
+
+        Ok(purge_rows(&conn, rows)?)
+    }
+
+    /// 彻底删除超过保留期的媒体，保留天数为 0 时不自动删除
+    pub async fn purge_expired(&self) -> Result<usize, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+        let retention_days = retention_days(&conn)?;
+        if retention_days == 0 {
+            return Ok(0);
+        }
+
+        let cutoff = Utc::now() - Duration::days(retention_days as i64);
+        let rows = find_purge_rows(&conn, "AND trashed_at < ?", vec![cutoff.to_rfc3339()])?;
+
+        let purged = purge_rows(&conn, rows)?;
+        if purged > 0 {
+            info!("已彻底删除 {} 个超过保留期的媒体", purged);
+        }
+        Ok(purged)
+    }
+
+    /// 获取回收站保留天数，0 表示不自动删除
+    pub async fn retention_days(&self) -> Result<u32, Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+
+        Ok(retention_days(&conn)?)
+    }
+
+    /// 设置回收站保留天数，0 表示不自动删除
+    pub async fn set_retention_days(&self, days: u32) -> Result<(), Box<dyn std::error::Error>> {
+        let conn = self.get_connection()?;
+        write_setting(&conn, RETENTION_SETTING, &days.to_string())?;
+
+        info!("回收站保留天数已设置为 {}", days);
+        Ok(())
+    }
+}
+
+fn retention_days(conn: &Connection) -> rusqlite::Result<u32> {
+    Ok(read_setting(conn, RETENTION_SETTING)?
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_RETENTION_DAYS))
+}
+
+/// 应用回收站目录
+pub(crate) fn trash_dir(app: &AppHandle) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    Ok(app.path().resolve(TRASH_DIR, BaseDirectory::AppLocalData)?)
+}
+
+/// 将单个媒体移入回收站，返回原文件实际的处理方式，媒体不存在或已在回收站中时返回 None
+pub(crate) fn trash_media(
+    conn: &Connection,
+    media_id: &str,
+    action: TrashFileAction,
+    trash_dir: &Path,
+) -> Result<Option<TrashOutcome>, Box<dyn std::error::Error>> {
+    let path: Option<String> = conn
+        .query_row(
+            "SELECT path FROM media_records WHERE id = ?1 AND trashed_at IS NULL",
+            params![media_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    let Some(path) = path else {
+        return Ok(None);
+    };
+
+    // 先更新记录再移动文件：更新失败时文件保持原样，移动失败时把记录改回
+    let (applied, trash_path) = trash_target(Path::new(&path), media_id, action, trash_dir);
+    conn.execute(
+        "UPDATE media_records SET trashed_at = ?1, trash_action = ?2, trash_path = ?3
+         WHERE id = ?4",
+        params![
+            Utc::now().to_rfc3339(),
+            applied.as_str(),
+            trash_path.as_ref().map(|path| path.to_string_lossy()),
+            media_id
+        ],
+    )?;
+
+    if let Err(e) = move_to_trash(Path::new(&path), applied, trash_path.as_deref()) {
+        if let Err(undo) = conn.execute(
+            "UPDATE media_records SET trashed_at = NULL, trash_action = NULL, trash_path = NULL
+             WHERE id = ?1",
+            params![media_id],
+        ) {
+            error!("移动文件失败后恢复媒体 {} 的记录失败: {}", media_id, undo);
+        }
+        return Err(format!("移动文件到回收站失败 {}: {}", path, e).into());
+    }
+    Ok(Some(TrashOutcome {
+        action: applied,
+        moved: trash_path.map(|trash_path| (PathBuf::from(path), trash_path)),
+    }))
+}
+
+/// 从回收站恢复单个媒体，返回移入时原文件的处理方式，媒体不在回收站中时返回 None
+pub(crate) fn restore_trashed_media(
+    conn: &Connection,
+    media_id: &str,
+) -> Result<Option<TrashOutcome>, Box<dyn std::error::Error>> {
+    let row: Option<(String, Option<String>, Option<String>)> = conn
+        .query_row(
+            "SELECT path, trash_action, trash_path FROM media_records
+             WHERE id = ?1 AND trashed_at IS NOT NULL",
+            params![media_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .optional()?;
+    let Some((path, action, trash_path)) = row else {
+        return Ok(None);
+    };
+
+    let action = action
+        .as_deref()
+        .and_then(TrashFileAction::parse)
+        .unwrap_or_default();
+    let moved = match (action, trash_path) {
+        (TrashFileAction::Managed, Some(trash_path)) => {
+            move_file(Path::new(&trash_path), Path::new(&path))
+                .map_err(|e| format!("恢复文件失败 {}: {}", path, e))?;
+            Some(trash_path)
+        }
+        (TrashFileAction::System, _) if !Path::new(&path).exists() => {
+            return Err(format!("原文件在系统回收站中，请先在系统回收站中还原: {}", path).into());
+        }
+        _ => None,
+    };
+
+    if let Err(e) = conn.execute(
+        "UPDATE media_records SET trashed_at = NULL, trash_action = NULL, trash_path = NULL
+         WHERE id = ?1",
+        params![media_id],
+    ) {
+        if let Some(trash_path) = &moved {
+            if let Err(undo) = move_file(Path::new(&path), Path::new(trash_path)) {
+                error!("更新记录失败后移回回收站文件失败 {}: {}", path, undo);
+            }
+        }
+        return Err(e.into());
+    }
+    Ok(Some(TrashOutcome {
+        action,
+        moved: moved.map(|trash_path| (PathBuf::from(trash_path), PathBuf::from(path))),
+    }))
+}
+
+/// 按 `action` 确定原文件实际的处理方式和在应用回收站中的路径，文件已不存在时不处理
+fn trash_target(
+    path: &Path,
+    media_id: &str,
+    action: TrashFileAction,
+    trash_dir: &Path,
+) -> (TrashFileAction, Option<PathBuf>) {
+    if !path.exists() {
+        return (TrashFileAction::Keep, None);
+    }
+
+    match action {
+        TrashFileAction::Managed => {
+            // 以媒体 ID 命名，避免不同文件夹中的同名文件冲突
+            let file_name = match path.extension() {
+                Some(extension) => format!("{}.{}", media_id, extension.to_string_lossy()),
+                None => media_id.to_string(),
+            };
+            (TrashFileAction::Managed, Some(trash_dir.join(file_name)))
+        }
+        action => (action, None),
+    }
+}
+
+/// 按 `trash_target` 确定的处理方式移动原文件
+fn move_to_trash(
+    path: &Path,
+    action: TrashFileAction,
+    trash_path: Option<&Path>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match (action, trash_path) {
+        (TrashFileAction::System, _) => trash::delete(path)?,
+        (TrashFileAction::Managed, Some(trash_path)) => move_file(path, trash_path)?,
+        _ => {}
+    }
+    Ok(())
+}
+
+fn find_purge_rows(
+    conn: &Connection,
+    condition: &str,
+    values: Vec<String>,
+) -> rusqlite::Result<Vec<PurgeRow>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, trash_action, trash_path, thumbnail_path FROM media_records
+         WHERE trashed_at IS NOT NULL {}",
+        condition
+    ))?;
+    let rows = stmt
+        .query_map(params_from_iter(values), |row| {
+            Ok(PurgeRow {
+                id: row.get(0)?,
+                action: row
+                    .get::<_, Option<String>>(1)?
+                    .and_then(|action| TrashFileAction::parse(&action)),
+                trash_path: row.get(2)?,
+                thumbnail_path: row.get(3)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(rows)
+}
+
+/// 删除记录后再删除应用回收站中的文件和不再被引用的缩略图
+fn purge_rows(conn: &Connection, rows: Vec<PurgeRow>) -> rusqlite::Result<usize> {
+    let tx = conn.unchecked_transaction()?;
+    for row in &rows {
+        tx.execute("DELETE FROM media_records WHERE id = ?1", params![row.id])?;
+    }
+    tx.commit()?;
+
+    for row in &rows {
+        if row.action == Some(TrashFileAction::Managed) {
+            if let Some(trash_path) = &row.trash_path {
+                if let Err(e) = fs::remove_file(trash_path) {
+                    warn!("删除回收站文件失败 {}: {}", trash_path, e);
+                }
+            }
+        }
+
+        // 缩略图按文件名生成，可能与其他媒体共用
+        if let Some(thumbnail_path) = &row.thumbnail_path {
+            let in_use: bool = conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM media_records WHERE thumbnail_path = ?1)",
+                params![thumbnail_path],
+                |row| row.get(0),
+            )?;
+            if !in_use {
+                let _ = fs::remove_file(thumbnail_path);
+            }
+        }
+    }
+
+    Ok(rows.len())
+}
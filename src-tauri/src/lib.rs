@@ -9,12 +9,67 @@ mod database;
 mod models;
 mod utils;
 
+use commands::album_commands::{
+    add_media_to_album, create_album, delete_album, get_album_media, get_albums, move_album,
+    remove_media_from_album, rename_album, reorder_album_media, update_album_details,
+};
+use commands::backup_commands::{
+    check_library_integrity, create_backup, get_backup_settings, get_backups, restore_backup,
+    set_backup_settings, spawn_backup_schedule,
+};
+use commands::contact_sheet_commands::export_contact_sheet;
 use commands::database_commands::{
     delete_all_media, delete_selected_media, get_media_detail, get_media_list, import_media,
 };
+use commands::export_commands::{
+    create_export_preset, delete_export_preset, export_media, get_export_presets,
+    update_export_preset,
+};
+use commands::face_commands::{cancel_face_detection, get_media_faces, start_face_detection};
+use commands::gallery_commands::export_gallery;
 use commands::image_import::{
     get_media_record, get_media_records, get_media_records_with_db, read_images_in_dir,
 };
+use commands::journal_commands::{get_journal, redo, undo};
+use commands::library_import_commands::{
+    get_library_import_settings, import_to_library, set_library_import_settings,
+};
+use commands::location_commands::{get_map_clusters, get_places, refresh_media_places};
+use commands::mark_commands::{
+    set_media_color_label, set_media_favorite, set_media_flag, set_media_rating,
+    sync_marks_from_xmp,
+};
+use commands::memory_commands::{
+    get_memories, get_memory_media, refresh_memories, spawn_memory_refresh,
+};
+use commands::motion_commands::extract_motion_clip;
+use commands::organize_commands::organize_media;
+use commands::person_commands::{
+    cluster_faces, get_people, get_person_faces, get_person_media, merge_people,
+    move_faces_to_person, reject_faces, rename_person, set_person_cover,
+};
+use commands::relink_commands::{
+    get_missing_media, propose_relink, relink_media, spawn_file_indexing,
+};
+use commands::rename_commands::{preview_rename, rename_media_files};
+use commands::search_commands::{
+    create_saved_search, delete_saved_search, get_saved_searches, parse_search_query, search_media,
+    update_saved_search,
+};
+use commands::smart_album_commands::{
+    count_smart_rule, create_smart_album, delete_smart_album, get_smart_album_media,
+    get_smart_albums, update_smart_album,
+};
+use commands::stats_commands::get_library_stats;
+use commands::tag_commands::{
+    add_tags_to_media, create_tag, delete_tag, get_tags, merge_tags, remove_tags_from_media,
+    rename_tag,
+};
+use commands::timeline_commands::{get_timeline, shift_media_taken_dates};
+use commands::trash_commands::{
+    empty_trash, get_trash, get_trash_retention_days, restore_media, set_trash_retention_days,
+    spawn_trash_purge,
+};
 use log::trace;
 use tauri::{path::BaseDirectory, Manager};
 use tauri_plugin_log::{Target, TargetKind};
@@ -29,6 +84,12 @@ pub fn run() {
                 .resolve("thumbnails", BaseDirectory::AppLocalData)?;
             std::fs::create_dir_all(&app_dir)?;
             trace!("Thumbnails will be stored in: {:?}", app_dir);
+            // 后台任务启动前先完成数据库迁移
+            database::open_connection(app.handle())?;
+            spawn_memory_refresh(app.handle().clone());
+            spawn_trash_purge(app.handle().clone());
+            spawn_backup_schedule(app.handle().clone());
+            spawn_file_indexing(app.handle().clone());
             Ok(())
         })
         .plugin(
@@ -53,7 +114,93 @@ pub fn run() {
             get_media_detail,
             import_media,
             delete_selected_media,
-            delete_all_media
+            delete_all_media,
+            extract_motion_clip,
+            get_tags,
+            create_tag,
+            rename_tag,
+            merge_tags,
+            delete_tag,
+            add_tags_to_media,
+            remove_tags_from_media,
+            get_albums,
+            create_album,
+            rename_album,
+            update_album_details,
+            move_album,
+            delete_album,
+            get_album_media,
+            add_media_to_album,
+            remove_media_from_album,
+            reorder_album_media,
+            get_smart_albums,
+            create_smart_album,
+            update_smart_album,
+            delete_smart_album,
+            get_smart_album_media,
+            count_smart_rule,
+            set_media_rating,
+            set_media_favorite,
+            set_media_color_label,
+            set_media_flag,
+            sync_marks_from_xmp,
+            search_media,
+            parse_search_query,
+            get_saved_searches,
+            create_saved_search,
+            update_saved_search,
+            delete_saved_search,
+            start_face_detection,
+            cancel_face_detection,
+            get_media_faces,
+            get_people,
+            get_person_faces,
+            get_person_media,
+            rename_person,
+            merge_people,
+            move_faces_to_person,
+            reject_faces,
+            set_person_cover,
+            cluster_faces,
+            get_places,
+            refresh_media_places,
+            get_map_clusters,
+            get_timeline,
+            shift_media_taken_dates,
+            get_memories,
+            get_memory_media,
+            refresh_memories,
+            get_library_stats,
+            get_trash,
+            restore_media,
+            empty_trash,
+            get_trash_retention_days,
+            set_trash_retention_days,
+            get_journal,
+            undo,
+            redo,
+            create_backup,
+            get_backups,
+            restore_backup,
+            get_backup_settings,
+            set_backup_settings,
+            check_library_integrity,
+            get_missing_media,
+            propose_relink,
+            relink_media,
+            get_library_import_settings,
+            set_library_import_settings,
+            import_to_library,
+            preview_rename,
+            rename_media_files,
+            organize_media,
+            export_media,
+            get_export_presets,
+            create_export_preset,
+            update_export_preset,
+            delete_export_preset,
+            export_gallery,
+            export_contact_sheet
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
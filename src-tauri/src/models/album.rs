@@ -0,0 +1,23 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlbumRecord {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    /// 所属文件夹
+    pub parent_id: Option<Uuid>,
+    /// 文件夹只包含子相册，不直接包含媒体
+    pub is_folder: bool,
+    pub cover_media_id: Option<Uuid>,
+    /// 封面缩略图，未设置封面时使用相册内的第一张
+    pub cover_thumbnail_path: Option<String>,
+    /// 在同级相册中的排序位置
+    pub position: u32,
+    pub media_count: u64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
@@ -0,0 +1,41 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::relink::MissingMedia;
+
+/// 数据库备份文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupInfo {
+    /// 备份目录中的文件名，恢复时使用
+    pub file_name: String,
+    pub path: String,
+    pub size: u64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 自动备份设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupSettings {
+    /// 自动备份间隔（小时），0 表示不自动备份
+    pub interval_hours: u32,
+    /// 保留的备份数量，超出时删除最早的备份
+    pub keep_count: u32,
+}
+
+/// 媒体库完整性检查结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityReport {
+    /// `PRAGMA integrity_check` 报告的问题，修复模式下为重建索引后仍存在的问题
+    pub database_errors: Vec<String>,
+    /// 没有被任何媒体引用的缩略图文件
+    pub orphaned_thumbnails: Vec<String>,
+    /// 原文件已不存在的媒体，文件夹不可访问（离线）的媒体不计入
+    pub missing_media: Vec<MissingMedia>,
+    /// tags 或 album_ids 中不是有效 JSON 的媒体
+    pub invalid_json_media_ids: Vec<String>,
+    /// 是否已按检查结果修复
+    pub repaired: bool,
+}
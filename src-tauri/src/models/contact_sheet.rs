@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::export::ExportFailure;
+
+/// 小样的输出格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ContactSheetFormat {
+    /// 多页 PDF，每页为一张图片
+    #[default]
+    Pdf,
+    /// 每页一个 JPEG 文件
+    Jpeg,
+}
+
+/// 纸张尺寸，均为纵向尺寸
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum PageSize {
+    #[default]
+    A4,
+    A3,
+    Letter,
+    Legal,
+    /// 自定义尺寸（毫米）
+    Custom {
+        width_mm: f32,
+        height_mm: f32,
+    },
+}
+
+impl PageSize {
+    /// 纵向的宽和高（毫米）
+    pub fn dimensions_mm(&self) -> (f32, f32) {
+        match *self {
+            PageSize::A4 => (210.0, 297.0),
+            PageSize::A3 => (297.0, 420.0),
+            PageSize::Letter => (215.9, 279.4),
+            PageSize::Legal => (215.9, 355.6),
+            PageSize::Custom {
+                width_mm,
+                height_mm,
+            } => (width_mm, height_mm),
+        }
+    }
+}
+
+/// 小样导出选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContactSheetOptions {
+    /// 按给定顺序排列的媒体
+    pub media_ids: Vec<String>,
+    /// 输出文件的绝对路径；JPEG 有多页时依次命名为 `名称-1.jpg`、`名称-2.jpg`
+    pub target: String,
+    #[serde(default)]
+    pub format: ContactSheetFormat,
+    #[serde(default)]
+    pub page_size: PageSize,
+    /// 横向排版
+    #[serde(default)]
+    pub landscape: bool,
+    /// 页边距（毫米）
+    #[serde(default = "default_margin_mm")]
+    pub margin_mm: f32,
+    /// 每行的照片数，行数按页面大小自动计算
+    #[serde(default = "default_columns")]
+    pub columns: u32,
+    /// 渲染分辨率（每英寸像素）
+    #[serde(default = "default_dpi")]
+    pub dpi: u32,
+    /// 文件名等文字使用的 TrueType / OpenType 字体文件
+    pub font_path: String,
+    /// 每页顶部的标题
+    pub title: Option<String>,
+    /// 在文件名和评分下显示相机、光圈等拍摄参数
+    #[serde(default)]
+    pub include_exif: bool,
+}
+
+fn default_margin_mm() -> f32 {
+    10.0
+}
+
+fn default_columns() -> u32 {
+    4
+}
+
+fn default_dpi() -> u32 {
+    150
+}
+
+/// 小样导出结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContactSheetSummary {
+    /// 生成的文件路径
+    pub files: Vec<String>,
+    pub pages: usize,
+    pub photos: usize,
+    /// 视频等无法加入小样的媒体，以及读取失败的照片
+    pub skipped: Vec<ExportFailure>,
+}
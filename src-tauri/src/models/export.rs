@@ -0,0 +1,167 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 导出的文件格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportFormat {
+    /// 原图，不缩放、不转换色彩空间
+    #[default]
+    Original,
+    Jpeg,
+    Png,
+    /// 无损 WebP，不使用质量设置
+    Webp,
+    Avif,
+}
+
+impl ExportFormat {
+    /// 转换后文件的扩展名，原图沿用原扩展名
+    pub fn extension(&self) -> Option<&'static str> {
+        match self {
+            ExportFormat::Original => None,
+            ExportFormat::Jpeg => Some("jpg"),
+            ExportFormat::Png => Some("png"),
+            ExportFormat::Webp => Some("webp"),
+            ExportFormat::Avif => Some("avif"),
+        }
+    }
+}
+
+/// 导出时对元数据的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MetadataPolicy {
+    /// 保留所有元数据
+    #[default]
+    KeepAll,
+    /// 移除 GPS 位置信息
+    StripGps,
+    /// 移除 EXIF、XMP、IPTC 等所有元数据，只保留色彩配置文件
+    StripAll,
+}
+
+/// 水印在图片中的位置
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WatermarkAnchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    #[default]
+    BottomRight,
+}
+
+/// 水印内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum WatermarkContent {
+    /// 单行文字，使用指定的 TrueType / OpenType 字体文件
+    Text {
+        text: String,
+        font_path: String,
+        /// 字号（像素），按导出后的图片尺寸计算
+        font_size: f32,
+        /// 文字颜色，`#RRGGBB` 格式
+        color: String,
+    },
+    /// 图片水印，通常为带透明度的 PNG 标志
+    Image {
+        path: String,
+        /// 水印宽度（像素），按比例缩放，为空时使用原尺寸
+        width: Option<u32>,
+    },
+}
+
+/// 水印设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatermarkSettings {
+    pub content: WatermarkContent,
+    #[serde(default)]
+    pub anchor: WatermarkAnchor,
+    /// 水印与图片边缘的距离（像素）
+    #[serde(default)]
+    pub margin: u32,
+    /// 不透明度（0-1）
+    #[serde(default = "default_opacity")]
+    pub opacity: f32,
+}
+
+fn default_opacity() -> f32 {
+    1.0
+}
+
+/// 边框设置，边框加在图片外侧，导出的图片会相应变大
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BorderSettings {
+    /// 边框宽度（像素）
+    pub width: u32,
+    /// 边框颜色，`#RRGGBB` 格式
+    pub color: String,
+}
+
+/// 导出设置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportSettings {
+    #[serde(default)]
+    pub format: ExportFormat,
+    /// 长边的最大像素，只缩小不放大，为空时保持原尺寸
+    pub long_edge: Option<u32>,
+    /// JPEG 和 AVIF 的质量（1-100），为空时使用 90
+    pub quality: Option<u8>,
+    /// 带有色彩配置文件的图片转换为 sRGB
+    #[serde(default)]
+    pub convert_to_srgb: bool,
+    /// 文件名模板，不含扩展名，为空时使用原文件名
+    pub file_name_template: Option<String>,
+    #[serde(default)]
+    pub metadata_policy: MetadataPolicy,
+    /// 水印，只能用于转换格式的导出
+    pub watermark: Option<WatermarkSettings>,
+    /// 边框，只能用于转换格式的导出
+    pub border: Option<BorderSettings>,
+}
+
+/// 命名的导出预设
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportPresetRecord {
+    pub id: Uuid,
+    pub name: String,
+    pub settings: ExportSettings,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 导出失败的媒体
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportFailure {
+    pub media_id: String,
+    pub path: String,
+    pub error: String,
+}
+
+/// 导出结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportSummary {
+    /// 导出的文件路径
+    pub exported: Vec<String>,
+    pub failed: Vec<ExportFailure>,
+    /// 导出文件的总大小（字节）
+    pub total_size: u64,
+}
@@ -0,0 +1,50 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FaceRecord {
+    pub id: Uuid,
+    pub media_id: Uuid,
+    pub person_id: Option<Uuid>,
+    /// 人脸框 [x, y, 宽, 高]，为相对图片宽高的比例（0-1）
+    pub bbox: [f64; 4],
+    pub confidence: f64,
+    /// 归属是否由用户确认，已确认的人脸在重新聚类时保持不变
+    pub confirmed: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersonRecord {
+    pub id: Uuid,
+    pub name: Option<String>,
+    /// 封面人脸，未指定时使用置信度最高的人脸
+    pub cover_face_id: Option<Uuid>,
+    pub cover_media_id: Option<Uuid>,
+    pub cover_thumbnail_path: Option<String>,
+    /// 封面人脸在图片中的位置，用于从缩略图裁剪头像
+    pub cover_bbox: Option<[f64; 4]>,
+    pub face_count: u64,
+    /// 包含该人物的照片数量
+    pub photo_count: u64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 一次聚类的结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClusterSummary {
+    /// 归入已有人物的人脸数量
+    pub assigned_faces: usize,
+    /// 新建的人物数量
+    pub new_people: usize,
+    /// 新建人物中包含的人脸数量
+    pub clustered_faces: usize,
+    /// 仍未归属任何人物的人脸数量
+    pub unassigned_faces: usize,
+}
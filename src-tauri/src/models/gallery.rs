@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::export::{ExportFailure, MetadataPolicy};
+
+/// 静态网站的输出方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GalleryOutput {
+    /// 写入空文件夹（不存在时创建）
+    #[default]
+    Folder,
+    /// 打包为 zip 文件
+    Zip,
+}
+
+/// 静态网站导出选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GalleryOptions {
+    /// 网站标题
+    pub title: String,
+    /// 导出的相册，按相册内的顺序排列；与 `media_ids` 二选一
+    pub album_id: Option<String>,
+    /// 导出的媒体，按给定顺序排列
+    pub media_ids: Option<Vec<String>>,
+    /// 目标文件夹或 zip 文件的绝对路径
+    pub target: String,
+    #[serde(default)]
+    pub output: GalleryOutput,
+    /// 每个索引页的照片数
+    #[serde(default = "default_page_size")]
+    pub page_size: usize,
+    /// 大图长边的像素
+    #[serde(default = "default_image_long_edge")]
+    pub image_long_edge: u32,
+    /// 在照片页显示相机、镜头、光圈等拍摄参数
+    #[serde(default)]
+    pub include_exif: bool,
+    /// 生成显示拍摄地点的地图页，默认只显示经纬网和照片位置，离线可用；
+    /// 元数据策略移除 GPS 时不生成地图页
+    #[serde(default)]
+    pub include_map: bool,
+    /// 地图页叠加在线的 OpenStreetMap 底图，浏览地图时需要联网
+    #[serde(default)]
+    pub online_map_tiles: bool,
+    /// 大图中元数据的处理方式，缩略图不包含元数据
+    #[serde(default)]
+    pub metadata_policy: MetadataPolicy,
+}
+
+fn default_page_size() -> usize {
+    60
+}
+
+fn default_image_long_edge() -> u32 {
+    2048
+}
+
+/// 静态网站导出结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GallerySummary {
+    /// 网站文件夹或 zip 文件的路径
+    pub output_path: String,
+    pub photos: usize,
+    pub pages: usize,
+    /// 视频等无法加入网站的媒体，以及读取失败的照片
+    pub skipped: Vec<ExportFailure>,
+}
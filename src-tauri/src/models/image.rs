@@ -29,4 +29,101 @@ pub struct MediaRecord {
     pub tags: Option<Vec<String>>,
     pub album_ids: Option<Vec<uuid::Uuid>>,
     pub face_count: Option<u32>,
+    /// 实况照片 / 动态照片的视频部分路径（嵌入式视频指向照片自身）
+    pub motion_path: Option<String>,
+    /// 嵌入式视频在文件中的起始偏移
+    pub motion_offset: Option<u64>,
+    /// 嵌入式视频的字节长度
+    pub motion_length: Option<u64>,
+    pub camera_make: Option<String>,
+    pub camera_model: Option<String>,
+    pub lens_model: Option<String>,
+    pub iso: Option<u32>,
+    /// 光圈值（f 数）
+    pub aperture: Option<f64>,
+    /// 焦距（毫米）
+    pub focal_length: Option<f64>,
+    /// GPS 纬度（十进制度数，南纬为负）
+    pub latitude: Option<f64>,
+    /// GPS 经度（十进制度数，西经为负）
+    pub longitude: Option<f64>,
+    /// 由 GPS 坐标离线反向地理编码得到的国家、一级行政区和城市
+    pub country: Option<String>,
+    pub region: Option<String>,
+    pub city: Option<String>,
+    /// 星级评分 0-5
+    pub rating: u8,
+    pub favorite: bool,
+    pub color_label: Option<ColorLabel>,
+    /// 挑选/排除标记
+    pub flag: PickFlag,
+    /// 图片说明
+    pub caption: Option<String>,
+}
+
+/// 颜色标签，名称与 Lightroom 写入 `xmp:Label` 的值一致
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ColorLabel {
+    Red,
+    Yellow,
+    Green,
+    Blue,
+    Purple,
+}
+
+impl ColorLabel {
+    pub const ALL: [ColorLabel; 5] = [
+        ColorLabel::Red,
+        ColorLabel::Yellow,
+        ColorLabel::Green,
+        ColorLabel::Blue,
+        ColorLabel::Purple,
+    ];
+
+    /// 数据库和 XMP 中使用的名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ColorLabel::Red => "Red",
+            ColorLabel::Yellow => "Yellow",
+            ColorLabel::Green => "Green",
+            ColorLabel::Blue => "Blue",
+            ColorLabel::Purple => "Purple",
+        }
+    }
+
+    /// 按名称解析，不区分大小写
+    pub fn parse(value: &str) -> Option<ColorLabel> {
+        Self::ALL
+            .into_iter()
+            .find(|label| label.as_str().eq_ignore_ascii_case(value.trim()))
+    }
+}
+
+/// 挑选标记，数据库中存储为 1 / 0 / -1
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PickFlag {
+    #[default]
+    None,
+    Pick,
+    Reject,
+}
+
+impl PickFlag {
+    pub fn to_db(self) -> i64 {
+        match self {
+            PickFlag::None => 0,
+            PickFlag::Pick => 1,
+            PickFlag::Reject => -1,
+        }
+    }
+
+    pub fn from_db(value: i64) -> PickFlag {
+        match value {
+            1 => PickFlag::Pick,
+            -1 => PickFlag::Reject,
+            _ => PickFlag::None,
+        }
+    }
 }
@@ -0,0 +1,16 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// 操作日志中的一条记录，对应一次可撤销的操作
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalEntry {
+    pub id: i64,
+    /// 操作名称，例如 "设置评分"
+    pub label: String,
+    /// 操作涉及的变更数量
+    pub change_count: u64,
+    /// 已撤销，可以重做
+    pub undone: bool,
+    pub created_at: DateTime<Utc>,
+}
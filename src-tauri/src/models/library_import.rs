@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+
+/// 导入到媒体库文件夹的方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportTransferMode {
+    /// 复制，保留原文件
+    Copy,
+    /// 移动，复制并校验内容后删除原文件
+    Move,
+}
+
+/// 媒体库文件夹及导入路径模板
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryImportSettings {
+    /// 媒体库文件夹，未设置时不能复制或移动导入
+    pub library_root: Option<String>,
+    /// 文件在媒体库文件夹中的相对路径模板，如 `{year}/{year}-{month}-{day}/{camera}_{seq}.{ext}`
+    pub path_template: String,
+}
+
+/// 导入到媒体库文件夹中的文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedFile {
+    pub media_id: String,
+    pub source_path: String,
+    pub path: String,
+}
+
+/// 导入失败的文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportFailure {
+    pub source_path: String,
+    pub error: String,
+}
+
+/// 复制或移动导入的结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryImportSummary {
+    pub imported: Vec<ImportedFile>,
+    /// 内容已在媒体库中而跳过的文件，移动导入时也不会删除
+    pub duplicates: Vec<String>,
+    pub failed: Vec<ImportFailure>,
+}
@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+/// 地图可视范围，经度 `west` 大于 `east` 时表示跨越 180° 经线
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MapBounds {
+    pub north: f64,
+    pub south: f64,
+    pub east: f64,
+    pub west: f64,
+}
+
+/// 地图上的照片聚合点
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MapCluster {
+    /// 聚合点位置，为其中照片坐标的平均值
+    pub latitude: f64,
+    pub longitude: f64,
+    pub count: u64,
+    /// 代表照片，优先选择收藏和评分高的照片
+    pub media_id: String,
+    pub thumbnail_path: Option<String>,
+    /// 聚合内照片的范围，点击后可缩放到该范围
+    pub bounds: MapBounds,
+}
+
+/// 某个地点下的媒体数量
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaceCount {
+    pub country: Option<String>,
+    pub region: Option<String>,
+    pub city: Option<String>,
+    pub media_count: u64,
+}
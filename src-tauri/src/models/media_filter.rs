@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::image::{ColorLabel, PickFlag};
+
+/// 媒体列表的筛选条件，未设置的字段不参与筛选
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MediaFilter {
+    /// 最低星级
+    pub min_rating: Option<u8>,
+    pub favorite: Option<bool>,
+    /// 任一颜色标签匹配即可，为空时不筛选
+    pub color_labels: Vec<ColorLabel>,
+    /// 任一挑选标记匹配即可，为空时不筛选
+    pub flags: Vec<PickFlag>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MediaSortField {
+    /// 导入时间
+    #[default]
+    CreatedAt,
+    /// 拍摄时间，没有拍摄时间时使用导入时间
+    TakenDate,
+    Name,
+    Size,
+    Rating,
+}
+
+/// 媒体列表的排序方式，默认按导入时间倒序
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MediaSort {
+    pub field: MediaSortField,
+    pub ascending: bool,
+}
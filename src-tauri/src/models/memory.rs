@@ -0,0 +1,50 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 回忆的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MemoryKind {
+    /// 往年的今天
+    OnThisDay,
+    /// 离开常住地的连续多天
+    Trip,
+    /// 短时间内密集拍摄的一组照片
+    Event,
+}
+
+impl MemoryKind {
+    pub const ALL: [MemoryKind; 3] = [MemoryKind::OnThisDay, MemoryKind::Trip, MemoryKind::Event];
+
+    /// 数据库中使用的名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MemoryKind::OnThisDay => "OnThisDay",
+            MemoryKind::Trip => "Trip",
+            MemoryKind::Event => "Event",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<MemoryKind> {
+        Self::ALL.into_iter().find(|kind| kind.as_str() == value)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryRecord {
+    pub id: Uuid,
+    pub kind: MemoryKind,
+    pub title: String,
+    pub subtitle: Option<String>,
+    /// 第一张照片的拍摄时间
+    pub start_date: DateTime<Utc>,
+    /// 最后一张照片的拍摄时间
+    pub end_date: DateTime<Utc>,
+    pub cover_media_id: Option<Uuid>,
+    pub cover_thumbnail_path: Option<String>,
+    pub media_count: u64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
@@ -1 +1,21 @@
+pub mod album;
+pub mod backup;
+pub mod contact_sheet;
+pub mod export;
+pub mod face;
+pub mod gallery;
 pub mod image;
+pub mod journal;
+pub mod library_import;
+pub mod location;
+pub mod media_filter;
+pub mod memory;
+pub mod organize;
+pub mod relink;
+pub mod rename;
+pub mod search;
+pub mod smart_album;
+pub mod stats;
+pub mod tag;
+pub mod timeline;
+pub mod trash;
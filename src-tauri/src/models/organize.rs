@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::rename::RenamePreview;
+
+/// 整理文件时新位置已有文件的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CollisionPolicy {
+    /// 跳过该文件，保持原位置
+    #[default]
+    Skip,
+    /// 在文件名后加编号，如 `IMG_0001-1.jpg`
+    Rename,
+}
+
+/// 按元数据整理文件夹的选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrganizeOptions {
+    /// 要整理的媒体，为空时整理媒体库中的所有媒体
+    pub media_ids: Option<Vec<String>>,
+    /// 目标文件夹，必须是绝对路径
+    pub target_root: String,
+    /// 文件夹模板，如 `{year}/{month}`、`{camera}`、`{album}`、`{person}`、`{country}/{city}`，文件名保持不变
+    pub folder_template: String,
+    #[serde(default)]
+    pub collision_policy: CollisionPolicy,
+    /// 只生成计划，不移动文件
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// 整理文件夹的结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrganizeResult {
+    /// 计划或已执行的移动，有冲突的文件被跳过
+    pub moves: Vec<RenamePreview>,
+    pub moved: usize,
+    /// 已在目标位置的文件数量
+    pub unchanged: usize,
+    /// 因冲突跳过的文件数量
+    pub skipped: usize,
+    /// 移动失败并已移回原位置的文件数量
+    pub failed: usize,
+    pub dry_run: bool,
+}
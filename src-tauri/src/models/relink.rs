@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+/// 原文件已不存在的媒体
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingMedia {
+    pub media_id: String,
+    pub path: String,
+    /// 所在文件夹也不存在，可能是外接硬盘未连接
+    pub offline: bool,
+}
+
+/// 丢失的媒体与新文件的匹配方式，按可信程度排列
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RelinkMatchKind {
+    /// 内容哈希相同
+    Hash,
+    /// 文件大小和修改时间相同
+    SizeAndModified,
+    /// 只有文件名相同
+    Name,
+}
+
+/// 重新关联的建议，需用户确认后再更新路径
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelinkProposal {
+    pub media_id: String,
+    pub old_path: String,
+    pub new_path: String,
+    pub match_kind: RelinkMatchKind,
+}
+
+/// 用户确认的重新关联
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelinkMatch {
+    pub media_id: String,
+    pub new_path: String,
+}
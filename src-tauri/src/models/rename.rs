@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+/// 重命名或移动媒体文件时的冲突
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RenameConflict {
+    /// 原文件不存在
+    SourceMissing,
+    /// 新位置已有其他文件
+    TargetExists,
+    /// 同一批中有多个文件使用相同的新路径
+    DuplicateTarget,
+}
+
+/// 重命名或移动的预览
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenamePreview {
+    pub media_id: String,
+    pub old_path: String,
+    pub new_path: String,
+    /// 有冲突时不能执行
+    pub conflict: Option<RenameConflict>,
+}
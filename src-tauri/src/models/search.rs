@@ -0,0 +1,30 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::image::MediaRecord;
+
+/// 全文搜索结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResult {
+    #[serde(flatten)]
+    pub record: MediaRecord,
+    /// bm25 相关度，数值越小越相关
+    pub rank: f64,
+    /// 匹配片段，关键词以 `<mark>` 包裹，其余内容已做 HTML 转义
+    pub snippet: Option<String>,
+}
+
+/// 保存的命名搜索
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedSearchRecord {
+    pub id: Uuid,
+    pub name: String,
+    /// 查询语法文本
+    pub query: String,
+    pub position: u32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
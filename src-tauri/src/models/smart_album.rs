@@ -0,0 +1,153 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::image::{ColorLabel, MediaKind, PickFlag};
+
+/// 智能相册的规则树，以 JSON 形式持久化，查询时编译为 SQL
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum SmartRule {
+    /// 满足全部子规则，子规则为空时匹配所有媒体
+    All {
+        rules: Vec<SmartRule>,
+    },
+    /// 满足任一子规则，子规则为空时不匹配任何媒体
+    Any {
+        rules: Vec<SmartRule>,
+    },
+    Not {
+        rule: Box<SmartRule>,
+    },
+    /// 拍摄时间范围，没有拍摄时间时使用导入时间；`from`、`to` 包含端点，`before` 不包含
+    DateRange {
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        #[serde(default)]
+        before: Option<DateTime<Utc>>,
+    },
+    /// 最近 N 天内拍摄，随时间推移自动更新
+    RecentDays {
+        days: u32,
+    },
+    /// 相机品牌或型号包含指定文本
+    Camera {
+        value: String,
+    },
+    Lens {
+        value: String,
+    },
+    /// 带有指定标签，`include_children` 为 true 时同时匹配子标签
+    Tag {
+        path: String,
+        #[serde(default = "default_true")]
+        include_children: bool,
+    },
+    Kind {
+        kind: MediaKind,
+    },
+    /// 文件扩展名，不区分大小写，如 ["heic", "dng"]
+    Extension {
+        extensions: Vec<String>,
+    },
+    /// 文件大小范围（字节）
+    Size {
+        min: Option<u64>,
+        max: Option<u64>,
+    },
+    Rating {
+        min: Option<u8>,
+        max: Option<u8>,
+    },
+    Favorite,
+    /// 带有任一指定颜色标签
+    ColorLabel {
+        labels: Vec<ColorLabel>,
+    },
+    Flag {
+        flag: PickFlag,
+    },
+    /// 以指定坐标为中心、半径若干公里内拍摄
+    Location {
+        latitude: f64,
+        longitude: f64,
+        radius_km: f64,
+    },
+    HasLocation,
+    /// 国家、行政区或城市名称包含指定文本
+    Place {
+        value: String,
+    },
+    /// 不属于任何相册
+    NotInAnyAlbum,
+    InAlbum {
+        album_id: String,
+    },
+    /// 相册名称等于指定文本（不区分大小写）
+    AlbumName {
+        name: String,
+    },
+    /// 文件路径包含指定文本
+    Folder {
+        value: String,
+    },
+    /// 数值字段比较，如 ISO > 3200
+    Compare {
+        field: NumericField,
+        op: CompareOp,
+        value: f64,
+    },
+    /// 全文搜索，匹配规则与 `search_media` 相同
+    Text {
+        query: String,
+    },
+    /// 文件名包含指定文本
+    NameContains {
+        value: String,
+    },
+}
+
+/// 可比较的数值字段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NumericField {
+    Iso,
+    Aperture,
+    FocalLength,
+    Rating,
+    /// 文件大小（字节）
+    Size,
+    Width,
+    Height,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CompareOp {
+    Eq,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SmartAlbumRecord {
+    pub id: Uuid,
+    pub name: String,
+    pub rule: SmartRule,
+    pub position: u32,
+    /// 当前符合规则的媒体数量
+    pub media_count: u64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
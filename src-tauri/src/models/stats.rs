@@ -0,0 +1,40 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// 按某个维度分组的媒体数量和占用空间
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatGroup {
+    /// 分组值，为空表示未知（例如没有相机信息）
+    pub key: Option<String>,
+    pub count: u64,
+    pub bytes: u64,
+}
+
+/// 媒体库统计与存储报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryStats {
+    /// 媒体数量，不含回收站
+    pub total_count: u64,
+    /// 原始文件的总大小（字节）
+    pub total_bytes: u64,
+    pub by_kind: Vec<StatGroup>,
+    /// 按 MIME 类型分组
+    pub by_format: Vec<StatGroup>,
+    /// 按拍摄年份（本地时间）分组，没有拍摄时间时使用导入时间
+    pub by_year: Vec<StatGroup>,
+    pub by_camera: Vec<StatGroup>,
+    pub by_folder: Vec<StatGroup>,
+    /// 缩略图缓存目录的总大小（字节）
+    pub thumbnail_bytes: u64,
+    /// 所在文件夹存在但文件已不存在的媒体数量
+    pub missing_count: u64,
+    /// 所在文件夹不可访问（例如外接硬盘未连接）的媒体数量
+    pub offline_count: u64,
+    /// 回收站中的媒体数量，不计入以上统计
+    pub trashed_count: u64,
+    pub trashed_bytes: u64,
+    /// 统计时间，结果可能来自缓存
+    pub generated_at: DateTime<Utc>,
+}
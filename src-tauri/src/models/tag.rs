@@ -0,0 +1,28 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagRecord {
+    pub id: Uuid,
+    /// 当前层级的名称，如 Kyoto
+    pub name: String,
+    pub parent_id: Option<Uuid>,
+    /// 完整层级路径，如 Travel/Japan/Kyoto
+    pub path: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 侧边栏使用的带计数标签
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagWithCount {
+    #[serde(flatten)]
+    pub tag: TagRecord,
+    /// 直接打上该标签的媒体数量
+    pub media_count: u64,
+    /// 包含子标签在内的媒体数量（去重）
+    pub total_count: u64,
+}
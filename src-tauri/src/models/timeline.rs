@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+/// 时间线的分组粒度
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TimelineGranularity {
+    Year,
+    #[default]
+    Month,
+    Day,
+}
+
+impl TimelineGranularity {
+    /// 对应的 SQLite strftime 格式，结果即分组的键
+    pub fn strftime_format(&self) -> &'static str {
+        match self {
+            TimelineGranularity::Year => "%Y",
+            TimelineGranularity::Month => "%Y-%m",
+            TimelineGranularity::Day => "%Y-%m-%d",
+        }
+    }
+}
+
+/// 时间线中的一个分组
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineBucket {
+    /// 分组键，按粒度为 `2024`、`2024-05` 或 `2024-05-17`（本地时间）
+    pub key: String,
+    pub year: i32,
+    pub month: Option<u32>,
+    pub day: Option<u32>,
+    pub count: u64,
+    /// 分组中第一条媒体在相同筛选和排序的媒体列表中的位置
+    pub offset: u64,
+}
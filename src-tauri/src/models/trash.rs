@@ -0,0 +1,51 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::image::MediaRecord;
+
+/// 删除媒体时对原文件的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TrashFileAction {
+    /// 只从媒体库移除，不处理原文件
+    #[default]
+    Keep,
+    /// 移到系统回收站，恢复时需在系统回收站中手动还原文件
+    System,
+    /// 移到应用管理的回收站目录，恢复时移回原位置
+    Managed,
+}
+
+impl TrashFileAction {
+    pub const ALL: [TrashFileAction; 3] = [
+        TrashFileAction::Keep,
+        TrashFileAction::System,
+        TrashFileAction::Managed,
+    ];
+
+    /// 数据库中使用的名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TrashFileAction::Keep => "Keep",
+            TrashFileAction::System => "System",
+            TrashFileAction::Managed => "Managed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<TrashFileAction> {
+        Self::ALL
+            .into_iter()
+            .find(|action| action.as_str() == value)
+    }
+}
+
+/// 回收站中的媒体
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashedMedia {
+    pub record: MediaRecord,
+    pub trashed_at: DateTime<Utc>,
+    pub file_action: TrashFileAction,
+    /// 到期后自动彻底删除，未设置保留期时为空
+    pub expires_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,218 @@
+use ab_glyph::FontVec;
+use image::{imageops, DynamicImage, RgbImage, Rgba, RgbaImage};
+
+use crate::models::contact_sheet::ContactSheetOptions;
+use crate::utils::text_render::{fit_text, render_text};
+
+/// 每英寸的毫米数
+const MM_PER_INCH: f32 = 25.4;
+
+/// 每英寸的点数，PDF 的页面尺寸以点为单位
+const POINTS_PER_INCH: f32 = 72.0;
+
+/// 文字高度（毫米），约为 8 磅
+const TEXT_SIZE_MM: f32 = 2.8;
+
+/// 照片之间的间距（毫米）
+const GAP_MM: f32 = 4.0;
+
+/// 照片格子的最小宽度（像素），列数过多时报错
+const MIN_CELL_WIDTH: u32 = 40;
+
+const TEXT_COLOR: Rgba<u8> = Rgba([34, 34, 34, 255]);
+const SECONDARY_TEXT_COLOR: Rgba<u8> = Rgba([110, 110, 110, 255]);
+const IMAGE_BACKGROUND: Rgba<u8> = Rgba([238, 238, 238, 255]);
+
+/// 小样中的一张照片
+pub struct SheetCell {
+    /// 已缩小到不超过格子大小的照片
+    pub image: DynamicImage,
+    pub name: String,
+    pub rating: u8,
+    pub exif: Option<String>,
+}
+
+/// 按页面尺寸、边距和列数计算出的排版（像素）
+pub struct SheetLayout {
+    /// 纸张尺寸（毫米），已按横向或纵向调整
+    width_mm: f32,
+    height_mm: f32,
+    pub page_width: u32,
+    pub page_height: u32,
+    margin: u32,
+    gap: u32,
+    columns: u32,
+    rows: u32,
+    /// 格子宽度，照片区域为同样大小的正方形
+    pub cell_width: u32,
+    font_size: f32,
+    line_height: u32,
+    label_lines: u32,
+    header_height: u32,
+}
+
+impl SheetLayout {
+    pub fn new(options: &ContactSheetOptions) -> Result<Self, String> {
+        if !(1..=20).contains(&options.columns) {
+            return Err(format!("列数必须在 1 到 20 之间: {}", options.columns));
+        }
+        if !(72..=600).contains(&options.dpi) {
+            return Err(format!("分辨率必须在 72 到 600 之间: {}", options.dpi));
+        }
+        let (width_mm, height_mm) = options.page_size.dimensions_mm();
+        let valid_size = |size: f32| size.is_finite() && (50.0..=1200.0).contains(&size);
+        if !valid_size(width_mm) || !valid_size(height_mm) {
+            return Err(format!("纸张尺寸无效: {} × {} 毫米", width_mm, height_mm));
+        }
+        if !options.margin_mm.is_finite() || options.margin_mm < 0.0 {
+            return Err(format!("页边距无效: {}", options.margin_mm));
+        }
+        let (width_mm, height_mm) = if options.landscape {
+            (height_mm, width_mm)
+        } else {
+            (width_mm, height_mm)
+        };
+
+        let to_px = |mm: f32| (mm / MM_PER_INCH * options.dpi as f32).round() as u32;
+        let page_width = to_px(width_mm);
+        let page_height = to_px(height_mm);
+        let margin = to_px(options.margin_mm);
+        let gap = to_px(GAP_MM);
+        let font_size = TEXT_SIZE_MM / MM_PER_INCH * options.dpi as f32;
+        let line_height = (font_size * 1.4).ceil() as u32;
+        let label_lines = if options.include_exif { 3 } else { 2 };
+        let header_height = if options
+            .title
+            .as_deref()
+            .is_some_and(|t| !t.trim().is_empty())
+        {
+            line_height * 2 + gap
+        } else {
+            0
+        };
+        let footer_height = line_height + gap;
+
+        let content_width = page_width.saturating_sub(margin * 2);
+        let cell_width =
+            content_width.saturating_sub(gap * (options.columns - 1)) / options.columns;
+        if cell_width < MIN_CELL_WIDTH {
+            return Err("每行照片过多或页边距过大，请减少列数或边距".to_string());
+        }
+        let cell_height = cell_width + line_height * label_lines;
+        let content_height = page_height
+            .saturating_sub(margin * 2)
+            .saturating_sub(header_height + footer_height);
+        let rows = (content_height + gap) / (cell_height + gap);
+        if rows == 0 {
+            return Err("页面高度放不下一行照片，请减少列数或边距".to_string());
+        }
+
+        Ok(Self {
+            width_mm,
+            height_mm,
+            page_width,
+            page_height,
+            margin,
+            gap,
+            columns: options.columns,
+            rows,
+            cell_width,
+            font_size,
+            line_height,
+            label_lines,
+            header_height,
+        })
+    }
+
+    /// 纸张尺寸（点），用于 PDF 页面
+    pub fn page_size_points(&self) -> (f32, f32) {
+        (
+            self.width_mm / MM_PER_INCH * POINTS_PER_INCH,
+            self.height_mm / MM_PER_INCH * POINTS_PER_INCH,
+        )
+    }
+
+    /// 每页的照片数
+    pub fn per_page(&self) -> usize {
+        (self.columns * self.rows) as usize
+    }
+}
+
+/// 绘制一页小样，页码 `page` 从 0 开始，显示在右下角
+pub fn render_sheet(
+    layout: &SheetLayout,
+    font: &FontVec,
+    cells: &[SheetCell],
+    title: Option<&str>,
+    page: usize,
+) -> RgbImage {
+    let mut canvas = RgbaImage::from_pixel(
+        layout.page_width,
+        layout.page_height,
+        Rgba([255, 255, 255, 255]),
+    );
+    let text_width = layout.cell_width as f32;
+
+    if let Some(title) = title.map(str::trim).filter(|title| !title.is_empty()) {
+        let title_size = layout.font_size * 1.6;
+        let content_width = (layout.page_width - layout.margin * 2) as f32;
+        let text = fit_text(font, title, title_size, content_width);
+        let layer = render_text(font, &text, title_size, TEXT_COLOR);
+        imageops::overlay(
+            &mut canvas,
+            &layer,
+            layout.margin as i64,
+            layout.margin as i64,
+        );
+    }
+
+    let top = layout.margin + layout.header_height;
+    let cell_height = layout.cell_width + layout.line_height * layout.label_lines;
+    for (index, cell) in cells.iter().take(layout.per_page()).enumerate() {
+        let column = index as u32 % layout.columns;
+        let row = index as u32 / layout.columns;
+        let x = layout.margin + column * (layout.cell_width + layout.gap);
+        let y = top + row * (cell_height + layout.gap);
+
+        let background =
+            RgbaImage::from_pixel(layout.cell_width, layout.cell_width, IMAGE_BACKGROUND);
+        imageops::replace(&mut canvas, &background, x as i64, y as i64);
+        let image = cell.image.to_rgba8();
+        let image_x = x + (layout.cell_width.saturating_sub(image.width())) / 2;
+        let image_y = y + (layout.cell_width.saturating_sub(image.height())) / 2;
+        imageops::overlay(&mut canvas, &image, image_x as i64, image_y as i64);
+
+        let rating = cell.rating.min(5) as usize;
+        let mut lines = vec![
+            (cell.name.clone(), TEXT_COLOR),
+            (
+                format!("{}{}", "★".repeat(rating), "☆".repeat(5 - rating)),
+                TEXT_COLOR,
+            ),
+        ];
+        if layout.label_lines > 2 {
+            lines.push((cell.exif.clone().unwrap_or_default(), SECONDARY_TEXT_COLOR));
+        }
+        for (line, (text, color)) in lines.into_iter().enumerate() {
+            if text.is_empty() {
+                continue;
+            }
+            let text = fit_text(font, &text, layout.font_size, text_width);
+            let layer = render_text(font, &text, layout.font_size, color);
+            let line_y = y + layout.cell_width + layout.line_height * line as u32 + layout.gap / 4;
+            imageops::overlay(&mut canvas, &layer, x as i64, line_y as i64);
+        }
+    }
+
+    let footer = (page + 1).to_string();
+    let layer = render_text(font, &footer, layout.font_size, SECONDARY_TEXT_COLOR);
+    let footer_x = layout
+        .page_width
+        .saturating_sub(layout.margin + layer.width());
+    let footer_y = layout
+        .page_height
+        .saturating_sub(layout.margin + layout.line_height);
+    imageops::overlay(&mut canvas, &layer, footer_x as i64, footer_y as i64);
+
+    DynamicImage::ImageRgba8(canvas).to_rgb8()
+}
@@ -0,0 +1,128 @@
+use std::{fs, io::BufReader, path::Path};
+
+use chrono::{DateTime, FixedOffset, Local, NaiveDateTime, TimeZone, Utc};
+use exif::{Exif, Field, In, Reader as ExifReader, Tag, Value};
+use log::info;
+
+/// 从 EXIF 中提取的拍摄信息
+#[derive(Debug, Clone, Default)]
+pub struct ExifMetadata {
+    pub taken_date: Option<DateTime<Utc>>,
+    pub camera_make: Option<String>,
+    pub camera_model: Option<String>,
+    pub lens_model: Option<String>,
+    pub iso: Option<u32>,
+    pub aperture: Option<f64>,
+    pub focal_length: Option<f64>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    /// 图片说明（ImageDescription）
+    pub caption: Option<String>,
+}
+
+/// 读取文件的 EXIF 拍摄信息，文件不含 EXIF 时返回 None
+pub fn extract_exif_metadata(path: &Path) -> Option<ExifMetadata> {
+    let file = fs::File::open(path).ok()?;
+    let mut bufreader = BufReader::new(file);
+
+    let exif = ExifReader::new().read_from_container(&mut bufreader).ok()?;
+
+    let metadata = ExifMetadata {
+        taken_date: read_taken_date(&exif),
+        camera_make: read_ascii(&exif, Tag::Make),
+        camera_model: read_ascii(&exif, Tag::Model),
+        lens_model: read_ascii(&exif, Tag::LensModel),
+        iso: exif
+            .get_field(Tag::PhotographicSensitivity, In::PRIMARY)
+            .and_then(|f| f.value.get_uint(0)),
+        aperture: read_rational(&exif, Tag::FNumber),
+        focal_length: read_rational(&exif, Tag::FocalLength),
+        latitude: read_coordinate(&exif, Tag::GPSLatitude, Tag::GPSLatitudeRef, b'S'),
+        longitude: read_coordinate(&exif, Tag::GPSLongitude, Tag::GPSLongitudeRef, b'W'),
+        caption: read_ascii(&exif, Tag::ImageDescription),
+    };
+
+    info!(
+        "EXIF 信息: 拍摄时间 {:?}, 相机 {:?}",
+        metadata.taken_date, metadata.camera_model
+    );
+    Some(metadata)
+}
+
+/// 解析 DateTimeOriginal，有 OffsetTimeOriginal 时按其时区换算，否则视为本地时间
+fn read_taken_date(exif: &Exif) -> Option<DateTime<Utc>> {
+    let raw = read_ascii(exif, Tag::DateTimeOriginal)?;
+    let naive = NaiveDateTime::parse_from_str(raw.trim(), "%Y:%m:%d %H:%M:%S").ok()?;
+
+    let offset = read_ascii(exif, Tag::OffsetTimeOriginal)
+        .and_then(|s| s.trim().parse::<FixedOffset>().ok());
+
+    match offset {
+        Some(offset) => offset
+            .from_local_datetime(&naive)
+            .single()
+            .map(|dt| dt.with_timezone(&Utc)),
+        None => Local
+            .from_local_datetime(&naive)
+            .earliest()
+            .map(|dt| dt.with_timezone(&Utc)),
+    }
+}
+
+/// 读取 ASCII 字段，去除结尾的空字符和空白
+fn read_ascii(exif: &Exif, tag: Tag) -> Option<String> {
+    let field = exif.get_field(tag, In::PRIMARY)?;
+    match &field.value {
+        Value::Ascii(values) => values
+            .first()
+            .map(|v| {
+                String::from_utf8_lossy(v)
+                    .trim_matches(char::from(0))
+                    .trim()
+                    .to_string()
+            })
+            .filter(|s| !s.is_empty()),
+        _ => None,
+    }
+}
+
+fn read_rational(exif: &Exif, tag: Tag) -> Option<f64> {
+    match &exif.get_field(tag, In::PRIMARY)?.value {
+        Value::Rational(values) => values.first().map(|r| r.to_f64()).filter(|v| v.is_finite()),
+        _ => None,
+    }
+}
+
+/// 将度/分/秒形式的 GPS 坐标转换为十进制度数，南纬和西经为负
+fn read_coordinate(exif: &Exif, tag: Tag, ref_tag: Tag, negative_ref: u8) -> Option<f64> {
+    let dms = match &exif.get_field(tag, In::PRIMARY)?.value {
+        Value::Rational(values) if values.len() >= 3 => values
+            .iter()
+            .take(3)
+            .map(|r| r.to_f64())
+            .collect::<Vec<_>>(),
+        _ => return None,
+    };
+    let degrees = dms[0] + dms[1] / 60.0 + dms[2] / 3600.0;
+    if !degrees.is_finite() {
+        return None;
+    }
+
+    let negative = exif
+        .get_field(ref_tag, In::PRIMARY)
+        .map(ref_is(negative_ref))
+        .unwrap_or(false);
+
+    Some(if negative { -degrees } else { degrees })
+}
+
+fn ref_is(expected: u8) -> impl Fn(&Field) -> bool {
+    move |field| match &field.value {
+        Value::Ascii(values) => values
+            .first()
+            .and_then(|v| v.first())
+            .map(|c| c.to_ascii_uppercase() == expected)
+            .unwrap_or(false),
+        _ => false,
+    }
+}
@@ -0,0 +1,98 @@
+/// 判定为同一人物的最小余弦相似度
+pub const SIMILARITY_THRESHOLD: f32 = 0.5;
+
+/// 新建人物所需的最少人脸数量，未达到的人脸保持未归属，等待后续聚类
+pub const MIN_CLUSTER_SIZE: usize = 2;
+
+/// 聚类计划，索引均对应传入的人脸列表
+#[derive(Debug, Default)]
+pub struct ClusterPlan {
+    /// (人脸索引, 已有人物索引)
+    pub assignments: Vec<(usize, usize)>,
+    /// 新的人物，每项为其包含的人脸索引
+    pub new_clusters: Vec<Vec<usize>>,
+}
+
+/// 计算两个向量的余弦相似度
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|v| v * v).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+/// 计算一组特征向量的中心，结果经过 L2 归一化
+pub fn centroid(embeddings: &[Vec<f32>]) -> Vec<f32> {
+    let Some(first) = embeddings.first() else {
+        return Vec::new();
+    };
+
+    let dimension = first.len();
+    let mut sum = vec![0.0; dimension];
+    for embedding in embeddings.iter().filter(|e| e.len() == dimension) {
+        sum.iter_mut().zip(embedding).for_each(|(s, v)| *s += v);
+    }
+    normalize(&mut sum);
+    sum
+}
+
+/// 增量聚类：人脸优先归入最相似的已有人物，其余人脸之间按顺序聚成新的人物
+///
+/// `people` 为已有人物的中心向量；与中心相似度不低于阈值的人脸直接归入，
+/// 剩余人脸依次加入最相似的新簇（簇中心随成员更新），少于 `MIN_CLUSTER_SIZE` 的簇被丢弃。
+pub fn plan_clusters(people: &[Vec<f32>], faces: &[Vec<f32>]) -> ClusterPlan {
+    let mut plan = ClusterPlan::default();
+    // 新簇的成员和未归一化的向量和
+    let mut clusters: Vec<(Vec<usize>, Vec<f32>)> = Vec::new();
+
+    for (index, embedding) in faces.iter().enumerate() {
+        if let Some(person) = best_match(people.iter(), embedding) {
+            plan.assignments.push((index, person));
+            continue;
+        }
+
+        match best_match(clusters.iter().map(|(_, sum)| sum), embedding) {
+            Some(cluster) => {
+                let (members, sum) = &mut clusters[cluster];
+                members.push(index);
+                sum.iter_mut().zip(embedding).for_each(|(s, v)| *s += v);
+            }
+            None => clusters.push((vec![index], embedding.clone())),
+        }
+    }
+
+    plan.new_clusters = clusters
+        .into_iter()
+        .map(|(members, _)| members)
+        .filter(|members| members.len() >= MIN_CLUSTER_SIZE)
+        .collect();
+    plan
+}
+
+/// 返回相似度最高且不低于阈值的候选索引
+fn best_match<'a>(
+    candidates: impl Iterator<Item = &'a Vec<f32>>,
+    embedding: &[f32],
+) -> Option<usize> {
+    candidates
+        .enumerate()
+        .map(|(index, candidate)| (index, cosine_similarity(candidate, embedding)))
+        .filter(|(_, similarity)| *similarity >= SIMILARITY_THRESHOLD)
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(index, _)| index)
+}
+
+fn normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|v| *v /= norm);
+    }
+}
@@ -0,0 +1,218 @@
+use std::path::Path;
+
+use image::{imageops::FilterType, DynamicImage, RgbImage};
+use tract_onnx::prelude::*;
+
+/// 人脸检测模型输入尺寸（宽, 高），对应 Ultra-Light-Fast-Generic-Face-Detector 的 RFB-320 模型
+const DETECTOR_INPUT_SIZE: (usize, usize) = (320, 240);
+
+/// 人脸特征模型输入边长，对应 ArcFace / MobileFaceNet 类模型的 112x112 输入
+const EMBEDDER_INPUT_SIZE: usize = 112;
+
+/// 检测置信度阈值
+const SCORE_THRESHOLD: f32 = 0.7;
+
+/// 非极大值抑制的 IoU 阈值
+const NMS_IOU_THRESHOLD: f32 = 0.3;
+
+/// 检测前将图片缩小到的最大边长，裁剪人脸时也使用该图片
+const MAX_ANALYSIS_SIZE: u32 = 1600;
+
+/// 裁剪人脸时在检测框四周额外保留的比例
+const CROP_MARGIN: f32 = 0.15;
+
+type OnnxModel = TypedRunnableModel<TypedModel>;
+
+/// 检测框 [x, y, 宽, 高] 及其置信度
+type Detection = ([f32; 4], f32);
+
+/// 检测到的人脸
+#[derive(Debug, Clone)]
+pub struct DetectedFace {
+    /// [x, y, 宽, 高]，为相对图片宽高的比例（0-1）
+    pub bbox: [f32; 4],
+    pub confidence: f32,
+    /// L2 归一化后的特征向量
+    pub embedding: Vec<f32>,
+}
+
+/// 基于 ONNX 模型的人脸检测与特征提取，只使用 CPU
+///
+/// 检测模型输入为 1x3x240x320 的 RGB 图像（(像素 - 127) / 128），输出为
+/// scores [1, N, 2] 和 boxes [1, N, 4]（相对坐标 x1, y1, x2, y2）；
+/// 特征模型输入为 1x3x112x112 的 RGB 图像（(像素 - 127.5) / 127.5），输出为 [1, D]。
+pub struct FaceAnalyzer {
+    detector: OnnxModel,
+    embedder: OnnxModel,
+}
+
+impl FaceAnalyzer {
+    /// 从本地文件加载检测模型和特征模型
+    pub fn load(
+        detector_path: &Path,
+        embedder_path: &Path,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let (width, height) = DETECTOR_INPUT_SIZE;
+        let detector = tract_onnx::onnx()
+            .model_for_path(detector_path)?
+            .with_input_fact(0, f32::fact([1, 3, height, width]).into())?
+            .into_optimized()?
+            .into_runnable()?;
+
+        let embedder = tract_onnx::onnx()
+            .model_for_path(embedder_path)?
+            .with_input_fact(
+                0,
+                f32::fact([1, 3, EMBEDDER_INPUT_SIZE, EMBEDDER_INPUT_SIZE]).into(),
+            )?
+            .into_optimized()?
+            .into_runnable()?;
+
+        Ok(Self { detector, embedder })
+    }
+
+    /// 检测图片中的人脸并提取特征
+    pub fn analyze(
+        &self,
+        image: &DynamicImage,
+    ) -> Result<Vec<DetectedFace>, Box<dyn std::error::Error>> {
+        let image = if image.width() > MAX_ANALYSIS_SIZE || image.height() > MAX_ANALYSIS_SIZE {
+            image
+                .thumbnail(MAX_ANALYSIS_SIZE, MAX_ANALYSIS_SIZE)
+                .to_rgb8()
+        } else {
+            image.to_rgb8()
+        };
+
+        let mut faces = Vec::new();
+        for (bbox, confidence) in self.detect(&image)? {
+            let embedding = self.embed(&image, &bbox)?;
+            faces.push(DetectedFace {
+                bbox,
+                confidence,
+                embedding,
+            });
+        }
+
+        Ok(faces)
+    }
+
+    fn detect(&self, image: &RgbImage) -> Result<Vec<Detection>, Box<dyn std::error::Error>> {
+        let (width, height) = DETECTOR_INPUT_SIZE;
+        let resized =
+            image::imageops::resize(image, width as u32, height as u32, FilterType::Triangle);
+        let input = to_tensor(&resized, 127.0, 128.0);
+
+        let outputs = self.detector.run(tvec!(input.into()))?;
+        if outputs.len() < 2 {
+            return Err(format!("人脸检测模型应输出 2 个张量，实际为 {}", outputs.len()).into());
+        }
+        let scores = outputs[0].to_array_view::<f32>()?;
+        let boxes = outputs[1].to_array_view::<f32>()?;
+
+        // 分数为 [1, N, 2]（背景、人脸），边框为 [1, N, 4]（归一化的左上和右下坐标）
+        let count = match (scores.shape(), boxes.shape()) {
+            ([1, n, s], [1, m, b]) if n == m && *s >= 2 && *b >= 4 => *n,
+            (scores, boxes) => {
+                return Err(
+                    format!("不支持的人脸检测模型输出形状: {:?} 和 {:?}", scores, boxes).into(),
+                )
+            }
+        };
+        let mut candidates = Vec::new();
+        for i in 0..count {
+            let score = scores[[0, i, 1]];
+            if score < SCORE_THRESHOLD {
+                continue;
+            }
+            let x1 = boxes[[0, i, 0]].clamp(0.0, 1.0);
+            let y1 = boxes[[0, i, 1]].clamp(0.0, 1.0);
+            let x2 = boxes[[0, i, 2]].clamp(0.0, 1.0);
+            let y2 = boxes[[0, i, 3]].clamp(0.0, 1.0);
+            if x2 > x1 && y2 > y1 {
+                candidates.push(([x1, y1, x2 - x1, y2 - y1], score));
+            }
+        }
+
+        Ok(non_max_suppression(candidates))
+    }
+
+    fn embed(
+        &self,
+        image: &RgbImage,
+        bbox: &[f32; 4],
+    ) -> Result<Vec<f32>, Box<dyn std::error::Error>> {
+        let face = crop_face(image, bbox);
+        let resized = image::imageops::resize(
+            &face,
+            EMBEDDER_INPUT_SIZE as u32,
+            EMBEDDER_INPUT_SIZE as u32,
+            FilterType::Triangle,
+        );
+        let input = to_tensor(&resized, 127.5, 127.5);
+
+        let outputs = self.embedder.run(tvec!(input.into()))?;
+        let mut embedding: Vec<f32> = outputs[0].to_array_view::<f32>()?.iter().copied().collect();
+
+        let norm = embedding.iter().map(|v| v * v).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            embedding.iter_mut().for_each(|v| *v /= norm);
+        }
+        Ok(embedding)
+    }
+}
+
+/// 将 RGB 图像转换为 NCHW 张量
+fn to_tensor(image: &RgbImage, mean: f32, scale: f32) -> Tensor {
+    let (width, height) = (image.width() as usize, image.height() as usize);
+    tract_ndarray::Array4::from_shape_fn((1, 3, height, width), |(_, c, y, x)| {
+        (image.get_pixel(x as u32, y as u32)[c] as f32 - mean) / scale
+    })
+    .into()
+}
+
+/// 以检测框为中心裁剪正方形区域，四周保留一定边距
+fn crop_face(image: &RgbImage, bbox: &[f32; 4]) -> RgbImage {
+    let (image_width, image_height) = (image.width() as f32, image.height() as f32);
+    let center_x = (bbox[0] + bbox[2] / 2.0) * image_width;
+    let center_y = (bbox[1] + bbox[3] / 2.0) * image_height;
+    let side = (bbox[2] * image_width).max(bbox[3] * image_height) * (1.0 + CROP_MARGIN * 2.0);
+
+    let x = (center_x - side / 2.0).clamp(0.0, image_width - 1.0) as u32;
+    let y = (center_y - side / 2.0).clamp(0.0, image_height - 1.0) as u32;
+    let width = (side as u32).clamp(1, image.width() - x);
+    let height = (side as u32).clamp(1, image.height() - y);
+
+    image::imageops::crop_imm(image, x, y, width, height).to_image()
+}
+
+/// 按置信度从高到低保留互不重叠的检测框
+fn non_max_suppression(mut candidates: Vec<Detection>) -> Vec<Detection> {
+    candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    let mut kept: Vec<Detection> = Vec::new();
+    for candidate in candidates {
+        if kept
+            .iter()
+            .all(|(bbox, _)| iou(bbox, &candidate.0) < NMS_IOU_THRESHOLD)
+        {
+            kept.push(candidate);
+        }
+    }
+    kept
+}
+
+fn iou(a: &[f32; 4], b: &[f32; 4]) -> f32 {
+    let left = a[0].max(b[0]);
+    let top = a[1].max(b[1]);
+    let right = (a[0] + a[2]).min(b[0] + b[2]);
+    let bottom = (a[1] + a[3]).min(b[1] + b[3]);
+
+    let intersection = (right - left).max(0.0) * (bottom - top).max(0.0);
+    let union = a[2] * a[3] + b[2] * b[3] - intersection;
+    if union <= 0.0 {
+        0.0
+    } else {
+        intersection / union
+    }
+}
@@ -0,0 +1,183 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use log::error;
+use sha2::{Digest, Sha256};
+
+/// 移动文件，目标所在目录不存在时自动创建
+///
+/// 优先使用重命名；跨磁盘等无法重命名的情况下复制后删除原文件，复制失败时不删除原文件。
+/// 目标已存在时返回错误，不会覆盖。
+pub fn move_file(from: &Path, to: &Path) -> io::Result<()> {
+    if to.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("目标文件已存在: {}", to.display()),
+        ));
+    }
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+
+    fs::copy(from, to)?;
+    if let Err(e) = fs::remove_file(from) {
+        let _ = fs::remove_file(to);
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// 移动一组文件，任何一步失败时将已移动的文件移回原位置并返回错误
+///
+/// 先把所有文件重命名为原目录中的隐藏临时文件，再逐个移到目标位置，
+/// 因此目标可以是同一批中其他文件的原路径，例如互换两个文件名。目标已存在时失败。
+pub fn move_files_atomically(moves: &[(PathBuf, PathBuf)]) -> io::Result<()> {
+    let temps: Vec<PathBuf> = moves.iter().map(|(from, _)| staging_path(from)).collect();
+    let mut staged = 0;
+    let mut placed = 0;
+
+    let result = (|| {
+        for ((from, _), temp) in moves.iter().zip(&temps) {
+            fs::rename(from, temp)?;
+            staged += 1;
+        }
+        for ((_, to), temp) in moves.iter().zip(&temps) {
+            move_file(temp, to)?;
+            placed += 1;
+        }
+        Ok(())
+    })();
+
+    if result.is_err() {
+        for ((_, to), temp) in moves.iter().zip(&temps).take(placed).rev() {
+            if let Err(e) = move_file(to, temp) {
+                error!("回滚文件移动失败 {}: {}", to.display(), e);
+            }
+        }
+        for ((from, _), temp) in moves.iter().zip(&temps).take(staged).rev() {
+            if let Err(e) = fs::rename(temp, from) {
+                error!("回滚文件移动失败 {}: {}", from.display(), e);
+            }
+        }
+    }
+    result
+}
+
+/// 批量移动时使用的临时文件，与原文件在同一目录中以便直接重命名
+fn staging_path(path: &Path) -> PathBuf {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    path.with_file_name(format!(".{}.moving", file_name))
+}
+
+/// 复制文件并校验内容哈希，目标所在目录不存在时自动创建
+///
+/// 先复制到同一目录下的隐藏临时文件，哈希与 `expected_hash` 一致后再重命名为目标文件，
+/// 校验失败时删除临时文件，媒体库中不会出现不完整的文件。复制后的文件保留原文件的修改时间。
+/// 目标已存在时返回错误，不会覆盖。
+pub fn copy_file_verified(from: &Path, to: &Path, expected_hash: &str) -> io::Result<()> {
+    if to.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("目标文件已存在: {}", to.display()),
+        ));
+    }
+    let parent = to.parent().unwrap_or_else(|| Path::new("."));
+    fs::create_dir_all(parent)?;
+
+    let file_name = to
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let temp = parent.join(format!(".{}.part", file_name));
+
+    let result = (|| {
+        fs::copy(from, &temp)?;
+        if let Ok(modified) = fs::metadata(from).and_then(|metadata| metadata.modified()) {
+            fs::File::options()
+                .write(true)
+                .open(&temp)?
+                .set_modified(modified)?;
+        }
+
+        let hash = file_sha256(&temp)?;
+        if hash != expected_hash {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("复制后的文件内容不一致: {}", to.display()),
+            ));
+        }
+        if to.exists() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("目标文件已存在: {}", to.display()),
+            ));
+        }
+        fs::rename(&temp, to)
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result
+}
+
+/// 文件名后加编号的路径，编号为 0 时返回原路径
+pub fn numbered_path(path: &Path, number: usize) -> PathBuf {
+    if number == 0 {
+        return path.to_path_buf();
+    }
+
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    match path.extension() {
+        Some(ext) => path.with_file_name(format!("{}-{}.{}", stem, number, ext.to_string_lossy())),
+        None => path.with_file_name(format!("{}-{}", stem, number)),
+    }
+}
+
+/// 计算文件内容的 SHA-256，返回十六进制字符串
+pub fn file_sha256(path: &Path) -> io::Result<String> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)?;
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// 递归列出目录下的所有文件，跳过以 `.` 开头的隐藏文件和目录，无法读取的目录被忽略
+pub fn walk_files(root: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut dirs = vec![root.to_path_buf()];
+
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            let path = entry.path();
+            match entry.file_type() {
+                Ok(file_type) if file_type.is_dir() => dirs.push(path),
+                Ok(file_type) if file_type.is_file() => files.push(path),
+                _ => {}
+            }
+        }
+    }
+
+    files.sort();
+    files
+}
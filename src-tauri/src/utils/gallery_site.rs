@@ -0,0 +1,517 @@
+use serde_json::json;
+
+/// 缩略图的长边像素，依次用于普通屏幕和高分辨率屏幕
+pub const THUMBNAIL_SIZES: [u32; 2] = [360, 720];
+
+/// 网站中的一张照片
+pub struct GalleryPhoto {
+    /// 文件名中使用的编号，如 `0001`
+    pub key: String,
+    /// 替代文本，使用原文件名
+    pub name: String,
+    pub caption: Option<String>,
+    /// 拍摄参数，如 `Canon EOS R5 · 50mm · f/2.8 · ISO 100`
+    pub exif: Option<String>,
+    pub location: Option<(f64, f64)>,
+}
+
+impl GalleryPhoto {
+    pub fn image_path(&self) -> String {
+        format!("images/{}.jpg", self.key)
+    }
+
+    pub fn thumbnail_path(&self, size: u32) -> String {
+        format!("thumbs/{}-{}.jpg", self.key, size)
+    }
+
+    pub fn page_path(&self) -> String {
+        format!("photos/{}.html", self.key)
+    }
+}
+
+/// 所有页面共用的样式表
+pub const STYLESHEET: &str = r#"* { box-sizing: border-box; }
+body { margin: 0; font-family: system-ui, -apple-system, "Segoe UI", sans-serif; background: #111; color: #eee; }
+a { color: inherit; }
+header { display: flex; align-items: baseline; gap: 1rem; padding: 1rem 1.5rem; }
+header h1 { margin: 0; font-size: 1.4rem; font-weight: 600; }
+header a { opacity: .7; text-decoration: none; }
+.grid { display: grid; grid-template-columns: repeat(auto-fill, minmax(180px, 1fr)); gap: 6px; padding: 0 1.5rem; }
+.grid a { display: block; aspect-ratio: 1; overflow: hidden; background: #222; }
+.grid img { width: 100%; height: 100%; object-fit: cover; display: block; }
+nav.pages { display: flex; justify-content: center; gap: .5rem; padding: 1.5rem; }
+nav.pages a, nav.pages span { padding: .3rem .7rem; border-radius: 4px; text-decoration: none; }
+nav.pages span { background: #333; }
+.photo { display: flex; flex-direction: column; align-items: center; min-height: 100vh; padding: 1rem; }
+.photo img { max-width: 100%; max-height: calc(100vh - 8rem); object-fit: contain; }
+.photo nav { display: flex; gap: 1.5rem; padding: .75rem; }
+.caption { text-align: center; max-width: 60rem; }
+.caption p { margin: .25rem 0; }
+.exif { opacity: .6; font-size: .85rem; }
+#map { position: absolute; top: 4rem; bottom: 0; left: 0; right: 0; overflow: hidden; background: #1b2633; touch-action: none; cursor: grab; }
+#map canvas, #map .layer { position: absolute; top: 0; left: 0; }
+#map .layer img { position: absolute; width: 256px; height: 256px; }
+#map .marker { position: absolute; width: 14px; height: 14px; margin: -7px 0 0 -7px; padding: 0; border: 2px solid #fff; border-radius: 50%; background: #e8603c; cursor: pointer; }
+#map .popup { position: absolute; transform: translate(-50%, calc(-100% - 12px)); padding: 4px; border-radius: 4px; background: #222; }
+#map .popup img { display: block; width: 160px; }
+#map .zoom { position: absolute; top: 10px; left: 10px; display: flex; flex-direction: column; gap: 4px; }
+#map .zoom button { width: 32px; height: 32px; font-size: 1.2rem; color: #eee; background: #222; border: 1px solid #444; border-radius: 4px; cursor: pointer; }
+#map .attribution { position: absolute; right: 0; bottom: 0; padding: 2px 6px; font-size: .75rem; background: rgba(0, 0, 0, .6); }
+"#;
+
+/// 在线底图的图块地址，仅在导出时启用在线底图才会使用
+const OSM_TILE_URL: &str = "https://tile.openstreetmap.org/{z}/{x}/{y}.png";
+
+/// 地图页使用的脚本，以 Web 墨卡托投影绘制经纬网、照片位置和可选的在线底图
+///
+/// 脚本随网站一起导出，不依赖任何外部库；未启用在线底图时地图完全离线可用。
+pub const MAP_SCRIPT: &str = r#"(function () {
+  "use strict";
+  var TILE_SIZE = 256, MIN_ZOOM = 1, MAX_ZOOM = 18, FIT_MAX_ZOOM = 14, FIT_PADDING = 40;
+  var container = document.getElementById("map");
+  var data = JSON.parse(document.getElementById("map-data").textContent);
+  var canvas = document.createElement("canvas");
+  var tileLayer = document.createElement("div");
+  var markerLayer = document.createElement("div");
+  tileLayer.className = markerLayer.className = "layer";
+  container.append(canvas, tileLayer, markerLayer);
+
+  var zoom = MIN_ZOOM, center = { x: 0, y: 0 }, tiles = {}, popup = null;
+
+  function worldSize(z) { return TILE_SIZE * Math.pow(2, z); }
+
+  function project(lat, lng, z) {
+    var sin = Math.sin(Math.max(-85.0511, Math.min(85.0511, lat)) * Math.PI / 180);
+    var size = worldSize(z);
+    return {
+      x: (lng + 180) / 360 * size,
+      y: (0.5 - Math.log((1 + sin) / (1 - sin)) / (4 * Math.PI)) * size
+    };
+  }
+
+  function latitudeAt(y, z) {
+    var n = Math.PI * (1 - 2 * y / worldSize(z));
+    return Math.atan(Math.sinh(n)) * 180 / Math.PI;
+  }
+
+  function viewport() {
+    var width = container.clientWidth, height = container.clientHeight;
+    return { width: width, height: height, left: center.x - width / 2, top: center.y - height / 2 };
+  }
+
+  function drawGraticule(view) {
+    canvas.width = view.width;
+    canvas.height = view.height;
+    var context = canvas.getContext("2d");
+    context.strokeStyle = "rgba(255, 255, 255, .15)";
+    context.fillStyle = "rgba(255, 255, 255, .4)";
+    context.font = "11px system-ui, sans-serif";
+    var step = zoom < 3 ? 30 : zoom < 5 ? 10 : zoom < 8 ? 2 : zoom < 11 ? 0.5 : 0.1;
+    var size = worldSize(zoom);
+    var west = view.left / size * 360 - 180, east = (view.left + view.width) / size * 360 - 180;
+    for (var lng = Math.ceil(west / step) * step; lng <= east; lng += step) {
+      var x = Math.round(project(0, lng, zoom).x - view.left) + 0.5;
+      context.beginPath();
+      context.moveTo(x, 0);
+      context.lineTo(x, view.height);
+      context.stroke();
+      context.fillText(+lng.toFixed(1) + "°", x + 3, view.height - 4);
+    }
+    var north = Math.min(85, latitudeAt(Math.max(view.top, 0), zoom));
+    var south = Math.max(-85, latitudeAt(Math.min(view.top + view.height, size), zoom));
+    for (var lat = Math.ceil(south / step) * step; lat <= north; lat += step) {
+      var y = Math.round(project(lat, 0, zoom).y - view.top) + 0.5;
+      context.beginPath();
+      context.moveTo(0, y);
+      context.lineTo(view.width, y);
+      context.stroke();
+      context.fillText(+lat.toFixed(1) + "°", 4, y - 3);
+    }
+  }
+
+  function drawTiles(view) {
+    if (!data.tiles) {
+      return;
+    }
+    var count = Math.pow(2, zoom), visible = {};
+    var firstX = Math.floor(view.left / TILE_SIZE), lastX = Math.floor((view.left + view.width) / TILE_SIZE);
+    var firstY = Math.max(0, Math.floor(view.top / TILE_SIZE));
+    var lastY = Math.min(count - 1, Math.floor((view.top + view.height) / TILE_SIZE));
+    for (var tx = firstX; tx <= lastX; tx++) {
+      for (var ty = firstY; ty <= lastY; ty++) {
+        var key = zoom + "/" + tx + "/" + ty;
+        var tile = tiles[key];
+        if (!tile) {
+          tile = document.createElement("img");
+          tile.alt = "";
+          tile.src = data.tiles.replace("{z}", zoom)
+            .replace("{x}", ((tx % count) + count) % count).replace("{y}", ty);
+          tileLayer.appendChild(tile);
+          tiles[key] = tile;
+        }
+        tile.style.left = (tx * TILE_SIZE - view.left) + "px";
+        tile.style.top = (ty * TILE_SIZE - view.top) + "px";
+        visible[key] = true;
+      }
+    }
+    Object.keys(tiles).forEach(function (key) {
+      if (!visible[key]) {
+        tiles[key].remove();
+        delete tiles[key];
+      }
+    });
+  }
+
+  function render() {
+    var view = viewport();
+    drawGraticule(view);
+    drawTiles(view);
+    data.markers.forEach(function (marker) {
+      var point = project(marker.lat, marker.lng, zoom);
+      marker.element.style.left = (point.x - view.left) + "px";
+      marker.element.style.top = (point.y - view.top) + "px";
+    });
+    if (popup) {
+      popup.element.style.left = popup.marker.element.style.left;
+      popup.element.style.top = popup.marker.element.style.top;
+    }
+  }
+
+  function closePopup() {
+    if (popup) {
+      popup.element.remove();
+      popup = null;
+    }
+  }
+
+  function openPopup(marker) {
+    closePopup();
+    var link = document.createElement("a");
+    link.className = "popup";
+    link.href = marker.page;
+    var image = document.createElement("img");
+    image.src = marker.thumbnail;
+    image.alt = marker.name;
+    link.appendChild(image);
+    markerLayer.appendChild(link);
+    popup = { marker: marker, element: link };
+    render();
+  }
+
+  function zoomTo(next, focusX, focusY) {
+    next = Math.max(MIN_ZOOM, Math.min(MAX_ZOOM, next));
+    if (next === zoom) {
+      return;
+    }
+    var view = viewport(), scale = Math.pow(2, next - zoom);
+    var offsetX = focusX - view.width / 2, offsetY = focusY - view.height / 2;
+    center.x = (center.x + offsetX) * scale - offsetX;
+    center.y = (center.y + offsetY) * scale - offsetY;
+    zoom = next;
+    render();
+  }
+
+  function fitBounds() {
+    var width = container.clientWidth - 2 * FIT_PADDING, height = container.clientHeight - 2 * FIT_PADDING;
+    for (zoom = FIT_MAX_ZOOM; zoom > MIN_ZOOM; zoom--) {
+      var points = data.markers.map(function (marker) { return project(marker.lat, marker.lng, zoom); });
+      var xs = points.map(function (point) { return point.x; });
+      var ys = points.map(function (point) { return point.y; });
+      if (Math.max.apply(null, xs) - Math.min.apply(null, xs) <= width
+          && Math.max.apply(null, ys) - Math.min.apply(null, ys) <= height) {
+        break;
+      }
+    }
+    var all = data.markers.map(function (marker) { return project(marker.lat, marker.lng, zoom); });
+    center.x = (Math.min.apply(null, all.map(function (p) { return p.x; })) + Math.max.apply(null, all.map(function (p) { return p.x; }))) / 2;
+    center.y = (Math.min.apply(null, all.map(function (p) { return p.y; })) + Math.max.apply(null, all.map(function (p) { return p.y; }))) / 2;
+  }
+
+  data.markers.forEach(function (marker) {
+    var button = document.createElement("button");
+    button.className = "marker";
+    button.title = marker.name;
+    button.addEventListener("click", function (event) {
+      event.stopPropagation();
+      openPopup(marker);
+    });
+    markerLayer.appendChild(button);
+    marker.element = button;
+  });
+
+  var controls = document.createElement("div");
+  controls.className = "zoom";
+  [["+", 1], ["−", -1]].forEach(function (control) {
+    var button = document.createElement("button");
+    button.textContent = control[0];
+    button.addEventListener("click", function (event) {
+      event.stopPropagation();
+      zoomTo(zoom + control[1], container.clientWidth / 2, container.clientHeight / 2);
+    });
+    button.addEventListener("pointerdown", function (event) { event.stopPropagation(); });
+    controls.appendChild(button);
+  });
+  container.appendChild(controls);
+
+  if (data.tiles) {
+    var attribution = document.createElement("div");
+    attribution.className = "attribution";
+    attribution.innerHTML = data.attribution;
+    container.appendChild(attribution);
+  }
+
+  var drag = null;
+  container.addEventListener("pointerdown", function (event) {
+    if (event.target.closest(".marker, .popup")) {
+      return;
+    }
+    drag = { x: event.clientX, y: event.clientY, moved: false };
+    container.setPointerCapture(event.pointerId);
+  });
+  container.addEventListener("pointermove", function (event) {
+    if (!drag) {
+      return;
+    }
+    var dx = event.clientX - drag.x, dy = event.clientY - drag.y;
+    drag.moved = drag.moved || Math.abs(dx) + Math.abs(dy) > 3;
+    center.x -= dx;
+    center.y -= dy;
+    drag.x = event.clientX;
+    drag.y = event.clientY;
+    render();
+  });
+  container.addEventListener("pointerup", function () {
+    if (drag && !drag.moved) {
+      closePopup();
+    }
+    drag = null;
+  });
+  container.addEventListener("wheel", function (event) {
+    event.preventDefault();
+    var rect = container.getBoundingClientRect();
+    zoomTo(zoom + (event.deltaY < 0 ? 1 : -1), event.clientX - rect.left, event.clientY - rect.top);
+  }, { passive: false });
+  window.addEventListener("resize", render);
+
+  fitBounds();
+  render();
+})();
+"#;
+
+/// 第 `page` 个索引页（从 0 开始）的文件名
+pub fn index_page_path(page: usize) -> String {
+    if page == 0 {
+        "index.html".to_string()
+    } else {
+        format!("page-{}.html", page + 1)
+    }
+}
+
+/// 生成索引页，显示当前页的缩略图网格和分页链接
+pub fn render_index_page(
+    title: &str,
+    photos: &[GalleryPhoto],
+    page: usize,
+    page_size: usize,
+    has_map: bool,
+) -> String {
+    let page_count = page_count(photos.len(), page_size);
+    let start = page * page_size;
+    let end = (start + page_size).min(photos.len());
+
+    let mut items = String::new();
+    for photo in &photos[start.min(end)..end] {
+        let srcset = THUMBNAIL_SIZES
+            .iter()
+            .map(|size| format!("{} {}w", photo.thumbnail_path(*size), size))
+            .collect::<Vec<_>>()
+            .join(", ");
+        items.push_str(&format!(
+            r#"<a href="{page}"><img src="{src}" srcset="{srcset}" sizes="(max-width: 600px) 50vw, 240px" alt="{alt}" loading="lazy"></a>
+"#,
+            page = photo.page_path(),
+            src = photo.thumbnail_path(THUMBNAIL_SIZES[0]),
+            srcset = srcset,
+            alt = escape_html(&photo.name),
+        ));
+    }
+
+    let mut pages = String::new();
+    if page_count > 1 {
+        pages.push_str(r#"<nav class="pages">"#);
+        for index in 0..page_count {
+            if index == page {
+                pages.push_str(&format!("<span>{}</span>", index + 1));
+            } else {
+                pages.push_str(&format!(
+                    r#"<a href="{}">{}</a>"#,
+                    index_page_path(index),
+                    index + 1
+                ));
+            }
+        }
+        pages.push_str("</nav>\n");
+    }
+
+    let map_link = if has_map {
+        r#"<a href="map.html">地图</a>"#
+    } else {
+        ""
+    };
+    document(
+        title,
+        "",
+        &format!(
+            r#"<header><h1>{title}</h1>{map_link}</header>
+<main class="grid">
+{items}</main>
+{pages}"#,
+            title = escape_html(title),
+            map_link = map_link,
+            items = items,
+            pages = pages,
+        ),
+    )
+}
+
+/// 生成单张照片的浏览页，支持左右方向键切换和 Esc 返回索引页
+pub fn render_photo_page(
+    title: &str,
+    photos: &[GalleryPhoto],
+    index: usize,
+    page_size: usize,
+) -> String {
+    let photo = &photos[index];
+    let back = format!("../{}", index_page_path(index / page_size.max(1)));
+    let previous = index
+        .checked_sub(1)
+        .map(|previous| format!("{}.html", photos[previous].key));
+    let next = photos
+        .get(index + 1)
+        .map(|next| format!("{}.html", next.key));
+
+    let link = |href: &Option<String>, label: &str, id: &str| match href {
+        Some(href) => format!(r#"<a id="{}" href="{}">{}</a>"#, id, href, label),
+        None => String::new(),
+    };
+    let mut caption = String::new();
+    if let Some(text) = &photo.caption {
+        caption.push_str(&format!("<p>{}</p>", escape_html(text)));
+    }
+    if let Some(exif) = &photo.exif {
+        caption.push_str(&format!(r#"<p class="exif">{}</p>"#, escape_html(exif)));
+    }
+
+    document(
+        &format!("{} - {}", photo.name, title),
+        "../",
+        &format!(
+            r#"<main class="photo">
+<nav>{previous}<a id="back" href="{back}">{title}</a>{next}</nav>
+<img src="../{image}" alt="{alt}">
+<div class="caption">{caption}</div>
+</main>
+<script>
+document.addEventListener("keydown", function (event) {{
+  var target = {{ ArrowLeft: "previous", ArrowRight: "next", Escape: "back" }}[event.key];
+  var link = target && document.getElementById(target);
+  if (link) location.href = link.href;
+}});
+</script>
+"#,
+            previous = link(&previous, "‹ 上一张", "previous"),
+            next = link(&next, "下一张 ›", "next"),
+            back = back,
+            title = escape_html(title),
+            image = photo.image_path(),
+            alt = escape_html(&photo.name),
+            caption = caption,
+        ),
+    )
+}
+
+/// 生成显示拍摄地点的地图页，没有照片带位置时返回 None
+///
+/// 地图由随网站导出的 `map.js` 绘制，默认只显示经纬网和照片位置，完全离线可用；
+/// `online_tiles` 为 true 时叠加 OpenStreetMap 在线底图，浏览时需要联网。
+pub fn render_map_page(title: &str, photos: &[GalleryPhoto], online_tiles: bool) -> Option<String> {
+    let markers: Vec<_> = photos
+        .iter()
+        .filter_map(|photo| {
+            let (latitude, longitude) = photo.location?;
+            Some(json!({
+                "lat": latitude,
+                "lng": longitude,
+                "name": photo.name,
+                "page": photo.page_path(),
+                "thumbnail": photo.thumbnail_path(THUMBNAIL_SIZES[0]),
+            }))
+        })
+        .collect();
+    if markers.is_empty() {
+        return None;
+    }
+    let data = json!({
+        "markers": markers,
+        "tiles": online_tiles.then_some(OSM_TILE_URL),
+        "attribution": "&copy; OpenStreetMap contributors",
+    });
+    // 防止数据中的 `</script>` 提前结束脚本
+    let data = serde_json::to_string(&data).ok()?.replace("</", "<\\/");
+
+    Some(document(
+        &format!("地图 - {}", title),
+        "",
+        &format!(
+            r#"<header><h1>{title}</h1><a href="index.html">照片</a></header>
+<div id="map"></div>
+<script type="application/json" id="map-data">{data}</script>
+<script src="map.js"></script>
+"#,
+            title = escape_html(title),
+            data = data,
+        ),
+    ))
+}
+
+/// 索引页数量，没有照片时也生成一页
+pub fn page_count(photos: usize, page_size: usize) -> usize {
+    photos.div_ceil(page_size.max(1)).max(1)
+}
+
+/// 转义 HTML 文本和属性值中的特殊字符
+pub fn escape_html(text: &str) -> String {
+    let mut html = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            '\'' => html.push_str("&#39;"),
+            c => html.push(c),
+        }
+    }
+    html
+}
+
+/// 完整的 HTML 文档，`root` 为页面到网站根目录的相对路径
+fn document(title: &str, root: &str, body: &str) -> String {
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="zh-CN">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{title}</title>
+<link rel="stylesheet" href="{root}style.css">
+</head>
+<body>
+{body}</body>
+</html>
+"#,
+        title = escape_html(title),
+        root = root,
+        body = body,
+    )
+}
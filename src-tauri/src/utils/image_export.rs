@@ -0,0 +1,236 @@
+use std::{fs, io::Cursor, path::Path};
+
+use image::{
+    codecs::{avif::AvifEncoder, jpeg::JpegEncoder, png::PngEncoder, webp::WebPEncoder},
+    imageops::FilterType,
+    DynamicImage, ImageDecoder, ImageEncoder, ImageReader,
+};
+use log::warn;
+use moxcms::{ColorProfile, DataColorSpace, Layout, TransformOptions};
+
+use crate::models::export::{ExportFormat, ExportSettings, MetadataPolicy};
+use crate::utils::metadata_filter::{filter_exif, strip_jpeg_metadata};
+use crate::utils::watermark::{add_border, apply_watermark};
+
+/// 未指定质量时 JPEG 和 AVIF 使用的质量
+const DEFAULT_QUALITY: u8 = 90;
+
+/// AVIF 编码速度（1-10），越大越快、压缩率越低
+const AVIF_SPEED: u8 = 6;
+
+/// 解码后的图片及需要写入导出文件的元数据
+struct DecodedImage {
+    image: DynamicImage,
+    icc_profile: Option<Vec<u8>>,
+    exif: Option<Vec<u8>>,
+}
+
+/// 按导出设置生成文件内容，原图且保留全部元数据时由调用方直接复制文件
+///
+/// 原图只能移除 JPEG 的元数据，其他格式需选择转换格式后才能移除；水印和边框只能用于转换格式。
+pub fn render_export(source: &Path, settings: &ExportSettings) -> Result<Vec<u8>, String> {
+    let extension = source
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    let format = settings.format;
+    if format == ExportFormat::Original {
+        if settings.watermark.is_some() || settings.border.is_some() {
+            return Err("原图无法添加水印或边框，请选择转换格式".to_string());
+        }
+        let data = fs::read(source).map_err(|e| format!("读取文件失败: {}", e))?;
+        return match (settings.metadata_policy, extension.as_str()) {
+            (MetadataPolicy::KeepAll, _) => Ok(data),
+            (policy, "jpg" | "jpeg") => strip_jpeg_metadata(&data, policy),
+            _ => Err("该格式的原图无法移除元数据，请选择转换格式".to_string()),
+        };
+    }
+
+    let mut decoded = decode_image(source)?;
+    if let Some(long_edge) = settings.long_edge.filter(|edge| *edge > 0) {
+        if decoded.image.width().max(decoded.image.height()) > long_edge {
+            decoded.image = decoded
+                .image
+                .resize(long_edge, long_edge, FilterType::Lanczos3);
+        }
+    }
+    if settings.convert_to_srgb {
+        if let Some(icc_profile) = &decoded.icc_profile {
+            match convert_to_srgb(&decoded.image, icc_profile) {
+                Ok(Some(image)) => {
+                    decoded.image = image;
+                    decoded.icc_profile = None;
+                }
+                Ok(None) => {}
+                Err(e) => warn!("转换为 sRGB 失败 {}: {}", source.display(), e),
+            }
+        }
+    }
+
+    if let Some(watermark) = &settings.watermark {
+        let has_alpha = decoded.image.color().has_alpha();
+        let mut canvas = decoded.image.to_rgba8();
+        apply_watermark(&mut canvas, watermark)?;
+        decoded.image = if has_alpha {
+            DynamicImage::ImageRgba8(canvas)
+        } else {
+            DynamicImage::ImageRgb8(DynamicImage::ImageRgba8(canvas).to_rgb8())
+        };
+    }
+    if let Some(border) = &settings.border {
+        decoded.image = add_border(&decoded.image, border)?;
+    }
+
+    // 图片已按 EXIF 方向旋转，写入的方向需改为正常
+    let exif = decoded
+        .exif
+        .as_deref()
+        .and_then(|exif| filter_exif(exif, settings.metadata_policy, true));
+    encode_image(
+        &decoded.image,
+        format,
+        settings.quality.unwrap_or(DEFAULT_QUALITY).clamp(1, 100),
+        decoded.icc_profile,
+        exif,
+    )
+}
+
+/// 解码图片并按 EXIF 方向旋转，不读取元数据
+pub fn load_oriented_image(source: &Path) -> Result<DynamicImage, String> {
+    decode_image(source).map(|decoded| decoded.image)
+}
+
+/// 解码图片并按 EXIF 方向旋转，同时读取色彩配置文件和 EXIF
+fn decode_image(source: &Path) -> Result<DecodedImage, String> {
+    let mut decoder = ImageReader::open(source)
+        .and_then(|reader| reader.with_guessed_format())
+        .map_err(|e| format!("读取文件失败: {}", e))?
+        .into_decoder()
+        .map_err(|e| format!("无法解码图片: {}", e))?;
+
+    let icc_profile = decoder.icc_profile().ok().flatten();
+    let exif = decoder.exif_metadata().ok().flatten();
+    let orientation = decoder.orientation().ok();
+    let mut image =
+        DynamicImage::from_decoder(decoder).map_err(|e| format!("无法解码图片: {}", e))?;
+    if let Some(orientation) = orientation {
+        image.apply_orientation(orientation);
+    }
+
+    Ok(DecodedImage {
+        image,
+        icc_profile,
+        exif,
+    })
+}
+
+/// 按色彩配置文件转换为 sRGB，配置文件不是 RGB 色彩空间时返回 None
+fn convert_to_srgb(
+    image: &DynamicImage,
+    icc_profile: &[u8],
+) -> Result<Option<DynamicImage>, String> {
+    let source = ColorProfile::new_from_slice(icc_profile).map_err(|e| e.to_string())?;
+    if source.color_space != DataColorSpace::Rgb {
+        return Ok(None);
+    }
+    let srgb = ColorProfile::new_srgb();
+
+    if image.color().has_alpha() {
+        let mut pixels = image.to_rgba8();
+        let transform = source
+            .create_transform_8bit(
+                Layout::Rgba,
+                &srgb,
+                Layout::Rgba,
+                TransformOptions::default(),
+            )
+            .map_err(|e| e.to_string())?;
+        let input = pixels.as_raw().clone();
+        transform
+            .transform(&input, &mut pixels)
+            .map_err(|e| e.to_string())?;
+        Ok(Some(DynamicImage::ImageRgba8(pixels)))
+    } else {
+        let mut pixels = image.to_rgb8();
+        let transform = source
+            .create_transform_8bit(Layout::Rgb, &srgb, Layout::Rgb, TransformOptions::default())
+            .map_err(|e| e.to_string())?;
+        let input = pixels.as_raw().clone();
+        transform
+            .transform(&input, &mut pixels)
+            .map_err(|e| e.to_string())?;
+        Ok(Some(DynamicImage::ImageRgb8(pixels)))
+    }
+}
+
+/// 编码为指定格式
+fn encode_image(
+    image: &DynamicImage,
+    format: ExportFormat,
+    quality: u8,
+    icc_profile: Option<Vec<u8>>,
+    exif: Option<Vec<u8>>,
+) -> Result<Vec<u8>, String> {
+    let mut buf = Cursor::new(Vec::new());
+
+    match format {
+        ExportFormat::Jpeg => {
+            // JPEG 不支持透明度
+            let image = DynamicImage::ImageRgb8(image.to_rgb8());
+            let encoder = JpegEncoder::new_with_quality(&mut buf, quality);
+            write_with_metadata(encoder, &image, icc_profile, exif)?;
+        }
+        ExportFormat::Png => {
+            write_with_metadata(PngEncoder::new(&mut buf), image, icc_profile, exif)?
+        }
+        ExportFormat::Webp => {
+            let image = if image.color().has_alpha() {
+                DynamicImage::ImageRgba8(image.to_rgba8())
+            } else {
+                DynamicImage::ImageRgb8(image.to_rgb8())
+            };
+            write_with_metadata(
+                WebPEncoder::new_lossless(&mut buf),
+                &image,
+                icc_profile,
+                exif,
+            )?;
+        }
+        ExportFormat::Avif => {
+            let image = if image.color().has_alpha() {
+                DynamicImage::ImageRgba8(image.to_rgba8())
+            } else {
+                DynamicImage::ImageRgb8(image.to_rgb8())
+            };
+            let encoder = AvifEncoder::new_with_speed_quality(&mut buf, AVIF_SPEED, quality);
+            write_with_metadata(encoder, &image, icc_profile, exif)?;
+        }
+        ExportFormat::Original => return Err("原图不需要重新编码".to_string()),
+    }
+
+    Ok(buf.into_inner())
+}
+
+/// 写入色彩配置文件和 EXIF 后编码，编码器不支持的元数据被忽略
+fn write_with_metadata<E: ImageEncoder>(
+    mut encoder: E,
+    image: &DynamicImage,
+    icc_profile: Option<Vec<u8>>,
+    exif: Option<Vec<u8>>,
+) -> Result<(), String> {
+    if let Some(icc_profile) = icc_profile {
+        let _ = encoder.set_icc_profile(icc_profile);
+    }
+    if let Some(exif) = exif {
+        let _ = encoder.set_exif_metadata(exif);
+    }
+    encoder
+        .write_image(
+            image.as_bytes(),
+            image.width(),
+            image.height(),
+            image.color().into(),
+        )
+        .map_err(|e| format!("编码图片失败: {}", e))
+}
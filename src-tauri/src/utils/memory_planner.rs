@@ -0,0 +1,287 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, Utc};
+
+use crate::models::memory::MemoryKind;
+use crate::utils::reverse_geocoder::haversine_km;
+
+/// 活动内相邻两张照片的最大拍摄间隔（分钟）
+pub const EVENT_MAX_GAP_MINUTES: i64 = 120;
+
+/// 构成活动的最少照片数量
+pub const EVENT_MIN_PHOTOS: usize = 12;
+
+/// 距离常住地超过该值（公里）的照片视为外出拍摄
+pub const TRIP_MIN_DISTANCE_KM: f64 = 100.0;
+
+/// 构成旅行的最少天数
+pub const TRIP_MIN_DAYS: i64 = 2;
+
+/// 旅行中允许连续多少天没有带定位的照片
+pub const TRIP_MAX_GAP_DAYS: i64 = 1;
+
+/// 推断常住地时使用的网格大小（度）
+const HOME_CELL_DEGREES: f64 = 0.5;
+
+/// 参与生成回忆的照片
+#[derive(Debug, Clone)]
+pub struct MemoryPhoto {
+    pub id: String,
+    pub taken: DateTime<Utc>,
+    /// 拍摄时间对应的本地时间，按天分组时使用
+    pub local: NaiveDateTime,
+    /// (纬度, 经度)
+    pub location: Option<(f64, f64)>,
+    pub city: Option<String>,
+    pub country: Option<String>,
+    /// 封面优先级，收藏和评分高的照片优先
+    pub score: i64,
+}
+
+/// 待保存的回忆
+#[derive(Debug, Clone)]
+pub struct PlannedMemory {
+    /// 生成规则内的稳定标识，刷新时据此更新已有回忆
+    pub key: String,
+    pub kind: MemoryKind,
+    pub title: String,
+    pub subtitle: Option<String>,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub cover_id: String,
+    /// 按拍摄时间排序的媒体 ID
+    pub media_ids: Vec<String>,
+}
+
+/// 根据照片生成那年今日、旅行和活动，`photos` 需按拍摄时间升序排列
+///
+/// 落在旅行日期内的活动已包含在旅行中，不再单独生成。
+pub fn plan_memories(photos: &[MemoryPhoto], today: NaiveDate) -> Vec<PlannedMemory> {
+    let mut memories: Vec<PlannedMemory> = plan_on_this_day(photos, today).into_iter().collect();
+
+    let trips = plan_trips(photos);
+    let trip_ranges: Vec<(DateTime<Utc>, DateTime<Utc>)> =
+        trips.iter().map(|trip| (trip.start, trip.end)).collect();
+    memories.extend(trips);
+
+    memories.extend(plan_events(photos).into_iter().filter(|event| {
+        !trip_ranges
+            .iter()
+            .any(|(start, end)| *start <= event.start && event.end <= *end)
+    }));
+
+    memories
+}
+
+/// 往年同月同日拍摄的照片
+fn plan_on_this_day(photos: &[MemoryPhoto], today: NaiveDate) -> Option<PlannedMemory> {
+    let members: Vec<&MemoryPhoto> = photos
+        .iter()
+        .filter(|photo| {
+            photo.local.month() == today.month()
+                && photo.local.day() == today.day()
+                && photo.local.year() < today.year()
+        })
+        .collect();
+    if members.is_empty() {
+        return None;
+    }
+
+    let years: BTreeSet<i32> = members.iter().map(|photo| photo.local.year()).collect();
+    let subtitle = match (years.first(), years.last()) {
+        (Some(first), Some(last)) if first == last => {
+            format!("{}年前的今天", today.year() - first)
+        }
+        (Some(first), Some(last)) => format!("{}年至{}年", first, last),
+        _ => return None,
+    };
+
+    Some(build_memory(
+        MemoryKind::OnThisDay,
+        "on-this-day".to_string(),
+        "那年今日".to_string(),
+        Some(subtitle),
+        &members,
+    ))
+}
+
+/// 按拍摄间隔切分照片，照片足够密集的一段视为一次活动
+fn plan_events(photos: &[MemoryPhoto]) -> Vec<PlannedMemory> {
+    let mut events = Vec::new();
+    let mut start = 0;
+
+    for index in 1..=photos.len() {
+        let split = index == photos.len()
+            || (photos[index].taken - photos[index - 1].taken).num_minutes()
+                > EVENT_MAX_GAP_MINUTES;
+        if !split {
+            continue;
+        }
+
+        let members: Vec<&MemoryPhoto> = photos[start..index].iter().collect();
+        start = index;
+        if members.len() < EVENT_MIN_PHOTOS {
+            continue;
+        }
+
+        let first = members[0].local;
+        let date = format_date(first.date());
+        let (title, subtitle) = match most_common(members.iter().map(|p| p.city.as_deref())) {
+            Some(city) => (city.to_string(), Some(date)),
+            None => (date, None),
+        };
+        events.push(build_memory(
+            MemoryKind::Event,
+            format!("event:{}", first.format("%Y-%m-%dT%H:%M")),
+            title,
+            subtitle,
+            &members,
+        ));
+    }
+
+    events
+}
+
+/// 以照片最集中的位置作为常住地，连续多天在远离常住地拍摄视为一次旅行
+///
+/// 某天带定位的照片中多数远离常住地时该天为外出；没有定位照片的日子不打断旅行，
+/// 但连续超过 `TRIP_MAX_GAP_DAYS` 天或出现在常住地拍摄的日子时旅行结束。
+fn plan_trips(photos: &[MemoryPhoto]) -> Vec<PlannedMemory> {
+    let Some((home_latitude, home_longitude)) = home_location(photos) else {
+        return Vec::new();
+    };
+
+    // 每天的 (外出照片数, 定位照片数)
+    let mut days: BTreeMap<NaiveDate, (usize, usize)> = BTreeMap::new();
+    for photo in photos {
+        if let Some((latitude, longitude)) = photo.location {
+            let day = days.entry(photo.local.date()).or_default();
+            day.1 += 1;
+            if haversine_km(latitude, longitude, home_latitude, home_longitude)
+                > TRIP_MIN_DISTANCE_KM
+            {
+                day.0 += 1;
+            }
+        }
+    }
+
+    let mut ranges: Vec<(NaiveDate, NaiveDate)> = Vec::new();
+    let mut current: Option<(NaiveDate, NaiveDate)> = None;
+    for (date, (away, located)) in days {
+        if away * 2 <= located {
+            ranges.extend(current.take());
+            continue;
+        }
+        match current.as_mut() {
+            Some((_, end)) if (date - *end).num_days() <= TRIP_MAX_GAP_DAYS + 1 => *end = date,
+            _ => ranges.extend(current.replace((date, date))),
+        }
+    }
+    ranges.extend(current);
+
+    ranges
+        .into_iter()
+        .filter(|(start, end)| (*end - *start).num_days() + 1 >= TRIP_MIN_DAYS)
+        .filter_map(|(start, end)| {
+            let members: Vec<&MemoryPhoto> = photos
+                .iter()
+                .filter(|photo| (start..=end).contains(&photo.local.date()))
+                .collect();
+            if members.is_empty() {
+                return None;
+            }
+
+            // 途经多个城市时以国家命名
+            let cities: BTreeSet<&str> = members.iter().filter_map(|p| p.city.as_deref()).collect();
+            let place = if cities.len() > 1 {
+                most_common(members.iter().map(|p| p.country.as_deref()))
+            } else {
+                cities.first().copied()
+            };
+            let title = match place {
+                Some(place) => format!("{}之旅", place),
+                None => "旅行".to_string(),
+            };
+
+            Some(build_memory(
+                MemoryKind::Trip,
+                format!("trip:{}", start),
+                title,
+                Some(format_date_range(start, end)),
+                &members,
+            ))
+        })
+        .collect()
+}
+
+/// 定位照片最多的网格内照片的平均位置
+fn home_location(photos: &[MemoryPhoto]) -> Option<(f64, f64)> {
+    let mut cells: HashMap<(i64, i64), Vec<(f64, f64)>> = HashMap::new();
+    for (latitude, longitude) in photos.iter().filter_map(|photo| photo.location) {
+        let cell = (
+            (latitude / HOME_CELL_DEGREES).floor() as i64,
+            (longitude / HOME_CELL_DEGREES).floor() as i64,
+        );
+        cells.entry(cell).or_default().push((latitude, longitude));
+    }
+
+    let (_, points) = cells
+        .into_iter()
+        .max_by(|a, b| a.1.len().cmp(&b.1.len()).then_with(|| b.0.cmp(&a.0)))?;
+    let count = points.len() as f64;
+    Some((
+        points.iter().map(|(latitude, _)| latitude).sum::<f64>() / count,
+        points.iter().map(|(_, longitude)| longitude).sum::<f64>() / count,
+    ))
+}
+
+/// 以优先级最高的照片为封面生成回忆，`members` 需按拍摄时间排序
+fn build_memory(
+    kind: MemoryKind,
+    key: String,
+    title: String,
+    subtitle: Option<String>,
+    members: &[&MemoryPhoto],
+) -> PlannedMemory {
+    let mut cover = members[0];
+    for photo in members {
+        if photo.score > cover.score {
+            cover = photo;
+        }
+    }
+
+    PlannedMemory {
+        key,
+        kind,
+        title,
+        subtitle,
+        start: members[0].taken,
+        end: members[members.len() - 1].taken,
+        cover_id: cover.id.clone(),
+        media_ids: members.iter().map(|photo| photo.id.clone()).collect(),
+    }
+}
+
+/// 出现次数最多的值，次数相同时取字典序较小的
+fn most_common<'a>(values: impl Iterator<Item = Option<&'a str>>) -> Option<&'a str> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for value in values.flatten() {
+        *counts.entry(value).or_default() += 1;
+    }
+    counts
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+        .map(|(value, _)| value)
+}
+
+fn format_date(date: NaiveDate) -> String {
+    format!("{}年{}月{}日", date.year(), date.month(), date.day())
+}
+
+fn format_date_range(start: NaiveDate, end: NaiveDate) -> String {
+    if start.year() == end.year() {
+        format!("{} - {}月{}日", format_date(start), end.month(), end.day())
+    } else {
+        format!("{} - {}", format_date(start), format_date(end))
+    }
+}
@@ -0,0 +1,153 @@
+use std::io::Cursor;
+
+use exif::{experimental::Writer, Context, Field, In, Reader as ExifReader, Tag, Value};
+
+use crate::models::export::MetadataPolicy;
+
+/// APP1 中 EXIF 数据的标识
+const EXIF_HEADER: &[u8] = b"Exif\0\0";
+
+/// APP1 中 XMP 数据的标识，包括扩展 XMP
+const XMP_HEADERS: &[&[u8]] = &[
+    b"http://ns.adobe.com/xap/1.0/\0",
+    b"http://ns.adobe.com/xmp/extension/\0",
+];
+
+/// APP2 中多图片格式（MPF）的标识，其中的偏移在截断附加图像后失效
+const MPF_HEADER: &[u8] = b"MPF\0";
+
+/// 指向图像数据的标签，重新写入 EXIF 时不能保留
+const DATA_POINTER_TAGS: &[Tag] = &[
+    Tag::StripOffsets,
+    Tag::StripByteCounts,
+    Tag::TileOffsets,
+    Tag::TileByteCounts,
+    Tag::JPEGInterchangeFormat,
+    Tag::JPEGInterchangeFormatLength,
+];
+
+/// 按策略重新生成 EXIF（TIFF 格式，不含 `Exif\0\0`），返回 None 表示不写入 EXIF
+///
+/// 缩略图被移除；`reset_orientation` 为 true 时方向改为正常，用于已按方向旋转过的图片。
+/// 无法解析或重新写入时返回 None，宁可丢失元数据也不保留可能包含位置的原始数据。
+pub fn filter_exif(
+    tiff: &[u8],
+    policy: MetadataPolicy,
+    reset_orientation: bool,
+) -> Option<Vec<u8>> {
+    if policy == MetadataPolicy::StripAll {
+        return None;
+    }
+
+    let exif = ExifReader::new().read_raw(tiff.to_vec()).ok()?;
+    let fields: Vec<Field> = exif
+        .fields()
+        .filter(|field| field.ifd_num == In::PRIMARY)
+        .filter(|field| !DATA_POINTER_TAGS.contains(&field.tag))
+        .filter(|field| {
+            !(policy == MetadataPolicy::StripGps && field.tag.context() == Context::Gps)
+        })
+        .map(|field| {
+            if reset_orientation && field.tag == Tag::Orientation {
+                Field {
+                    value: Value::Short(vec![1]),
+                    ..field.clone()
+                }
+            } else {
+                field.clone()
+            }
+        })
+        .collect();
+
+    let mut writer = Writer::new();
+    for field in &fields {
+        writer.push_field(field);
+    }
+    let mut buf = Cursor::new(Vec::new());
+    writer.write(&mut buf, exif.little_endian()).ok()?;
+
+    Some(buf.into_inner())
+}
+
+/// 不重新编码，按策略移除 JPEG 中的元数据段
+///
+/// 移除 GPS 时重写 EXIF，并移除含有位置信息的 XMP；移除全部时去掉 EXIF、XMP、IPTC 和注释，
+/// 保留色彩配置文件。两种情况都会截掉主图像之后附加的数据（多图片格式的附图、动态照片视频），
+/// 因为其中可能带有各自的元数据。
+pub fn strip_jpeg_metadata(data: &[u8], policy: MetadataPolicy) -> Result<Vec<u8>, String> {
+    if policy == MetadataPolicy::KeepAll {
+        return Ok(data.to_vec());
+    }
+    if !data.starts_with(&[0xFF, 0xD8]) {
+        return Err("不是有效的 JPEG 文件".to_string());
+    }
+
+    let mut output = vec![0xFF, 0xD8];
+    let mut pos = 2;
+    loop {
+        // 跳过标记前的填充字节
+        while data.get(pos) == Some(&0xFF) && data.get(pos + 1) == Some(&0xFF) {
+            pos += 1;
+        }
+        let (Some(0xFF), Some(&marker)) = (data.get(pos), data.get(pos + 1)) else {
+            return Err("JPEG 文件结构无效".to_string());
+        };
+        if marker == 0xDA {
+            break;
+        }
+        let length = match data.get(pos + 2..pos + 4) {
+            Some(bytes) => u16::from_be_bytes([bytes[0], bytes[1]]) as usize,
+            None => return Err("JPEG 文件不完整".to_string()),
+        };
+        let Some(payload) = data.get(pos + 4..pos + 2 + length) else {
+            return Err("JPEG 文件不完整".to_string());
+        };
+        let segment = &data[pos..pos + 2 + length];
+        pos += 2 + length;
+
+        let keep = match marker {
+            // APP1：EXIF 或 XMP
+            0xE1 if payload.starts_with(EXIF_HEADER) => {
+                if let Some(exif) = filter_exif(&payload[EXIF_HEADER.len()..], policy, false) {
+                    write_segment(&mut output, 0xE1, &[EXIF_HEADER, &exif].concat())?;
+                }
+                false
+            }
+            0xE1 if XMP_HEADERS.iter().any(|header| payload.starts_with(header)) => {
+                policy == MetadataPolicy::StripGps && !contains(payload, b"GPS")
+            }
+            0xE2 if payload.starts_with(MPF_HEADER) => false,
+            // APP13：Photoshop / IPTC；COM：注释
+            0xED | 0xFE => policy != MetadataPolicy::StripAll,
+            _ => true,
+        };
+        if keep {
+            output.extend_from_slice(segment);
+        }
+    }
+
+    // 扫描数据中的 0xFF 后只会跟 0x00 或复位标记，第一个 EOI 即主图像的结尾
+    let scan = &data[pos..];
+    let end = scan
+        .windows(2)
+        .position(|window| window == [0xFF, 0xD9])
+        .map(|index| index + 2)
+        .unwrap_or(scan.len());
+    output.extend_from_slice(&scan[..end]);
+
+    Ok(output)
+}
+
+fn write_segment(output: &mut Vec<u8>, marker: u8, payload: &[u8]) -> Result<(), String> {
+    let length = u16::try_from(payload.len() + 2).map_err(|_| "元数据段过大".to_string())?;
+    output.extend_from_slice(&[0xFF, marker]);
+    output.extend_from_slice(&length.to_be_bytes());
+    output.extend_from_slice(payload);
+    Ok(())
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack
+        .windows(needle.len())
+        .any(|window| window == needle)
+}
@@ -1 +1,18 @@
+pub mod contact_sheet;
+pub mod exif_metadata;
+pub mod face_clustering;
+pub mod face_detection;
+pub mod file_ops;
+pub mod gallery_site;
+pub mod image_export;
 pub mod image_processor;
+pub mod memory_planner;
+pub mod metadata_filter;
+pub mod motion_photo;
+pub mod path_template;
+pub mod pdf_writer;
+pub mod reverse_geocoder;
+pub mod search_query;
+pub mod text_render;
+pub mod watermark;
+pub mod xmp_sidecar;
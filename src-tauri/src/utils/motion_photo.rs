@@ -0,0 +1,501 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs::{self, File},
+    io::{self, BufReader, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+use exif::{Reader as ExifReader, Tag, Value};
+
+/// JPEG APP1 段中 XMP 数据的命名空间前缀
+const XMP_SIGNATURE: &[u8] = b"http://ns.adobe.com/xap/1.0/\0";
+
+/// Apple MakerNote 的头部签名
+const APPLE_MAKER_NOTE_SIGNATURE: &[u8] = b"Apple iOS\0";
+
+/// Apple MakerNote 中 ContentIdentifier 的标签号
+const APPLE_CONTENT_IDENTIFIER_TAG: u16 = 0x0011;
+
+/// QuickTime 元数据中实况照片标识符的键名
+const QUICKTIME_CONTENT_IDENTIFIER_KEY: &[u8] = b"com.apple.quicktime.content.identifier";
+
+/// 三星动态照片尾部数据中视频开始前的标记
+const SAMSUNG_MOTION_PHOTO_MARKER: &[u8] = b"MotionPhoto_Data";
+
+/// 从文件末尾向前查找三星标记时每次读取的字节数
+const SCAN_CHUNK_SIZE: usize = 64 * 1024;
+
+/// 读取 moov 原子时允许的最大字节数，防止异常文件占用过多内存
+const MAX_MOOV_SIZE: u64 = 64 * 1024 * 1024;
+
+/// 照片对应的视频部分
+#[derive(Debug, Clone)]
+pub struct MotionComponent {
+    /// 视频所在文件路径（嵌入式视频为照片自身）
+    pub path: PathBuf,
+    /// 嵌入式视频的起始偏移
+    pub offset: Option<u64>,
+    /// 嵌入式视频的字节长度
+    pub length: Option<u64>,
+}
+
+/// 同一批导入文件所在目录中的实况照片视频索引
+#[derive(Debug, Default)]
+pub struct LivePhotoIndex {
+    by_identifier: HashMap<String, PathBuf>,
+    by_stem: HashMap<PathBuf, PathBuf>,
+    identifiers: HashMap<PathBuf, String>,
+}
+
+impl LivePhotoIndex {
+    /// 扫描照片所在目录中的 MOV 文件并建立索引
+    pub fn build(stills: &[PathBuf]) -> Self {
+        let mut index = Self::default();
+        let dirs: HashSet<&Path> = stills.iter().filter_map(|p| p.parent()).collect();
+
+        for dir in dirs {
+            let Ok(entries) = fs::read_dir(dir) else {
+                continue;
+            };
+
+            for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+                let is_mov = path
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .map(|ext| ext.eq_ignore_ascii_case("mov"))
+                    .unwrap_or(false);
+                if !is_mov || !path.is_file() {
+                    continue;
+                }
+
+                if let Some(key) = stem_key(&path) {
+                    index.by_stem.insert(key, path.clone());
+                }
+                if let Some(identifier) = read_quicktime_content_identifier(&path) {
+                    index.by_identifier.insert(identifier.clone(), path.clone());
+                    index.identifiers.insert(path, identifier);
+                }
+            }
+        }
+
+        index
+    }
+
+    /// 查找照片对应的实况照片视频
+    ///
+    /// 优先按 ContentIdentifier 匹配；同名文件只有在两者标识符不冲突时才视为一对。
+    pub fn find_companion(&self, still: &Path) -> Option<PathBuf> {
+        if self.by_stem.is_empty() {
+            return None;
+        }
+
+        let still_identifier = read_still_content_identifier(still);
+        if let Some(mov) = still_identifier
+            .as_ref()
+            .and_then(|id| self.by_identifier.get(id))
+        {
+            return Some(mov.clone());
+        }
+
+        let mov = self.by_stem.get(&stem_key(still)?)?;
+        match (still_identifier, self.identifiers.get(mov)) {
+            (Some(a), Some(b)) if &a != b => None,
+            _ => Some(mov.clone()),
+        }
+    }
+}
+
+/// 检测照片的视频部分：先查找配对的实况照片视频，再检查文件内嵌的动态照片视频
+pub fn detect_motion_component(
+    path: &Path,
+    live_photos: &LivePhotoIndex,
+) -> Option<MotionComponent> {
+    if let Some(mov) = live_photos.find_companion(path) {
+        return Some(MotionComponent {
+            path: mov,
+            offset: None,
+            length: None,
+        });
+    }
+
+    let (offset, length) = detect_embedded_motion(path)?;
+    Some(MotionComponent {
+        path: path.to_path_buf(),
+        offset: Some(offset),
+        length: Some(length),
+    })
+}
+
+/// 检测 JPEG 中嵌入的动态照片视频，返回视频的 (偏移, 长度)
+///
+/// 支持 Google `GCamera:MicroVideoOffset`、`Container:Directory` 中的
+/// `MotionPhoto` 条目以及三星 `MotionPhoto_Data` 尾部数据。
+pub fn detect_embedded_motion(path: &Path) -> Option<(u64, u64)> {
+    let is_jpeg = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| matches!(ext.to_lowercase().as_str(), "jpg" | "jpeg"))
+        .unwrap_or(false);
+    if !is_jpeg {
+        return None;
+    }
+
+    let mut file = File::open(path).ok()?;
+    let file_len = file.metadata().ok()?.len();
+
+    let from_xmp = read_jpeg_xmp(&mut file).and_then(|xmp| {
+        xmp_value(&xmp, "GCamera:MicroVideoOffset")
+            .or_else(|| motion_photo_item_length(&xmp))
+            .and_then(|v| v.trim().parse::<u64>().ok())
+    });
+
+    let (offset, length) = match from_xmp {
+        Some(length) if length > 0 && length < file_len => (file_len - length, length),
+        _ => find_samsung_motion_data(&mut file, file_len)?,
+    };
+
+    is_mp4_at(&mut file, offset).then_some((offset, length))
+}
+
+/// 将嵌入式视频的字节范围写入目标文件
+pub fn extract_embedded_motion(
+    source: &Path,
+    offset: u64,
+    length: u64,
+    destination: &Path,
+) -> io::Result<()> {
+    let mut input = File::open(source)?;
+    input.seek(SeekFrom::Start(offset))?;
+
+    if let Some(parent) = destination.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut output = File::create(destination)?;
+    let copied = io::copy(&mut input.take(length), &mut output)?;
+
+    if copied != length {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            format!("视频数据不完整: 期望 {} 字节，实际 {} 字节", length, copied),
+        ));
+    }
+
+    Ok(())
+}
+
+/// 从照片的 Apple MakerNote 中读取实况照片标识符
+pub fn read_still_content_identifier(path: &Path) -> Option<String> {
+    let file = File::open(path).ok()?;
+    let exif = ExifReader::new()
+        .read_from_container(&mut BufReader::new(file))
+        .ok()?;
+
+    let field = exif.get_field(Tag::MakerNote, exif::In::PRIMARY)?;
+    match &field.value {
+        Value::Undefined(note, _) => parse_apple_content_identifier(note),
+        _ => None,
+    }
+}
+
+/// 从 MOV 文件的 `moov/meta` 元数据中读取实况照片标识符
+pub fn read_quicktime_content_identifier(path: &Path) -> Option<String> {
+    let mut file = File::open(path).ok()?;
+    let file_len = file.metadata().ok()?.len();
+
+    let moov = read_top_level_atom(&mut file, file_len, b"moov")?;
+    let meta = find_atom(&moov, b"meta")?;
+    // QuickTime 的 meta 不带 version/flags，ISO BMFF 的 meta 带 4 字节头
+    let meta = if meta.get(4..8) == Some(b"hdlr".as_slice()) {
+        meta
+    } else {
+        meta.get(4..)?
+    };
+
+    let keys = find_atom(meta, b"keys")?;
+    let ilst = find_atom(meta, b"ilst")?;
+
+    // keys: version/flags(4) + entry_count(4) + [size(4) + namespace(4) + name]
+    let count = read_u32_be(keys, 4)?;
+    let mut pos = 8;
+    let mut key_index = None;
+    for index in 1..=count {
+        let size = read_u32_be(keys, pos)? as usize;
+        if size < 8 {
+            return None;
+        }
+        if keys.get(pos + 8..pos + size)? == QUICKTIME_CONTENT_IDENTIFIER_KEY {
+            key_index = Some(index);
+            break;
+        }
+        pos += size;
+    }
+    let key_index = key_index?.to_be_bytes();
+
+    // ilst 的子原子类型即为 keys 中从 1 开始的序号
+    let item = atoms(ilst)
+        .find(|(kind, _)| *kind == key_index)
+        .map(|(_, body)| body)?;
+    // data: type(4) + locale(4) + value
+    let data = find_atom(item, b"data")?;
+    let value = String::from_utf8_lossy(data.get(8..)?).trim().to_string();
+
+    (!value.is_empty()).then_some(value)
+}
+
+/// 解析 Apple MakerNote 中的 ContentIdentifier
+fn parse_apple_content_identifier(note: &[u8]) -> Option<String> {
+    if !note.starts_with(APPLE_MAKER_NOTE_SIGNATURE) {
+        return None;
+    }
+
+    // 头部: "Apple iOS\0" + 版本(2) + 字节序(2)，随后为 IFD，偏移量相对 MakerNote 起始位置
+    let big_endian = note.get(12..14)? == b"MM";
+    let read_u16 = |pos: usize| -> Option<u16> {
+        let bytes: [u8; 2] = note.get(pos..pos + 2)?.try_into().ok()?;
+        Some(if big_endian {
+            u16::from_be_bytes(bytes)
+        } else {
+            u16::from_le_bytes(bytes)
+        })
+    };
+    let read_u32 = |pos: usize| -> Option<u32> {
+        let bytes: [u8; 4] = note.get(pos..pos + 4)?.try_into().ok()?;
+        Some(if big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        })
+    };
+
+    let count = read_u16(14)? as usize;
+    for i in 0..count {
+        let entry = 16 + i * 12;
+        // 类型 2 为 ASCII 字符串
+        if read_u16(entry)? != APPLE_CONTENT_IDENTIFIER_TAG || read_u16(entry + 2)? != 2 {
+            continue;
+        }
+
+        let len = read_u32(entry + 4)? as usize;
+        let data = if len <= 4 {
+            note.get(entry + 8..entry + 8 + len)?
+        } else {
+            let offset = read_u32(entry + 8)? as usize;
+            note.get(offset..offset + len)?
+        };
+
+        let value = String::from_utf8_lossy(data)
+            .trim_end_matches('\0')
+            .trim()
+            .to_string();
+        return (!value.is_empty()).then_some(value);
+    }
+
+    None
+}
+
+/// 读取 JPEG 文件中的 XMP 数据包
+fn read_jpeg_xmp(file: &mut File) -> Option<String> {
+    file.seek(SeekFrom::Start(0)).ok()?;
+    let mut reader = BufReader::new(file);
+
+    let mut soi = [0u8; 2];
+    reader.read_exact(&mut soi).ok()?;
+    if soi != [0xFF, 0xD8] {
+        return None;
+    }
+
+    loop {
+        let mut marker = [0u8; 2];
+        reader.read_exact(&mut marker).ok()?;
+        if marker[0] != 0xFF {
+            return None;
+        }
+        // 图像数据开始或结束后不再有元数据段
+        if marker[1] == 0xDA || marker[1] == 0xD9 {
+            return None;
+        }
+
+        let mut len = [0u8; 2];
+        reader.read_exact(&mut len).ok()?;
+        let len = u16::from_be_bytes(len) as usize;
+        if len < 2 {
+            return None;
+        }
+
+        let mut payload = vec![0u8; len - 2];
+        reader.read_exact(&mut payload).ok()?;
+        if marker[1] == 0xE1 && payload.starts_with(XMP_SIGNATURE) {
+            return Some(String::from_utf8_lossy(&payload[XMP_SIGNATURE.len()..]).into_owned());
+        }
+    }
+}
+
+/// 读取 XMP 中的属性值，兼容属性和子元素两种写法
+fn xmp_value(xmp: &str, name: &str) -> Option<String> {
+    for quote in ['"', '\''] {
+        let pattern = format!("{}={}", name, quote);
+        if let Some(start) = xmp.find(&pattern).map(|i| i + pattern.len()) {
+            let end = xmp[start..].find(quote)?;
+            return Some(xmp[start..start + end].to_string());
+        }
+    }
+
+    let open = format!("<{}>", name);
+    let start = xmp.find(&open)? + open.len();
+    let end = xmp[start..].find('<')?;
+    Some(xmp[start..start + end].to_string())
+}
+
+/// 在 `Container:Directory` 中查找语义为 MotionPhoto 的条目长度
+fn motion_photo_item_length(xmp: &str) -> Option<String> {
+    let semantic = xmp.find("Item:Semantic=\"MotionPhoto\"")?;
+    let start = xmp[..semantic].rfind('<')?;
+    let end = semantic + xmp[semantic..].find('>')?;
+    xmp_value(&xmp[start..end], "Item:Length")
+}
+
+/// 查找三星动态照片尾部数据中的视频，返回 (偏移, 长度)
+fn find_samsung_motion_data(file: &mut File, file_len: u64) -> Option<(u64, u64)> {
+    // 三星的尾部数据以 "SEFT" 结尾，先检查签名避免扫描普通照片
+    let mut signature = [0u8; 4];
+    file.seek(SeekFrom::End(-4)).ok()?;
+    file.read_exact(&mut signature).ok()?;
+    if &signature != b"SEFT" {
+        return None;
+    }
+
+    let marker = rfind_in_file(file, file_len, SAMSUNG_MOTION_PHOTO_MARKER)?;
+    let offset = marker + SAMSUNG_MOTION_PHOTO_MARKER.len() as u64;
+
+    // 视频之后的 SEF 目录大小未知，以视频内顶层原子的总长度为准
+    let length = mp4_length(file, offset, file_len).unwrap_or(file_len - offset);
+    Some((offset, length))
+}
+
+/// 从文件末尾向前分块查找字节序列最后一次出现的偏移
+fn rfind_in_file(file: &mut File, file_len: u64, pattern: &[u8]) -> Option<u64> {
+    let overlap = pattern.len() as u64 - 1;
+    let mut buf = vec![0u8; SCAN_CHUNK_SIZE];
+    let mut end = file_len;
+    while end >= pattern.len() as u64 {
+        let start = end.saturating_sub(SCAN_CHUNK_SIZE as u64);
+        let chunk = &mut buf[..(end - start) as usize];
+        file.seek(SeekFrom::Start(start)).ok()?;
+        file.read_exact(chunk).ok()?;
+
+        if let Some(index) = chunk.windows(pattern.len()).rposition(|w| w == pattern) {
+            return Some(start + index as u64);
+        }
+        if start == 0 {
+            break;
+        }
+        // 保留重叠部分，避免漏掉跨越分块边界的匹配
+        end = start + overlap;
+    }
+
+    None
+}
+
+/// 计算从 offset 起连续 MP4 顶层原子的总长度
+fn mp4_length(file: &mut File, offset: u64, file_len: u64) -> Option<u64> {
+    let mut pos = offset;
+    while file_len.saturating_sub(pos) >= 8 {
+        let mut header = [0u8; 8];
+        file.seek(SeekFrom::Start(pos)).ok()?;
+        file.read_exact(&mut header).ok()?;
+
+        let size = u32::from_be_bytes(header[0..4].try_into().ok()?) as u64;
+        let kind = &header[4..8];
+        if size < 8 || !kind.iter().all(|b| b.is_ascii_alphanumeric() || *b == b' ') {
+            break;
+        }
+        pos = pos.checked_add(size)?;
+    }
+
+    (pos > offset).then_some(pos.min(file_len) - offset)
+}
+
+/// 检查指定偏移处是否为 MP4 / MOV 的 ftyp 原子
+fn is_mp4_at(file: &mut File, offset: u64) -> bool {
+    let mut header = [0u8; 8];
+    file.seek(SeekFrom::Start(offset)).is_ok()
+        && file.read_exact(&mut header).is_ok()
+        && &header[4..8] == b"ftyp"
+}
+
+/// 在文件顶层查找指定类型的原子并读取其内容
+fn read_top_level_atom(file: &mut File, file_len: u64, name: &[u8; 4]) -> Option<Vec<u8>> {
+    let mut pos = 0u64;
+    while file_len.saturating_sub(pos) >= 8 {
+        file.seek(SeekFrom::Start(pos)).ok()?;
+        let mut header = [0u8; 8];
+        file.read_exact(&mut header).ok()?;
+
+        let mut size = u32::from_be_bytes(header[0..4].try_into().ok()?) as u64;
+        let mut header_len = 8;
+        if size == 1 {
+            let mut large = [0u8; 8];
+            file.read_exact(&mut large).ok()?;
+            size = u64::from_be_bytes(large);
+            header_len = 16;
+        } else if size == 0 {
+            size = file_len - pos;
+        }
+        // 长度小于头部的原子无法前进，视为文件损坏
+        if size < header_len {
+            return None;
+        }
+
+        if &header[4..8] == name {
+            let body_len = size - header_len;
+            if body_len > MAX_MOOV_SIZE {
+                return None;
+            }
+            let mut body = vec![0u8; body_len as usize];
+            file.read_exact(&mut body).ok()?;
+            return Some(body);
+        }
+
+        pos = pos.checked_add(size)?;
+    }
+
+    None
+}
+
+/// 遍历缓冲区中的原子，产出 (类型, 内容)
+fn atoms(buf: &[u8]) -> impl Iterator<Item = ([u8; 4], &[u8])> {
+    let mut pos = 0usize;
+    std::iter::from_fn(move || {
+        let size = read_u32_be(buf, pos)? as usize;
+        let kind: [u8; 4] = buf.get(pos + 4..pos + 8)?.try_into().ok()?;
+        let (header_len, size) = match size {
+            0 => (8, buf.len() - pos),
+            1 => (16, read_u64_be(buf, pos + 8)? as usize),
+            _ => (8, size),
+        };
+        let body = buf.get(pos + header_len..pos.checked_add(size)?)?;
+        pos += size;
+        Some((kind, body))
+    })
+}
+
+/// 查找缓冲区中第一个指定类型的原子
+fn find_atom<'a>(buf: &'a [u8], name: &[u8; 4]) -> Option<&'a [u8]> {
+    atoms(buf)
+        .find(|(kind, _)| kind == name)
+        .map(|(_, body)| body)
+}
+
+fn read_u32_be(buf: &[u8], pos: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(buf.get(pos..pos + 4)?.try_into().ok()?))
+}
+
+fn read_u64_be(buf: &[u8], pos: usize) -> Option<u64> {
+    Some(u64::from_be_bytes(buf.get(pos..pos + 8)?.try_into().ok()?))
+}
+
+/// 同目录下不区分大小写的文件名主干，用于按文件名配对
+fn stem_key(path: &Path) -> Option<PathBuf> {
+    let stem = path.file_stem()?.to_str()?.to_lowercase();
+    Some(path.parent()?.join(stem))
+}
@@ -0,0 +1,186 @@
+use std::{
+    collections::HashMap,
+    path::{Component, Path, PathBuf},
+};
+
+use chrono::{DateTime, Local, NaiveDate, Utc};
+
+/// 序号和计数未指定位数时补零到的位数
+const DEFAULT_SEQ_WIDTH: usize = 4;
+
+/// 模板中缺少对应信息时使用的值
+const UNKNOWN_VALUE: &str = "Unknown";
+
+/// 路径模板中可用的媒体信息
+#[derive(Debug, Clone, Default)]
+pub struct TemplateValues {
+    /// 拍摄时间，按本地时区展开为日期和时间
+    pub taken_date: Option<DateTime<Utc>>,
+    pub camera_make: Option<String>,
+    pub camera_model: Option<String>,
+    /// 媒体所在的相册，有多个时由调用方选择其一
+    pub album: Option<String>,
+    /// 照片中的人物，有多个时由调用方选择其一
+    pub person: Option<String>,
+    pub country: Option<String>,
+    pub region: Option<String>,
+    pub city: Option<String>,
+    /// 原文件名，不含扩展名
+    pub name: String,
+    /// 扩展名，不含 `.`
+    pub ext: String,
+    /// 同一天拍摄的文件中从 1 开始的序号
+    pub seq: u64,
+    /// 整批文件中从 1 开始的计数
+    pub counter: u64,
+}
+
+impl TemplateValues {
+    /// 从文件路径读取原文件名和扩展名
+    pub fn for_path(path: &Path) -> Self {
+        Self {
+            name: path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            ext: path
+                .extension()
+                .map(|ext| ext.to_string_lossy().to_lowercase())
+                .unwrap_or_default(),
+            ..Default::default()
+        }
+    }
+}
+
+/// 按当前顺序为一批文件编号：`counter` 连续递增，`seq` 在每个拍摄日期内从 1 开始
+pub fn assign_sequences<'a>(values: impl IntoIterator<Item = &'a mut TemplateValues>) {
+    let mut day_counts: HashMap<Option<NaiveDate>, u64> = HashMap::new();
+    for (index, value) in values.into_iter().enumerate() {
+        let day = value
+            .taken_date
+            .map(|date| date.with_timezone(&Local).date_naive());
+        let count = day_counts.entry(day).or_insert(0);
+        *count += 1;
+        value.seq = *count;
+        value.counter = (index + 1) as u64;
+    }
+}
+
+/// 按模板生成相对路径，`/` 分隔文件夹
+///
+/// 支持的变量：`{year}` `{month}` `{day}` `{hour}` `{minute}` `{second}` `{date}`（年月日）
+/// `{time}`（时分秒）`{camera}` `{make}` `{model}` `{album}` `{person}` `{country}` `{region}`
+/// `{city}` `{name}` `{ext}` `{seq}` `{counter}`，序号和计数可写为 `{seq:3}` 指定补零位数。
+/// 变量的值中不能用于文件名的字符替换为 `_`；生成的路径为空、是绝对路径或包含 `..` 时返回错误。
+pub fn render_path_template(template: &str, values: &TemplateValues) -> Result<PathBuf, String> {
+    let date = values.taken_date.map(|date| date.with_timezone(&Local));
+    let date_part = |format: &str| {
+        date.map(|date| date.format(format).to_string())
+            .unwrap_or_else(|| UNKNOWN_VALUE.to_string())
+    };
+
+    let mut rendered = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        rendered.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('}') else {
+            return Err(format!("模板中的 {{ 没有闭合: {}", template));
+        };
+        let variable = &rest[start + 1..start + end];
+        rest = &rest[start + end + 1..];
+
+        let (name, width) = match variable.split_once(':') {
+            Some((name, width)) => (
+                name,
+                Some(
+                    width
+                        .parse::<usize>()
+                        .map_err(|_| format!("无效的补零位数: {{{}}}", variable))?,
+                ),
+            ),
+            None => (variable, None),
+        };
+        if width.is_some() && !matches!(name, "seq" | "counter") {
+            return Err(format!(
+                "只有 seq 和 counter 可以指定补零位数: {{{}}}",
+                variable
+            ));
+        }
+        let value = match name {
+            "year" => date_part("%Y"),
+            "month" => date_part("%m"),
+            "day" => date_part("%d"),
+            "hour" => date_part("%H"),
+            "minute" => date_part("%M"),
+            "second" => date_part("%S"),
+            "date" => date_part("%Y%m%d"),
+            "time" => date_part("%H%M%S"),
+            "camera" => camera_name(values),
+            "make" => text_or_unknown(&values.camera_make),
+            "model" => text_or_unknown(&values.camera_model),
+            "album" => text_or_unknown(&values.album),
+            "person" => text_or_unknown(&values.person),
+            "country" => text_or_unknown(&values.country),
+            "region" => text_or_unknown(&values.region),
+            "city" => text_or_unknown(&values.city),
+            "name" => values.name.clone(),
+            "ext" => values.ext.clone(),
+            "seq" => format!(
+                "{:0width$}",
+                values.seq,
+                width = width.unwrap_or(DEFAULT_SEQ_WIDTH)
+            ),
+            "counter" => format!(
+                "{:0width$}",
+                values.counter,
+                width = width.unwrap_or(DEFAULT_SEQ_WIDTH)
+            ),
+            _ => return Err(format!("未知的模板变量: {{{}}}", variable)),
+        };
+        rendered.push_str(&sanitize_component(&value));
+    }
+    rendered.push_str(rest);
+
+    let path: PathBuf = rendered
+        .split('/')
+        .map(str::trim)
+        .filter(|component| !component.is_empty())
+        .collect();
+    let relative = path
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)));
+    if path.as_os_str().is_empty() || !relative {
+        return Err(format!("模板生成的路径无效: {}", rendered));
+    }
+
+    Ok(path)
+}
+
+/// 相机名称：型号通常已包含品牌，只有型号缺失时使用品牌
+fn camera_name(values: &TemplateValues) -> String {
+    match values.camera_model.as_deref().map(str::trim) {
+        Some(model) if !model.is_empty() => model.to_string(),
+        _ => text_or_unknown(&values.camera_make),
+    }
+}
+
+fn text_or_unknown(value: &Option<String>) -> String {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .unwrap_or(UNKNOWN_VALUE)
+        .to_string()
+}
+
+/// 替换不能用于文件名的字符，防止变量的值中带有路径分隔符
+fn sanitize_component(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect()
+}
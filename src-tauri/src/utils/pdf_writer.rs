@@ -0,0 +1,107 @@
+use std::fmt::Write as _;
+
+/// PDF 中的一页，内容为铺满页面的 JPEG 图片
+pub struct PdfPage {
+    pub jpeg: Vec<u8>,
+    /// 图片的像素尺寸
+    pub width: u32,
+    pub height: u32,
+}
+
+/// 生成每页一张 JPEG 图片的 PDF，页面尺寸以点（1/72 英寸）为单位
+///
+/// 图片以 DCTDecode 直接嵌入，不重新压缩。
+pub fn write_image_pdf(pages: &[PdfPage], page_width: f32, page_height: f32) -> Vec<u8> {
+    let mut pdf = PdfBuilder::default();
+    pdf.buffer
+        .extend_from_slice(b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n");
+
+    // 对象编号：1 为目录，2 为页面树，之后每页依次为页面、内容流和图片
+    let page_ids: Vec<usize> = (0..pages.len()).map(|index| 3 + index * 3).collect();
+    pdf.object(1, b"<< /Type /Catalog /Pages 2 0 R >>");
+    let kids = page_ids
+        .iter()
+        .map(|id| format!("{} 0 R", id))
+        .collect::<Vec<_>>()
+        .join(" ");
+    pdf.object(
+        2,
+        format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids, pages.len()).as_bytes(),
+    );
+
+    for (page, id) in pages.iter().zip(page_ids) {
+        let (content_id, image_id) = (id + 1, id + 2);
+        pdf.object(
+            id,
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {w:.2} {h:.2}] \
+                 /Resources << /XObject << /Im0 {image} 0 R >> >> /Contents {content} 0 R >>",
+                w = page_width,
+                h = page_height,
+                image = image_id,
+                content = content_id,
+            )
+            .as_bytes(),
+        );
+
+        let content = format!(
+            "q {:.2} 0 0 {:.2} 0 0 cm /Im0 Do Q",
+            page_width, page_height
+        );
+        pdf.stream(content_id, "", content.as_bytes());
+        pdf.stream(
+            image_id,
+            &format!(
+                "/Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /DeviceRGB \
+                 /BitsPerComponent 8 /Filter /DCTDecode",
+                page.width, page.height
+            ),
+            &page.jpeg,
+        );
+    }
+
+    pdf.finish(1)
+}
+
+#[derive(Default)]
+struct PdfBuilder {
+    buffer: Vec<u8>,
+    /// 对象编号及其在文件中的偏移，编号从 1 开始连续
+    offsets: Vec<(usize, usize)>,
+}
+
+impl PdfBuilder {
+    fn object(&mut self, id: usize, body: &[u8]) {
+        self.offsets.push((id, self.buffer.len()));
+        self.buffer
+            .extend_from_slice(format!("{} 0 obj\n", id).as_bytes());
+        self.buffer.extend_from_slice(body);
+        self.buffer.extend_from_slice(b"\nendobj\n");
+    }
+
+    fn stream(&mut self, id: usize, dictionary: &str, data: &[u8]) {
+        let mut body =
+            format!("<< {} /Length {} >>\nstream\n", dictionary, data.len()).into_bytes();
+        body.extend_from_slice(data);
+        body.extend_from_slice(b"\nendstream");
+        self.object(id, &body);
+    }
+
+    fn finish(mut self, root: usize) -> Vec<u8> {
+        self.offsets.sort();
+        let xref_offset = self.buffer.len();
+        let mut xref = format!("xref\n0 {}\n0000000000 65535 f \n", self.offsets.len() + 1);
+        for (_, offset) in &self.offsets {
+            let _ = writeln!(xref, "{:010} 00000 n ", offset);
+        }
+        let _ = write!(
+            xref,
+            "trailer\n<< /Size {} /Root {} 0 R >>\nstartxref\n{}\n%%EOF\n",
+            self.offsets.len() + 1,
+            root,
+            xref_offset
+        );
+        self.buffer.extend_from_slice(xref.as_bytes());
+        self.buffer
+    }
+}
@@ -0,0 +1,217 @@
+use std::{collections::HashMap, sync::OnceLock};
+
+use log::{info, warn};
+
+/// 构建时由 `gazetteer/embed.rs` 校验后内置的地名索引，不可用时为空
+static GAZETTEER_DATA: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/gazetteer.bin"));
+
+/// 索引文件头，与 `gazetteer/compile.rs` 保持一致
+const MAGIC: &[u8; 4] = b"LGZ1";
+
+/// 网格单元大小（度）
+const CELL_SIZE: f64 = 1.0;
+
+/// 距离最近地点超过该值（公里）时视为无法定位，例如海上
+const MAX_DISTANCE_KM: f64 = 150.0;
+
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+/// 反向地理编码结果
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Place {
+    pub country: Option<String>,
+    pub region: Option<String>,
+    pub city: Option<String>,
+}
+
+struct Entry {
+    latitude: f32,
+    longitude: f32,
+    city: u32,
+    region: u32,
+    country: u32,
+}
+
+/// 离线地名索引，按经纬度网格划分以加速最近邻查询
+pub struct Gazetteer {
+    strings: Vec<String>,
+    entries: Vec<Entry>,
+    grid: HashMap<(i32, i32), Vec<u32>>,
+}
+
+impl Gazetteer {
+    /// 解析编译后的索引，格式错误时返回 None
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        let mut reader = Reader { data, offset: 0 };
+        if reader.take(4)? != MAGIC {
+            return None;
+        }
+
+        let string_count = reader.u32()? as usize;
+        let mut strings = Vec::with_capacity(string_count);
+        for _ in 0..string_count {
+            let len = reader.u16()? as usize;
+            strings.push(String::from_utf8_lossy(reader.take(len)?).into_owned());
+        }
+
+        let entry_count = reader.u32()? as usize;
+        let mut entries = Vec::with_capacity(entry_count);
+        let mut grid: HashMap<(i32, i32), Vec<u32>> = HashMap::new();
+        for index in 0..entry_count {
+            let entry = Entry {
+                latitude: f32::from_bits(reader.u32()?),
+                longitude: f32::from_bits(reader.u32()?),
+                city: reader.u32()?,
+                region: reader.u32()?,
+                country: reader.u32()?,
+            };
+            grid.entry(cell(entry.latitude as f64, entry.longitude as f64))
+                .or_default()
+                .push(index as u32);
+            entries.push(entry);
+        }
+
+        Some(Self {
+            strings,
+            entries,
+            grid,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 查找距离坐标最近的地点
+    pub fn lookup(&self, latitude: f64, longitude: f64) -> Option<Place> {
+        if !latitude.is_finite() || !longitude.is_finite() || latitude.abs() > 90.0 {
+            return None;
+        }
+
+        let (row, column) = cell(latitude, longitude);
+        // 搜索范围需覆盖 MAX_DISTANCE_KM，高纬度地区经度方向需要更多单元
+        let lat_cells = (MAX_DISTANCE_KM / (111.0 * CELL_SIZE)).ceil() as i32;
+        let lon_span = latitude.to_radians().cos().max(0.01) * 111.0 * CELL_SIZE;
+        let lon_cells =
+            ((MAX_DISTANCE_KM / lon_span).ceil() as i32).min((180.0 / CELL_SIZE) as i32);
+        let columns = (360.0 / CELL_SIZE) as i32;
+
+        let mut best: Option<(f64, &Entry)> = None;
+        for d_row in -lat_cells..=lat_cells {
+            for d_column in -lon_cells..=lon_cells {
+                // 经度方向跨越 ±180° 时回绕
+                let wrapped = (column + d_column).rem_euclid(columns);
+                let Some(indexes) = self.grid.get(&(row + d_row, wrapped)) else {
+                    continue;
+                };
+                for &index in indexes {
+                    let entry = &self.entries[index as usize];
+                    let distance = haversine_km(
+                        latitude,
+                        longitude,
+                        entry.latitude as f64,
+                        entry.longitude as f64,
+                    );
+                    if best.is_none_or(|(best_distance, _)| distance < best_distance) {
+                        best = Some((distance, entry));
+                    }
+                }
+            }
+        }
+
+        let (distance, entry) = best?;
+        if distance > MAX_DISTANCE_KM {
+            return None;
+        }
+        Some(Place {
+            country: self.name(entry.country),
+            region: self.name(entry.region),
+            city: self.name(entry.city),
+        })
+    }
+
+    fn name(&self, index: u32) -> Option<String> {
+        self.strings.get(index as usize).cloned()
+    }
+}
+
+/// 使用内置地名索引进行反向地理编码，索引不可用或附近没有地点时返回 None
+pub fn reverse_geocode(latitude: f64, longitude: f64) -> Option<Place> {
+    gazetteer()?.lookup(latitude, longitude)
+}
+
+/// 内置地名索引是否可用，构建时索引缺失或校验失败则不可用
+pub fn is_available() -> bool {
+    gazetteer().is_some()
+}
+
+/// 内置地名索引，在首次调用时加载
+fn gazetteer() -> Option<&'static Gazetteer> {
+    static GAZETTEER: OnceLock<Option<Gazetteer>> = OnceLock::new();
+
+    GAZETTEER
+        .get_or_init(|| {
+            if GAZETTEER_DATA.is_empty() {
+                warn!("程序未内置地名索引，离线地理编码不可用");
+                return None;
+            }
+            match Gazetteer::parse(GAZETTEER_DATA) {
+                Some(gazetteer) if !gazetteer.is_empty() => {
+                    info!("已加载离线地名索引，共 {} 个地点", gazetteer.len());
+                    Some(gazetteer)
+                }
+                Some(_) => {
+                    warn!("内置地名索引为空，离线地理编码不可用");
+                    None
+                }
+                None => {
+                    warn!("内置地名索引格式错误，离线地理编码不可用");
+                    None
+                }
+            }
+        })
+        .as_ref()
+}
+
+/// 坐标所在的网格单元 (行, 列)，列从经度 -180° 开始编号
+fn cell(latitude: f64, longitude: f64) -> (i32, i32) {
+    let columns = (360.0 / CELL_SIZE) as i32;
+    let column = ((longitude + 180.0) / CELL_SIZE).floor() as i32;
+    (
+        (latitude / CELL_SIZE).floor() as i32,
+        column.rem_euclid(columns),
+    )
+}
+
+pub(crate) fn haversine_km(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let d_lat = (lat2 - lat1).to_radians();
+    let d_lon = (lon2 - lon1).to_radians();
+    let a = (d_lat / 2.0).sin().powi(2)
+        + lat1.to_radians().cos() * lat2.to_radians().cos() * (d_lon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self.data.get(self.offset..self.offset + len)?;
+        self.offset += len;
+        Some(bytes)
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        Some(u16::from_le_bytes(self.take(2)?.try_into().ok()?))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.take(4)?.try_into().ok()?))
+    }
+}
@@ -0,0 +1,561 @@
+use std::ops::Range;
+
+use chrono::{DateTime, Local, Months, NaiveDate, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::image::{ColorLabel, MediaKind, PickFlag};
+use crate::models::smart_album::{CompareOp, NumericField, SmartRule};
+
+/// 查询语法错误，位置以 UTF-16 编码单位计算，便于前端直接标注输入框中的文本
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryParseError {
+    pub message: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl std::fmt::Display for QueryParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}（位置 {}-{}）", self.message, self.start, self.end)
+    }
+}
+
+impl std::error::Error for QueryParseError {}
+
+/// 解析搜索框中的查询文本，空查询返回匹配全部媒体的规则
+///
+/// 支持的写法：
+/// - 普通词和带引号的短语：`sunset "new york"`，全文搜索并做前缀匹配
+/// - 字段条件：`camera:"X-T4"`、`tag:beach`、`album:Rejects`、`iso:>3200`、
+///   `f:1.4..2.8`、`taken:2021-06..2021-08`、`size:>10mb`、`place:Kyoto`、`is:favorite`、
+///   `has:location`
+/// - `-` 排除，`OR` 表示或，括号分组；相邻条件之间为与
+///
+/// 解析结果与智能相册使用同一种规则树，因此搜索可以直接保存为智能相册。
+pub fn parse_search_query(input: &str) -> Result<SmartRule, QueryParseError> {
+    let tokens = tokenize(input).map_err(|(message, span)| error_at(input, message, span))?;
+    let mut parser = Parser {
+        input,
+        tokens,
+        pos: 0,
+    };
+
+    if parser.tokens.is_empty() {
+        return Ok(SmartRule::All { rules: Vec::new() });
+    }
+
+    let rule = parser.parse_or()?;
+    if let Some(token) = parser.tokens.get(parser.pos) {
+        return Err(parser.error("多余的右括号", token.span.clone()));
+    }
+    Ok(rule)
+}
+
+#[derive(Debug, Clone)]
+enum TokenKind {
+    LParen,
+    RParen,
+    Not,
+    Or,
+    Term(String),
+    Field {
+        name: String,
+        name_span: Range<usize>,
+        value: String,
+        value_span: Range<usize>,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    /// 在输入中的字节范围
+    span: Range<usize>,
+}
+
+type LexError = (String, Range<usize>);
+
+fn tokenize(input: &str) -> Result<Vec<Token>, LexError> {
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+
+    while let Some(c) = input[pos..].chars().next() {
+        let start = pos;
+        match c {
+            c if c.is_whitespace() => pos += c.len_utf8(),
+            '(' => {
+                pos += 1;
+                tokens.push(Token {
+                    kind: TokenKind::LParen,
+                    span: start..pos,
+                });
+            }
+            ')' => {
+                pos += 1;
+                tokens.push(Token {
+                    kind: TokenKind::RParen,
+                    span: start..pos,
+                });
+            }
+            '-' => {
+                pos += 1;
+                match input[pos..].chars().next() {
+                    Some(next) if !next.is_whitespace() && next != ')' => tokens.push(Token {
+                        kind: TokenKind::Not,
+                        span: start..pos,
+                    }),
+                    _ => return Err(("- 后缺少要排除的条件".to_string(), start..pos)),
+                }
+            }
+            '"' => {
+                let (text, end) = read_quoted(input, pos)?;
+                if text.trim().is_empty() {
+                    return Err(("引号中没有内容".to_string(), start..end));
+                }
+                pos = end;
+                tokens.push(Token {
+                    kind: TokenKind::Term(text),
+                    span: start..pos,
+                });
+            }
+            _ => {
+                let name_len = input[pos..]
+                    .find(|c: char| !c.is_ascii_alphabetic())
+                    .unwrap_or(input.len() - pos);
+                if name_len > 0 && input[pos + name_len..].starts_with(':') {
+                    let name_span = pos..pos + name_len;
+                    let value_start = pos + name_len + 1;
+                    let (value, value_end) = if input[value_start..].starts_with('"') {
+                        read_quoted(input, value_start)?
+                    } else {
+                        let end = word_end(input, value_start);
+                        (input[value_start..end].to_string(), end)
+                    };
+                    if value.trim().is_empty() {
+                        return Err((
+                            format!("{} 缺少条件值", &input[name_span.clone()]),
+                            start..value_end,
+                        ));
+                    }
+                    pos = value_end;
+                    tokens.push(Token {
+                        kind: TokenKind::Field {
+                            name: input[name_span.clone()].to_string(),
+                            name_span,
+                            value: value.trim().to_string(),
+                            value_span: value_start..value_end,
+                        },
+                        span: start..pos,
+                    });
+                } else {
+                    pos = word_end(input, pos);
+                    let word = &input[start..pos];
+                    tokens.push(Token {
+                        kind: if word == "OR" {
+                            TokenKind::Or
+                        } else {
+                            TokenKind::Term(word.to_string())
+                        },
+                        span: start..pos,
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// 读取从 `start`（引号处）开始的带引号文本，返回内容和结束位置
+fn read_quoted(input: &str, start: usize) -> Result<(String, usize), LexError> {
+    let content_start = start + 1;
+    match input[content_start..].find('"') {
+        Some(len) => Ok((
+            input[content_start..content_start + len].to_string(),
+            content_start + len + 1,
+        )),
+        None => Err(("引号未闭合".to_string(), start..input.len())),
+    }
+}
+
+/// 普通词在空白或括号处结束
+fn word_end(input: &str, start: usize) -> usize {
+    input[start..]
+        .find(|c: char| c.is_whitespace() || c == '(' || c == ')')
+        .map(|len| start + len)
+        .unwrap_or(input.len())
+}
+
+/// 将字节范围转换为 UTF-16 位置
+fn error_at(input: &str, message: impl Into<String>, span: Range<usize>) -> QueryParseError {
+    QueryParseError {
+        message: message.into(),
+        start: input[..span.start].encode_utf16().count(),
+        end: input[..span.end].encode_utf16().count(),
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: impl Into<String>, span: Range<usize>) -> QueryParseError {
+        error_at(self.input, message, span)
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    /// or_expr := and_expr ("OR" and_expr)*
+    fn parse_or(&mut self) -> Result<SmartRule, QueryParseError> {
+        let mut rules = vec![self.parse_and()?];
+
+        while let Some(Token {
+            kind: TokenKind::Or,
+            span,
+        }) = self.peek().cloned()
+        {
+            self.pos += 1;
+            match self.peek().map(|t| &t.kind) {
+                None | Some(TokenKind::Or) | Some(TokenKind::RParen) => {
+                    return Err(self.error("OR 后缺少条件", span));
+                }
+                _ => rules.push(self.parse_and()?),
+            }
+        }
+
+        Ok(if rules.len() == 1 {
+            rules.remove(0)
+        } else {
+            SmartRule::Any { rules }
+        })
+    }
+
+    /// and_expr := unary+
+    fn parse_and(&mut self) -> Result<SmartRule, QueryParseError> {
+        let mut rules = Vec::new();
+        while let Some(token) = self.peek() {
+            match token.kind {
+                TokenKind::Or | TokenKind::RParen => break,
+                _ => rules.push(self.parse_unary()?),
+            }
+        }
+
+        match (rules.len(), self.peek()) {
+            (0, Some(token)) if matches!(token.kind, TokenKind::Or) => {
+                Err(self.error("OR 前缺少条件", token.span.clone()))
+            }
+            (0, Some(token)) => Err(self.error("多余的右括号", token.span.clone())),
+            (0, None) => Err(self.error("缺少搜索条件", self.input.len()..self.input.len())),
+            (1, _) => Ok(rules.remove(0)),
+            _ => Ok(SmartRule::All { rules }),
+        }
+    }
+
+    /// unary := "-" unary | primary
+    fn parse_unary(&mut self) -> Result<SmartRule, QueryParseError> {
+        if let Some(Token {
+            kind: TokenKind::Not,
+            span,
+        }) = self.peek().cloned()
+        {
+            self.pos += 1;
+            if matches!(
+                self.peek().map(|t| &t.kind),
+                None | Some(TokenKind::Or) | Some(TokenKind::RParen)
+            ) {
+                return Err(self.error("- 后缺少要排除的条件", span));
+            }
+            return Ok(SmartRule::Not {
+                rule: Box::new(self.parse_unary()?),
+            });
+        }
+        self.parse_primary()
+    }
+
+    /// primary := "(" or_expr ")" | term | field
+    fn parse_primary(&mut self) -> Result<SmartRule, QueryParseError> {
+        let token = self
+            .next()
+            .ok_or_else(|| self.error("缺少搜索条件", self.input.len()..self.input.len()))?;
+
+        match token.kind {
+            TokenKind::LParen => {
+                if let Some(close) = self.peek().filter(|t| matches!(t.kind, TokenKind::RParen)) {
+                    return Err(self.error("括号中没有条件", token.span.start..close.span.end));
+                }
+                let rule = self.parse_or()?;
+                match self.next() {
+                    Some(Token {
+                        kind: TokenKind::RParen,
+                        ..
+                    }) => Ok(rule),
+                    _ => Err(self.error("缺少右括号", token.span)),
+                }
+            }
+            TokenKind::Term(text) => Ok(SmartRule::Text { query: text }),
+            TokenKind::Field {
+                name,
+                name_span,
+                value,
+                value_span,
+            } => self.field_rule(&name, name_span, &value, value_span),
+            TokenKind::RParen => Err(self.error("多余的右括号", token.span)),
+            TokenKind::Or => Err(self.error("OR 前缺少条件", token.span)),
+            TokenKind::Not => Err(self.error("- 后缺少要排除的条件", token.span)),
+        }
+    }
+
+    fn field_rule(
+        &self,
+        name: &str,
+        name_span: Range<usize>,
+        value: &str,
+        value_span: Range<usize>,
+    ) -> Result<SmartRule, QueryParseError> {
+        let invalid = |message: String| self.error(message, value_span.clone());
+        let lower = value.to_lowercase();
+
+        let rule = match name.to_lowercase().as_str() {
+            "camera" => SmartRule::Camera {
+                value: value.to_string(),
+            },
+            "lens" => SmartRule::Lens {
+                value: value.to_string(),
+            },
+            "tag" => SmartRule::Tag {
+                path: value.to_string(),
+                include_children: true,
+            },
+            "album" => SmartRule::AlbumName {
+                name: value.to_string(),
+            },
+            "name" => SmartRule::NameContains {
+                value: value.to_string(),
+            },
+            "place" | "country" | "city" => SmartRule::Place {
+                value: value.to_string(),
+            },
+            "folder" | "path" => SmartRule::Folder {
+                value: value.to_string(),
+            },
+            "ext" => SmartRule::Extension {
+                extensions: value.split(',').map(str::to_string).collect(),
+            },
+            "type" | "kind" => SmartRule::Kind {
+                kind: match lower.as_str() {
+                    "image" | "photo" | "照片" | "图片" => MediaKind::Image,
+                    "video" | "视频" => MediaKind::Vedio,
+                    _ => return Err(invalid(format!("未知的媒体类型: {}", value))),
+                },
+            },
+            "label" | "color" => {
+                let labels = value
+                    .split(',')
+                    .map(|label| {
+                        ColorLabel::parse(label)
+                            .ok_or_else(|| invalid(format!("未知的颜色标签: {}", label)))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                SmartRule::ColorLabel { labels }
+            }
+            "is" => match lower.as_str() {
+                "favorite" | "fav" => SmartRule::Favorite,
+                "pick" | "picked" => SmartRule::Flag {
+                    flag: PickFlag::Pick,
+                },
+                "reject" | "rejected" => SmartRule::Flag {
+                    flag: PickFlag::Reject,
+                },
+                "unrated" => SmartRule::Compare {
+                    field: NumericField::Rating,
+                    op: CompareOp::Eq,
+                    value: 0.0,
+                },
+                "noalbum" | "unsorted" => SmartRule::NotInAnyAlbum,
+                _ => return Err(invalid(format!("未知的 is 条件: {}", value))),
+            },
+            "has" => match lower.as_str() {
+                "location" | "gps" => SmartRule::HasLocation,
+                _ => return Err(invalid(format!("未知的 has 条件: {}", value))),
+            },
+            "iso" => self.numeric_rule(NumericField::Iso, value, &value_span, parse_number)?,
+            "f" | "aperture" => {
+                self.numeric_rule(NumericField::Aperture, value, &value_span, |v| {
+                    parse_number(v.trim_start_matches("f/").trim_start_matches('f'))
+                })?
+            }
+            "focal" => self.numeric_rule(NumericField::FocalLength, value, &value_span, |v| {
+                parse_number(v.trim_end_matches("mm"))
+            })?,
+            "rating" | "stars" => {
+                self.numeric_rule(NumericField::Rating, value, &value_span, parse_number)?
+            }
+            "size" => self.numeric_rule(NumericField::Size, value, &value_span, parse_size)?,
+            "width" => self.numeric_rule(NumericField::Width, value, &value_span, parse_number)?,
+            "height" => {
+                self.numeric_rule(NumericField::Height, value, &value_span, parse_number)?
+            }
+            "taken" | "date" => self.date_rule(value, &value_span)?,
+            _ => return Err(self.error(format!("未知的搜索字段: {}", name), name_span)),
+        };
+
+        Ok(rule)
+    }
+
+    /// 数值条件：`x`、`>x`、`>=x`、`<x`、`<=x`、`a..b`（任一端可省略）
+    fn numeric_rule(
+        &self,
+        field: NumericField,
+        value: &str,
+        value_span: &Range<usize>,
+        parse: impl Fn(&str) -> Option<f64>,
+    ) -> Result<SmartRule, QueryParseError> {
+        let invalid = || self.error(format!("无法识别的数值: {}", value), value_span.clone());
+        let mut rules = Vec::new();
+
+        for (op, operand) in split_comparison(value).ok_or_else(invalid)? {
+            rules.push(SmartRule::Compare {
+                field,
+                op,
+                value: parse(&operand.to_lowercase()).ok_or_else(invalid)?,
+            });
+        }
+
+        Ok(if rules.len() == 1 {
+            rules.remove(0)
+        } else {
+            SmartRule::All { rules }
+        })
+    }
+
+    /// 日期条件，日期为本地时间的年、月或日，如 `2021`、`2021-06`、`2021-06-15`
+    fn date_rule(
+        &self,
+        value: &str,
+        value_span: &Range<usize>,
+    ) -> Result<SmartRule, QueryParseError> {
+        let invalid = || {
+            self.error(
+                format!(
+                    "无法识别的日期: {}（应为 YYYY、YYYY-MM 或 YYYY-MM-DD）",
+                    value
+                ),
+                value_span.clone(),
+            )
+        };
+
+        // 结束时间为下一周期的开始，不包含在区间内，带小数秒的时间也能落在最后一秒中
+        let mut from = None;
+        let mut before = None;
+        for (op, operand) in split_comparison(value).ok_or_else(invalid)? {
+            let (start, end) = parse_period(operand).ok_or_else(invalid)?;
+            match op {
+                CompareOp::Eq => {
+                    from = Some(start);
+                    before = Some(end);
+                }
+                CompareOp::Gt => from = Some(end),
+                CompareOp::Gte => from = Some(start),
+                CompareOp::Lt => before = Some(start),
+                CompareOp::Lte => before = Some(end),
+            }
+        }
+
+        Ok(SmartRule::DateRange {
+            from,
+            to: None,
+            before,
+        })
+    }
+}
+
+/// 拆分比较运算符或区间，返回 (运算符, 操作数) 列表
+fn split_comparison(value: &str) -> Option<Vec<(CompareOp, &str)>> {
+    for (prefix, op) in [
+        (">=", CompareOp::Gte),
+        ("<=", CompareOp::Lte),
+        (">", CompareOp::Gt),
+        ("<", CompareOp::Lt),
+        ("=", CompareOp::Eq),
+    ] {
+        if let Some(operand) = value.strip_prefix(prefix) {
+            return Some(vec![(op, operand.trim())]);
+        }
+    }
+
+    if let Some((low, high)) = value.split_once("..") {
+        let mut parts = Vec::new();
+        if !low.trim().is_empty() {
+            parts.push((CompareOp::Gte, low.trim()));
+        }
+        if !high.trim().is_empty() {
+            parts.push((CompareOp::Lte, high.trim()));
+        }
+        return if parts.is_empty() { None } else { Some(parts) };
+    }
+
+    Some(vec![(CompareOp::Eq, value.trim())])
+}
+
+fn parse_number(value: &str) -> Option<f64> {
+    value.trim().parse::<f64>().ok().filter(|v| v.is_finite())
+}
+
+/// 解析带单位的文件大小，如 `500kb`、`10mb`、`1.5gb`，单位按 1024 进位
+fn parse_size(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let digits = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(value.len());
+    let multiplier = match value[digits..].trim() {
+        "" | "b" => 1.0,
+        "k" | "kb" => 1024.0,
+        "m" | "mb" => 1024.0 * 1024.0,
+        "g" | "gb" => 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    parse_number(&value[..digits]).map(|n| n * multiplier)
+}
+
+/// 解析年、月或日，返回本地时间下该周期的开始和下一周期的开始
+fn parse_period(value: &str) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    let parts: Vec<&str> = value.split('-').collect();
+    let number = |index: usize| parts.get(index)?.parse::<u32>().ok();
+
+    let year = parts.first()?.parse::<i32>().ok()?;
+    let (start, end) = match parts.len() {
+        1 => {
+            let start = NaiveDate::from_ymd_opt(year, 1, 1)?;
+            (start, start.checked_add_months(Months::new(12))?)
+        }
+        2 => {
+            let start = NaiveDate::from_ymd_opt(year, number(1)?, 1)?;
+            (start, start.checked_add_months(Months::new(1))?)
+        }
+        3 => {
+            let start = NaiveDate::from_ymd_opt(year, number(1)?, number(2)?)?;
+            (start, start.succ_opt()?)
+        }
+        _ => return None,
+    };
+
+    let local_midnight = |date: NaiveDate| {
+        Local
+            .from_local_datetime(&date.and_hms_opt(0, 0, 0)?)
+            .earliest()
+            .map(|dt| dt.with_timezone(&Utc))
+    };
+    Some((local_midnight(start)?, local_midnight(end)?))
+}
@@ -0,0 +1,95 @@
+use std::fs;
+
+use ab_glyph::{point, Font, FontVec, Glyph, PxScale, ScaleFont};
+use image::{Rgba, RgbaImage};
+
+/// 读取 TrueType / OpenType 字体文件
+pub fn load_font(path: &str) -> Result<FontVec, String> {
+    let data = fs::read(path).map_err(|e| format!("无法读取字体 {}: {}", path, e))?;
+    FontVec::try_from_vec(data).map_err(|e| format!("无法解析字体 {}: {}", path, e))
+}
+
+/// 解析 `#RRGGBB` 格式的颜色
+pub fn parse_color(color: &str) -> Result<Rgba<u8>, String> {
+    let invalid = || format!("颜色格式无效，应为 #RRGGBB: {}", color);
+    let hex = color.strip_prefix('#').ok_or_else(invalid)?;
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(invalid());
+    }
+
+    let channel =
+        |index: usize| u8::from_str_radix(&hex[index..index + 2], 16).map_err(|_| invalid());
+    Ok(Rgba([channel(0)?, channel(2)?, channel(4)?, 255]))
+}
+
+/// 单行文字的宽度（像素）
+pub fn text_width(font: &FontVec, text: &str, font_size: f32) -> f32 {
+    layout(font, text, font_size).1
+}
+
+/// 截断文字使宽度不超过 `max_width`，被截断时以 `…` 结尾
+pub fn fit_text(font: &FontVec, text: &str, font_size: f32, max_width: f32) -> String {
+    if text_width(font, text, font_size) <= max_width {
+        return text.to_string();
+    }
+
+    let mut chars: Vec<char> = text.chars().collect();
+    while !chars.is_empty() {
+        chars.pop();
+        let candidate: String = chars.iter().chain(['…'].iter()).collect();
+        if text_width(font, &candidate, font_size) <= max_width {
+            return candidate;
+        }
+    }
+    String::new()
+}
+
+/// 将单行文字绘制到与文字大小相同的透明图层上
+pub fn render_text(font: &FontVec, text: &str, font_size: f32, color: Rgba<u8>) -> RgbaImage {
+    let scaled = font.as_scaled(PxScale::from(font_size));
+    let (glyphs, caret) = layout(font, text, font_size);
+
+    let width = caret.ceil().max(1.0) as u32;
+    let height = (scaled.ascent() - scaled.descent()).ceil().max(1.0) as u32;
+    let mut layer = RgbaImage::from_pixel(width, height, Rgba([color[0], color[1], color[2], 0]));
+    for glyph in glyphs {
+        let Some(outlined) = font.outline_glyph(glyph) else {
+            continue;
+        };
+        let bounds = outlined.px_bounds();
+        outlined.draw(|x, y, coverage| {
+            let x = bounds.min.x as i64 + x as i64;
+            let y = bounds.min.y as i64 + y as i64;
+            if x < 0 || y < 0 || x >= width as i64 || y >= height as i64 {
+                return;
+            }
+            // 相邻字形可能重叠，取覆盖率较大的值
+            let pixel = layer.get_pixel_mut(x as u32, y as u32);
+            let alpha = (coverage.clamp(0.0, 1.0) * 255.0).round() as u8;
+            pixel[3] = pixel[3].max(alpha);
+        });
+    }
+
+    layer
+}
+
+/// 排列字形，返回字形及文字的总宽度，控制字符被忽略
+fn layout(font: &FontVec, text: &str, font_size: f32) -> (Vec<Glyph>, f32) {
+    let scale = PxScale::from(font_size);
+    let scaled = font.as_scaled(scale);
+
+    let mut glyphs = Vec::new();
+    let mut caret = 0.0;
+    let mut previous = None;
+    for c in text.chars().filter(|c| !c.is_control()) {
+        let glyph_id = scaled.glyph_id(c);
+        if let Some(previous) = previous {
+            caret += scaled.kern(previous, glyph_id);
+        }
+        glyphs.push(glyph_id.with_scale_and_position(scale, point(caret, scaled.ascent())));
+        caret += scaled.h_advance(glyph_id);
+        previous = Some(glyph_id);
+    }
+
+    (glyphs, caret)
+}
@@ -0,0 +1,188 @@
+use image::{imageops, imageops::FilterType, DynamicImage, RgbaImage};
+
+use crate::models::export::{BorderSettings, WatermarkAnchor, WatermarkContent, WatermarkSettings};
+use crate::utils::text_render::{load_font, parse_color, render_text, text_width};
+
+/// 水印字号、文字宽度和图片宽度最多为图片长边的倍数，防止生成过大的图层
+const MAX_WATERMARK_RATIO: u32 = 4;
+
+/// 检查水印设置，字体和图片文件在导出时才读取
+pub fn validate_watermark(watermark: &WatermarkSettings) -> Result<(), String> {
+    if !(0.0..=1.0).contains(&watermark.opacity) {
+        return Err(format!(
+            "水印不透明度必须在 0 到 1 之间: {}",
+            watermark.opacity
+        ));
+    }
+
+    match &watermark.content {
+        WatermarkContent::Text {
+            text,
+            font_path,
+            font_size,
+            color,
+        } => {
+            if text.trim().is_empty() {
+                return Err("水印文字不能为空".to_string());
+            }
+            if font_path.is_empty() {
+                return Err("未指定水印字体".to_string());
+            }
+            if font_size.is_nan() || *font_size <= 0.0 {
+                return Err(format!("水印字号必须大于 0: {}", font_size));
+            }
+            parse_color(color)?;
+        }
+        WatermarkContent::Image { path, width } => {
+            if path.is_empty() {
+                return Err("未指定水印图片".to_string());
+            }
+            if *width == Some(0) {
+                return Err("水印宽度必须大于 0".to_string());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// 检查边框设置
+pub fn validate_border(border: &BorderSettings) -> Result<(), String> {
+    parse_color(&border.color)?;
+    Ok(())
+}
+
+/// 在图片上绘制水印，超出图片的部分被裁掉
+///
+/// 字号、文字宽度或缩放后的水印图片超过图片长边的 `MAX_WATERMARK_RATIO` 倍时返回错误。
+pub fn apply_watermark(image: &mut RgbaImage, watermark: &WatermarkSettings) -> Result<(), String> {
+    validate_watermark(watermark)?;
+    let max_size = image
+        .width()
+        .max(image.height())
+        .saturating_mul(MAX_WATERMARK_RATIO);
+
+    let mut layer = match &watermark.content {
+        WatermarkContent::Text {
+            text,
+            font_path,
+            font_size,
+            color,
+        } => {
+            if *font_size > max_size as f32 {
+                return Err(format!(
+                    "水印字号 {} 超过图片长边的 {} 倍",
+                    font_size, MAX_WATERMARK_RATIO
+                ));
+            }
+            let font = load_font(font_path)?;
+            if text_width(&font, text, *font_size) > max_size as f32 {
+                return Err(format!(
+                    "水印文字宽度超过图片长边的 {} 倍，请缩短文字或减小字号",
+                    MAX_WATERMARK_RATIO
+                ));
+            }
+            render_text(&font, text, *font_size, parse_color(color)?)
+        }
+        WatermarkContent::Image { path, width } => {
+            let logo = image::open(path)
+                .map_err(|e| format!("无法读取水印图片 {}: {}", path, e))?
+                .to_rgba8();
+            match width {
+                Some(width) if *width != logo.width() => {
+                    let height =
+                        (logo.height() as u64 * *width as u64 / logo.width() as u64).max(1);
+                    if *width > max_size || height > max_size as u64 {
+                        return Err(format!(
+                            "缩放后的水印图片超过图片长边的 {} 倍",
+                            MAX_WATERMARK_RATIO
+                        ));
+                    }
+                    imageops::resize(&logo, *width, height as u32, FilterType::Lanczos3)
+                }
+                _ => logo,
+            }
+        }
+    };
+
+    if watermark.opacity < 1.0 {
+        for pixel in layer.pixels_mut() {
+            pixel[3] = (pixel[3] as f32 * watermark.opacity).round() as u8;
+        }
+    }
+    let (x, y) = anchor_position(
+        watermark.anchor,
+        image.dimensions(),
+        layer.dimensions(),
+        watermark.margin,
+    );
+    imageops::overlay(image, &layer, x, y);
+
+    Ok(())
+}
+
+/// 在图片外侧加边框，保持原图的颜色类型（有无透明通道）
+///
+/// 边框宽度不能超过图片的长边。
+pub fn add_border(image: &DynamicImage, border: &BorderSettings) -> Result<DynamicImage, String> {
+    let color = parse_color(&border.color)?;
+    if border.width == 0 {
+        return Ok(image.clone());
+    }
+    if border.width > image.width().max(image.height()) {
+        return Err(format!(
+            "边框宽度 {} 超过图片长边 {}",
+            border.width,
+            image.width().max(image.height())
+        ));
+    }
+
+    let width = image.width().saturating_add(border.width.saturating_mul(2));
+    let height = image
+        .height()
+        .saturating_add(border.width.saturating_mul(2));
+    let mut canvas = RgbaImage::from_pixel(width, height, color);
+    imageops::replace(
+        &mut canvas,
+        &image.to_rgba8(),
+        border.width as i64,
+        border.width as i64,
+    );
+
+    let canvas = DynamicImage::ImageRgba8(canvas);
+    if image.color().has_alpha() {
+        Ok(canvas)
+    } else {
+        Ok(DynamicImage::ImageRgb8(canvas.to_rgb8()))
+    }
+}
+
+/// 按位置和边距计算水印左上角的坐标
+fn anchor_position(
+    anchor: WatermarkAnchor,
+    (width, height): (u32, u32),
+    (layer_width, layer_height): (u32, u32),
+    margin: u32,
+) -> (i64, i64) {
+    let (width, height, margin) = (width as i64, height as i64, margin as i64);
+    let (layer_width, layer_height) = (layer_width as i64, layer_height as i64);
+
+    let left = margin;
+    let center_x = (width - layer_width) / 2;
+    let right = width - layer_width - margin;
+    let top = margin;
+    let center_y = (height - layer_height) / 2;
+    let bottom = height - layer_height - margin;
+
+    match anchor {
+        WatermarkAnchor::TopLeft => (left, top),
+        WatermarkAnchor::Top => (center_x, top),
+        WatermarkAnchor::TopRight => (right, top),
+        WatermarkAnchor::Left => (left, center_y),
+        WatermarkAnchor::Center => (center_x, center_y),
+        WatermarkAnchor::Right => (right, center_y),
+        WatermarkAnchor::BottomLeft => (left, bottom),
+        WatermarkAnchor::Bottom => (center_x, bottom),
+        WatermarkAnchor::BottomRight => (right, bottom),
+    }
+}
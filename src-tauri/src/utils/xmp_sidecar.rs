@@ -0,0 +1,185 @@
+use std::{
+    fs, io,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use crate::models::image::{ColorLabel, PickFlag};
+
+/// xmp 命名空间
+const XMP_NAMESPACE: &str = "http://ns.adobe.com/xap/1.0/";
+
+/// 新建边车文件时使用的模板
+const SIDECAR_TEMPLATE: &str = r#"<x:xmpmeta xmlns:x="adobe:ns:meta/">
+ <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+  <rdf:Description rdf:about=""
+    xmlns:xmp="http://ns.adobe.com/xap/1.0/">
+  </rdf:Description>
+ </rdf:RDF>
+</x:xmpmeta>
+"#;
+
+/// XMP 边车文件中的评分和颜色标签
+///
+/// 按 Adobe 的约定，`xmp:Rating` 为 -1 表示排除。
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct XmpMarks {
+    pub rating: u8,
+    pub color_label: Option<ColorLabel>,
+    pub rejected: bool,
+}
+
+/// 查找媒体文件已有的边车文件，支持 `IMG_0001.xmp` 和 `IMG_0001.CR2.xmp` 两种命名
+pub fn find_sidecar(media_path: &Path) -> Option<PathBuf> {
+    sidecar_candidates(media_path)
+        .into_iter()
+        .find(|path| path.is_file())
+}
+
+/// 媒体文件移动或重命名后边车文件的新路径，沿用原边车文件的命名方式
+pub fn moved_sidecar_path(media_path: &Path, sidecar: &Path, new_media_path: &Path) -> PathBuf {
+    if sidecar == media_path.with_extension("xmp") {
+        new_media_path.with_extension("xmp")
+    } else {
+        let mut full_name = new_media_path.as_os_str().to_os_string();
+        full_name.push(".xmp");
+        PathBuf::from(full_name)
+    }
+}
+
+/// 读取边车文件中的评分和颜色标签，没有边车文件时返回 None
+pub fn read_xmp_marks(media_path: &Path) -> Option<XmpMarks> {
+    let xml = fs::read_to_string(find_sidecar(media_path)?).ok()?;
+
+    let rating = get_property(&xml, "xmp:Rating")
+        .and_then(|value| value.trim().parse::<f64>().ok())
+        .unwrap_or(0.0);
+    let color_label = get_property(&xml, "xmp:Label").and_then(|value| ColorLabel::parse(&value));
+
+    Some(XmpMarks {
+        rating: rating.clamp(0.0, 5.0).round() as u8,
+        color_label,
+        rejected: rating < 0.0,
+    })
+}
+
+/// 将评分、颜色标签和排除标记写入边车文件，保留文件中的其他内容
+///
+/// 没有边车文件时新建 `<文件名>.xmp`，返回写入的文件路径。
+pub fn write_xmp_marks(
+    media_path: &Path,
+    rating: u8,
+    color_label: Option<ColorLabel>,
+    flag: PickFlag,
+) -> io::Result<PathBuf> {
+    let (sidecar, mut xml) = match find_sidecar(media_path) {
+        Some(path) => {
+            let xml = fs::read_to_string(&path)?;
+            (path, xml)
+        }
+        None => (
+            media_path.with_extension("xmp"),
+            SIDECAR_TEMPLATE.to_string(),
+        ),
+    };
+
+    let rating = match flag {
+        PickFlag::Reject => Some("-1".to_string()),
+        _ if rating > 0 => Some(rating.min(5).to_string()),
+        _ => None,
+    };
+    set_property(&mut xml, "xmp:Rating", rating.as_deref())?;
+    set_property(
+        &mut xml,
+        "xmp:Label",
+        color_label.map(|label| label.as_str()),
+    )?;
+
+    // 先写临时文件再替换，避免写入中断时损坏原有边车文件
+    let temp_path = sidecar.with_extension("xmp.tmp");
+    fs::write(&temp_path, xml)?;
+    fs::rename(&temp_path, &sidecar)?;
+
+    Ok(sidecar)
+}
+
+fn sidecar_candidates(media_path: &Path) -> [PathBuf; 2] {
+    let mut full_name = media_path.as_os_str().to_os_string();
+    full_name.push(".xmp");
+    [media_path.with_extension("xmp"), PathBuf::from(full_name)]
+}
+
+/// 属性值在 XML 中的位置
+enum PropertySpan {
+    /// `name="value"` 形式，`whole` 包含前导空白
+    Attribute {
+        whole: Range<usize>,
+        value: Range<usize>,
+    },
+    /// `<name>value</name>` 形式
+    Element {
+        whole: Range<usize>,
+        value: Range<usize>,
+    },
+}
+
+fn find_property(xml: &str, name: &str) -> Option<PropertySpan> {
+    for quote in ['"', '\''] {
+        let pattern = format!("{}={}", name, quote);
+        if let Some(index) = xml.find(&pattern) {
+            let value_start = index + pattern.len();
+            let value_end = value_start + xml[value_start..].find(quote)?;
+            let whole_start = xml[..index].trim_end().len();
+            return Some(PropertySpan::Attribute {
+                whole: whole_start..value_end + 1,
+                value: value_start..value_end,
+            });
+        }
+    }
+
+    let open = format!("<{}>", name);
+    let close = format!("</{}>", name);
+    let start = xml.find(&open)?;
+    let value_start = start + open.len();
+    let value_end = value_start + xml[value_start..].find(&close)?;
+    Some(PropertySpan::Element {
+        whole: start..value_end + close.len(),
+        value: value_start..value_end,
+    })
+}
+
+fn get_property(xml: &str, name: &str) -> Option<String> {
+    match find_property(xml, name)? {
+        PropertySpan::Attribute { value, .. } | PropertySpan::Element { value, .. } => {
+            Some(xml[value].to_string())
+        }
+    }
+}
+
+/// 设置或删除属性，已有属性原地修改，否则以属性形式添加到第一个 `rdf:Description`
+fn set_property(xml: &mut String, name: &str, value: Option<&str>) -> io::Result<()> {
+    match (find_property(xml, name), value) {
+        (Some(PropertySpan::Attribute { value: range, .. }), Some(value))
+        | (Some(PropertySpan::Element { value: range, .. }), Some(value)) => {
+            xml.replace_range(range, value);
+        }
+        (Some(PropertySpan::Attribute { whole, .. }), None)
+        | (Some(PropertySpan::Element { whole, .. }), None) => {
+            xml.replace_range(whole, "");
+        }
+        (None, Some(value)) => {
+            let tag = "<rdf:Description";
+            let insert_at = xml
+                .find(tag)
+                .map(|index| index + tag.len())
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "无法识别的 XMP 文件"))?;
+            let mut attributes = format!(" {}=\"{}\"", name, value);
+            if !xml.contains("xmlns:xmp=") {
+                attributes.push_str(&format!(" xmlns:xmp=\"{}\"", XMP_NAMESPACE));
+            }
+            xml.insert_str(insert_at, &attributes);
+        }
+        (None, None) => {}
+    }
+    Ok(())
+}
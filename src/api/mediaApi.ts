@@ -1,24 +1,19 @@
 import { tauriClient } from "./tauriClient";
 import { ListRequest, ListResponse } from "../types/filters";
-import { MediaRecord } from "../types/models";
+import { MediaRecord, SearchResult } from "../types/models";
 
 export const mediaApi = {
     async listMedia(req: ListRequest): Promise<ListResponse<MediaRecord>> {
-        // 从数据库获取所有媒体记录
-        const allRecords = await tauriClient.call<MediaRecord[]>("get_media_list");
-
-        // 简单分页和过滤实现
         const { pagination = { page: 1, pageSize: 20 }, sort, filters } = req;
-        let filteredRecords = allRecords;
 
-        // 应用关键词过滤
-        if (filters?.query) {
-            const keyword = filters.query.toLowerCase();
-            filteredRecords = filteredRecords.filter(record =>
-                record.name.toLowerCase().includes(keyword) ||
-                record.path.toLowerCase().includes(keyword)
-            );
-        }
+        // 有关键词时使用后端全文搜索（按相关度排序），否则获取所有媒体记录
+        const keyword = filters?.query?.trim();
+        const allRecords = keyword
+            ? await tauriClient.call<SearchResult[]>("search_media", { query: keyword })
+            : await tauriClient.call<MediaRecord[]>("get_media_list");
+
+        // 简单分页和过滤实现
+        let filteredRecords: MediaRecord[] = allRecords;
 
         // 应用媒体类型过滤
         if (filters?.kind) {
@@ -22,7 +22,404 @@ export interface MediaRecord {
     updatedAt: Timestamp;
     tags?: string[];
     albumIds?: UUID[];
-    facesCount?: number;
+    /** 检测到的人脸数量，未检测时为空 */
+    faceCount?: number | null;
+    motionPath?: string | null;
+    motionOffset?: number | null;
+    motionLength?: number | null;
+    cameraMake?: string | null;
+    cameraModel?: string | null;
+    lensModel?: string | null;
+    iso?: number | null;
+    aperture?: number | null;
+    focalLength?: number | null;
+    latitude?: number | null;
+    longitude?: number | null;
+    /** 由 GPS 坐标离线反向地理编码得到的地名 */
+    country?: string | null;
+    region?: string | null;
+    city?: string | null;
+    rating: number;
+    favorite: boolean;
+    colorLabel?: ColorLabel | null;
+    flag: PickFlag;
+    caption?: string | null;
+}
+
+export interface SearchResult extends MediaRecord {
+    rank: number;
+    /** 匹配片段，关键词以 <mark> 包裹，其余内容已转义 */
+    snippet?: string | null;
+}
+
+export type ColorLabel = "red" | "yellow" | "green" | "blue" | "purple";
+
+export type PickFlag = "none" | "pick" | "reject";
+
+export interface MediaFilter {
+    minRating?: number | null;
+    favorite?: boolean | null;
+    colorLabels?: ColorLabel[];
+    flags?: PickFlag[];
+}
+
+export interface MediaSort {
+    field: "createdAt" | "takenDate" | "name" | "size" | "rating";
+    ascending?: boolean;
+}
+
+export type TimelineGranularity = "year" | "month" | "day";
+
+export interface TimelineBucket {
+    /** 按粒度为 2024、2024-05 或 2024-05-17（本地时间） */
+    key: string;
+    year: number;
+    month?: number | null;
+    day?: number | null;
+    count: number;
+    /** 分组中第一条媒体在媒体列表中的位置 */
+    offset: number;
+}
+
+export type MemoryKind = "onThisDay" | "trip" | "event";
+
+export interface MemoryRecord {
+    id: UUID;
+    kind: MemoryKind;
+    title: string;
+    subtitle?: string | null;
+    startDate: Timestamp;
+    endDate: Timestamp;
+    coverMediaId?: UUID | null;
+    coverThumbnailPath?: string | null;
+    mediaCount: number;
+    createdAt: Timestamp;
+    updatedAt: Timestamp;
+}
+
+export interface StatGroup {
+    /** 为空表示未知，例如没有相机信息 */
+    key?: string | null;
+    count: number;
+    bytes: number;
+}
+
+export interface LibraryStats {
+    totalCount: number;
+    totalBytes: number;
+    byKind: StatGroup[];
+    /** 按 MIME 类型分组 */
+    byFormat: StatGroup[];
+    byYear: StatGroup[];
+    byCamera: StatGroup[];
+    byFolder: StatGroup[];
+    thumbnailBytes: number;
+    /** 文件夹存在但文件已不存在 */
+    missingCount: number;
+    /** 文件夹不可访问，例如外接硬盘未连接 */
+    offlineCount: number;
+    /** 回收站中的媒体，不计入以上统计 */
+    trashedCount: number;
+    trashedBytes: number;
+    generatedAt: Timestamp;
+}
+
+/** 删除媒体时对原文件的处理方式：仅移出媒体库、移到系统回收站或移到应用回收站目录 */
+export type TrashFileAction = "keep" | "system" | "managed";
+
+export interface TrashedMedia {
+    record: MediaRecord;
+    trashedAt: Timestamp;
+    fileAction: TrashFileAction;
+    /** 到期后自动彻底删除，未设置保留期时为空 */
+    expiresAt?: Timestamp | null;
+}
+
+/** 可撤销的操作记录 */
+export interface JournalEntry {
+    id: number;
+    label: string;
+    changeCount: number;
+    /** 已撤销，可以重做 */
+    undone: boolean;
+    createdAt: Timestamp;
+}
+
+export interface BackupInfo {
+    /** 备份目录中的文件名，恢复时使用 */
+    fileName: string;
+    path: string;
+    size: number;
+    createdAt: Timestamp;
+}
+
+export interface BackupSettings {
+    /** 自动备份间隔（小时），0 表示不自动备份 */
+    intervalHours: number;
+    keepCount: number;
+}
+
+export interface MissingMedia {
+    mediaId: UUID;
+    path: string;
+    /** 所在文件夹也不存在，可能是外接硬盘未连接 */
+    offline: boolean;
+}
+
+/** 匹配方式，按可信程度排列 */
+export type RelinkMatchKind = "hash" | "sizeAndModified" | "name";
+
+export interface RelinkProposal {
+    mediaId: UUID;
+    oldPath: string;
+    newPath: string;
+    matchKind: RelinkMatchKind;
+}
+
+export interface RelinkMatch {
+    mediaId: UUID;
+    newPath: string;
+}
+
+export interface IntegrityReport {
+    databaseErrors: string[];
+    orphanedThumbnails: string[];
+    /** 原文件已不存在的媒体，离线的媒体不计入 */
+    missingMedia: MissingMedia[];
+    invalidJsonMediaIds: UUID[];
+    repaired: boolean;
+}
+
+/** 导入到媒体库文件夹的方式：复制保留原文件，移动在校验后删除原文件 */
+export type ImportTransferMode = 'copy' | 'move';
+
+export interface LibraryImportSettings {
+    /** 媒体库文件夹，未设置时不能复制或移动导入 */
+    libraryRoot?: string | null;
+    /** 相对路径模板，如 `{year}/{year}-{month}-{day}/{camera}_{seq}.{ext}` */
+    pathTemplate: string;
+}
+
+export interface ImportedFile {
+    mediaId: UUID;
+    sourcePath: string;
+    path: string;
+}
+
+export interface ImportFailure {
+    sourcePath: string;
+    error: string;
+}
+
+export interface LibraryImportSummary {
+    imported: ImportedFile[];
+    /** 内容已在媒体库中而跳过的文件 */
+    duplicates: string[];
+    failed: ImportFailure[];
+}
+
+/** 重命名或移动文件时的冲突：原文件不存在、新位置已有文件、同一批中新路径重复 */
+export type RenameConflict = 'sourceMissing' | 'targetExists' | 'duplicateTarget';
+
+export interface RenamePreview {
+    mediaId: UUID;
+    oldPath: string;
+    newPath: string;
+    /** 有冲突时不能执行 */
+    conflict?: RenameConflict | null;
+}
+
+/** 整理文件时新位置已有文件的处理方式：跳过或在文件名后加编号 */
+export type CollisionPolicy = 'skip' | 'rename';
+
+export interface OrganizeOptions {
+    /** 为空时整理媒体库中的所有媒体 */
+    mediaIds?: UUID[] | null;
+    targetRoot: string;
+    /** 文件夹模板，如 `{year}/{month}`、`{camera}`、`{album}`、`{person}`、`{country}/{city}` */
+    folderTemplate: string;
+    collisionPolicy?: CollisionPolicy;
+    /** 只生成计划，不移动文件 */
+    dryRun?: boolean;
+}
+
+export interface OrganizeResult {
+    moves: RenamePreview[];
+    moved: number;
+    unchanged: number;
+    skipped: number;
+    failed: number;
+    dryRun: boolean;
+}
+
+export interface OrganizeProgressEvent {
+    current: number;
+    total: number;
+}
+
+/** 导出格式，`original` 为原图；WebP 为无损编码 */
+export type ExportFormat = 'original' | 'jpeg' | 'png' | 'webp' | 'avif';
+
+/** 导出时对元数据的处理方式 */
+export type MetadataPolicy = 'keepAll' | 'stripGps' | 'stripAll';
+
+/** 水印在图片中的位置 */
+export type WatermarkAnchor =
+    | 'topLeft'
+    | 'top'
+    | 'topRight'
+    | 'left'
+    | 'center'
+    | 'right'
+    | 'bottomLeft'
+    | 'bottom'
+    | 'bottomRight';
+
+export type WatermarkContent =
+    /** 单行文字，fontSize 为导出后图片中的像素，color 为 `#RRGGBB` */
+    | { type: 'text'; text: string; fontPath: string; fontSize: number; color: string }
+    /** 图片水印（如 PNG 标志），width 为缩放后的宽度 */
+    | { type: 'image'; path: string; width?: number | null };
+
+export interface WatermarkSettings {
+    content: WatermarkContent;
+    /** 默认右下角 */
+    anchor?: WatermarkAnchor;
+    /** 与图片边缘的距离（像素） */
+    margin?: number;
+    /** 不透明度（0-1），默认 1 */
+    opacity?: number;
+}
+
+/** 加在图片外侧的边框 */
+export interface BorderSettings {
+    width: number;
+    /** `#RRGGBB` */
+    color: string;
+}
+
+export interface ExportSettings {
+    format?: ExportFormat;
+    /** 长边的最大像素，只缩小不放大 */
+    longEdge?: number | null;
+    /** JPEG 和 AVIF 的质量（1-100），默认 90 */
+    quality?: number | null;
+    /** 带有色彩配置文件的图片转换为 sRGB */
+    convertToSrgb?: boolean;
+    /** 文件名模板，不含扩展名，为空时使用原文件名 */
+    fileNameTemplate?: string | null;
+    metadataPolicy?: MetadataPolicy;
+    /** 水印和边框只能用于转换格式的导出 */
+    watermark?: WatermarkSettings | null;
+    border?: BorderSettings | null;
+}
+
+export interface ExportPresetRecord {
+    id: UUID;
+    name: string;
+    settings: ExportSettings;
+    createdAt: Timestamp;
+    updatedAt: Timestamp;
+}
+
+export interface ExportFailure {
+    mediaId: UUID;
+    path: string;
+    error: string;
+}
+
+export interface ExportSummary {
+    exported: string[];
+    failed: ExportFailure[];
+    /** 导出文件的总大小（字节） */
+    totalSize: number;
+}
+
+export interface ExportProgressEvent {
+    current: number;
+    total: number;
+    currentFile: string;
+}
+
+/** 静态网站写入空文件夹或 zip 文件 */
+export type GalleryOutput = 'folder' | 'zip';
+
+export interface GalleryOptions {
+    title: string;
+    /** 导出的相册，与 mediaIds 二选一 */
+    albumId?: string | null;
+    mediaIds?: UUID[] | null;
+    /** 目标文件夹或 zip 文件的绝对路径 */
+    target: string;
+    output?: GalleryOutput;
+    /** 每个索引页的照片数，默认 60 */
+    pageSize?: number;
+    /** 大图长边的像素，默认 2048 */
+    imageLongEdge?: number;
+    /** 在照片页显示拍摄参数 */
+    includeExif?: boolean;
+    /** 生成地图页，默认只显示经纬网和照片位置，离线可用 */
+    includeMap?: boolean;
+    /** 地图页叠加在线的 OpenStreetMap 底图，浏览地图时需要联网 */
+    onlineMapTiles?: boolean;
+    metadataPolicy?: MetadataPolicy;
+}
+
+export interface GallerySummary {
+    outputPath: string;
+    photos: number;
+    pages: number;
+    /** 视频等无法加入网站的媒体及读取失败的照片 */
+    skipped: ExportFailure[];
+}
+
+export interface GalleryExportProgressEvent {
+    current: number;
+    total: number;
+}
+
+/** 小样输出为多页 PDF 或每页一个 JPEG */
+export type ContactSheetFormat = 'pdf' | 'jpeg';
+
+/** 纸张尺寸（纵向），自定义尺寸以毫米为单位 */
+export type PageSize =
+    | 'a4'
+    | 'a3'
+    | 'letter'
+    | 'legal'
+    | { custom: { widthMm: number; heightMm: number } };
+
+export interface ContactSheetOptions {
+    mediaIds: UUID[];
+    /** 输出文件的绝对路径，JPEG 有多页时依次命名为 `名称-1.jpg`、`名称-2.jpg` */
+    target: string;
+    format?: ContactSheetFormat;
+    pageSize?: PageSize;
+    landscape?: boolean;
+    /** 页边距（毫米），默认 10 */
+    marginMm?: number;
+    /** 每行的照片数，默认 4，行数自动计算 */
+    columns?: number;
+    /** 渲染分辨率，默认 150 */
+    dpi?: number;
+    /** 文字使用的 TrueType / OpenType 字体文件 */
+    fontPath: string;
+    title?: string | null;
+    /** 显示相机、光圈等拍摄参数 */
+    includeExif?: boolean;
+}
+
+export interface ContactSheetSummary {
+    files: string[];
+    pages: number;
+    photos: number;
+    /** 视频等无法加入小样的媒体及读取失败的照片 */
+    skipped: ExportFailure[];
+}
+
+export interface ContactSheetProgressEvent {
+    current: number;
+    total: number;
 }
 
 export interface MetadataRecord {
@@ -40,8 +437,11 @@ export interface FaceRecord {
     id: UUID;
     mediaId: UUID;
     personId?: UUID | null;
+    /** [x, y, 宽, 高]，为相对图片宽高的比例 */
     bbox: [number, number, number, number];
     confidence?: number | null;
+    /** 归属是否由用户确认 */
+    confirmed: boolean;
     createdAt: Timestamp;
     updatedAt: Timestamp;
 }
@@ -49,14 +449,114 @@ export interface FaceRecord {
 export interface PersonRecord {
     id: UUID;
     name?: string | null;
+    coverFaceId?: UUID | null;
+    coverMediaId?: UUID | null;
+    coverThumbnailPath?: string | null;
+    coverBbox?: [number, number, number, number] | null;
+    faceCount: number;
+    photoCount: number;
     createdAt: Timestamp;
     updatedAt: Timestamp;
 }
 
+export interface ClusterSummary {
+    assignedFaces: number;
+    newPeople: number;
+    clusteredFaces: number;
+    unassignedFaces: number;
+}
+
+export interface MapBounds {
+    north: number;
+    south: number;
+    east: number;
+    west: number;
+}
+
+export interface MapCluster {
+    latitude: number;
+    longitude: number;
+    count: number;
+    mediaId: UUID;
+    thumbnailPath?: string | null;
+    bounds: MapBounds;
+}
+
+export interface PlaceCount {
+    country?: string | null;
+    region?: string | null;
+    city?: string | null;
+    mediaCount: number;
+}
+
 export interface AlbumRecord {
     id: UUID;
     name: string;
     description?: string | null;
+    parentId?: UUID | null;
+    isFolder: boolean;
+    coverMediaId?: UUID | null;
+    coverThumbnailPath?: string | null;
+    position: number;
+    mediaCount: number;
+    createdAt: Timestamp;
+    updatedAt: Timestamp;
+}
+
+export type SmartRule =
+    | { type: "all"; rules: SmartRule[] }
+    | { type: "any"; rules: SmartRule[] }
+    | { type: "not"; rule: SmartRule }
+    | { type: "dateRange"; from?: Timestamp | null; to?: Timestamp | null; before?: Timestamp | null }
+    | { type: "recentDays"; days: number }
+    | { type: "camera"; value: string }
+    | { type: "lens"; value: string }
+    | { type: "tag"; path: string; includeChildren?: boolean }
+    | { type: "kind"; kind: "image" | "vedio" | "other" }
+    | { type: "extension"; extensions: string[] }
+    | { type: "size"; min?: number | null; max?: number | null }
+    | { type: "rating"; min?: number | null; max?: number | null }
+    | { type: "favorite" }
+    | { type: "colorLabel"; labels: ColorLabel[] }
+    | { type: "flag"; flag: PickFlag }
+    | { type: "location"; latitude: number; longitude: number; radiusKm: number }
+    | { type: "hasLocation" }
+    | { type: "place"; value: string }
+    | { type: "notInAnyAlbum" }
+    | { type: "inAlbum"; albumId: UUID }
+    | { type: "albumName"; name: string }
+    | { type: "folder"; value: string }
+    | {
+          type: "compare";
+          field: "iso" | "aperture" | "focalLength" | "rating" | "size" | "width" | "height";
+          op: "eq" | "gt" | "gte" | "lt" | "lte";
+          value: number;
+      }
+    | { type: "text"; query: string }
+    | { type: "nameContains"; value: string };
+
+/** 查询语法错误，start/end 为输入文本中的位置（UTF-16） */
+export interface QueryParseError {
+    message: string;
+    start: number;
+    end: number;
+}
+
+export interface SavedSearchRecord {
+    id: UUID;
+    name: string;
+    query: string;
+    position: number;
+    createdAt: Timestamp;
+    updatedAt: Timestamp;
+}
+
+export interface SmartAlbumRecord {
+    id: UUID;
+    name: string;
+    rule: SmartRule;
+    position: number;
+    mediaCount: number;
     createdAt: Timestamp;
     updatedAt: Timestamp;
 }
@@ -64,6 +564,13 @@ export interface AlbumRecord {
 export interface TagRecord {
     id: UUID;
     name: string;
+    parentId?: UUID | null;
+    path: string;
     createdAt: Timestamp;
     updatedAt: Timestamp;
+}
+
+export interface TagWithCount extends TagRecord {
+    mediaCount: number;
+    totalCount: number;
 }
\ No newline at end of file
@@ -6,6 +6,8 @@ export type TauriEventName =
     | "import-complete"
     | "thumbnail-generated"
     | "faces-detected"
+    | "face-detection-progress"
+    | "people-updated"
     | "media-updated"
     | "album-updated"
     | "tag-updated"
@@ -46,11 +48,20 @@ export interface FacesDetectedEvent {
     faces: FaceRecord[];
 }
 
+export interface FaceDetectionProgressEvent {
+    current: number;
+    total: number;
+    currentFile?: string;
+    step: "detecting" | "completed" | "cancelled";
+}
+
 export type TauriEventPayloadMap = {
     "import-progress": ImportProgressEvent;
     "import-complete": ImportCompleteEvent;
     "thumbnail-generated": ThumbnailGeneratedEvent;
     "faces-detected": FacesDetectedEvent;
+    "face-detection-progress": FaceDetectionProgressEvent;
+    "people-updated": null;
     "media-updated": MediaRecord;
     "album-updated": AlbumRecord;
     "tag-updated": TagRecord;